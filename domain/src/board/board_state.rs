@@ -48,6 +48,22 @@ impl Board {
             })
     }
 
+    pub fn legal_moves(&self, player: &Player) -> Vec<Move> {
+        self.piece_positions_for_player(player)
+            .iter()
+            .filter_map(|pos| {
+                self[pos].map(|piece| {
+                    piece
+                        .get_moves(piece.piece_color, piece.has_moved, *pos, self)
+                        .into_iter()
+                        .filter(|m| m.is_legal(self))
+                        .collect::<Vec<Move>>()
+                })
+            })
+            .flatten()
+            .collect()
+    }
+
     pub fn piece_positions_for_player(&self, player: &Player) -> Vec<Position> {
         self.piece_positions()
             .into_iter()
@@ -95,9 +111,43 @@ impl Board {
         self.en_passant_square.black = None;
         self.en_passant_square.white = None;
     }
+
+    pub fn has_short_castling_rights(&self, color: Color) -> bool {
+        let row = Self::back_rank(color);
+        self.are_king_and_rook_unmoved(color, Position::new(row, 4), Position::new(row, 7))
+    }
+
+    pub fn has_long_castling_rights(&self, color: Color) -> bool {
+        let row = Self::back_rank(color);
+        self.are_king_and_rook_unmoved(color, Position::new(row, 4), Position::new(row, 0))
+    }
 }
 
 impl Board {
+    fn back_rank(color: Color) -> i8 {
+        match color {
+            Color::White => 7,
+            Color::Black => 0,
+        }
+    }
+
+    fn are_king_and_rook_unmoved(
+        &self,
+        color: Color,
+        king_pos: Position,
+        rook_pos: Position,
+    ) -> bool {
+        let is_unmoved = |pos: &Position, kind: PieceKind| {
+            self[pos].is_some_and(|piece| {
+                piece.piece_color == color
+                    && PieceKind::from(&piece.piece_type) == kind
+                    && !piece.has_moved
+            })
+        };
+
+        is_unmoved(&king_pos, PieceKind::King) && is_unmoved(&rook_pos, PieceKind::Rook)
+    }
+
     fn init_starting_position() -> Self {
         let builder = BoardBuilder::new();
         builder
@@ -1,4 +1,7 @@
-use std::ops::{Add, AddAssign};
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign},
+};
 
 use derive_new::new;
 use serde::{Deserialize, Serialize};
@@ -14,6 +17,18 @@ pub struct Position {
 }
 
 impl Position {
+    pub fn from_algebraic(square: &str) -> Option<Self> {
+        let mut chars = square.chars();
+        let file = chars.next()?;
+        let rank = chars.next()?.to_digit(10)?;
+
+        if chars.next().is_some() || !('a'..='h').contains(&file) || !(1..=8).contains(&rank) {
+            return None;
+        }
+
+        Some(Self::new(8 - rank as i8, file as i8 - b'a' as i8))
+    }
+
     pub fn square_color(&self) -> Color {
         if self.row + self.column % 2 == 0 {
             return Color::White;
@@ -22,6 +37,12 @@ impl Position {
     }
 }
 
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", (b'a' + self.column as u8) as char, 8 - self.row)
+    }
+}
+
 impl Add<Direction> for Position {
     type Output = Self;
 
@@ -73,3 +94,32 @@ impl From<(usize, usize)> for Position {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algebraic_round_trip() {
+        let e4 = Position::from((4, 4));
+        let a8 = Position::from((0, 0));
+        let h1 = Position::from((7, 7));
+
+        assert_eq!(e4.to_string(), "e4");
+        assert_eq!(a8.to_string(), "a8");
+        assert_eq!(h1.to_string(), "h1");
+
+        assert_eq!(Position::from_algebraic("e4"), Some(e4));
+        assert_eq!(Position::from_algebraic("a8"), Some(a8));
+        assert_eq!(Position::from_algebraic("h1"), Some(h1));
+    }
+
+    #[test]
+    fn test_from_algebraic_rejects_invalid_squares() {
+        assert_eq!(Position::from_algebraic("i1"), None);
+        assert_eq!(Position::from_algebraic("a9"), None);
+        assert_eq!(Position::from_algebraic("a0"), None);
+        assert_eq!(Position::from_algebraic("e44"), None);
+        assert_eq!(Position::from_algebraic(""), None);
+    }
+}
@@ -1,13 +1,39 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    board::{Board, Direction, Position},
+    board::{Board, BoardBuilder, Direction, Position},
     moves::{Move, MoveType},
     pieces::{Piece, PieceKind, PieceType},
 };
 
 use super::{Color, Player};
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenError {
+    InvalidPlacement(String),
+    InvalidSideToMove(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidCounter(String),
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// The fields of a FEN record, parsed into the pieces `GameState` is built from.
+pub(super) struct ParsedFen {
+    pub board: Board,
+    pub player: Player,
+    pub halfmove_clock: u8,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FenString(String);
 
@@ -24,12 +50,191 @@ impl FenString {
         Self(inner)
     }
 
-    pub(super) fn inner(self) -> String {
-        self.0
+    pub(super) fn as_str(&self) -> &str {
+        &self.0
     }
 }
 
 impl FenString {
+    /// Parses the six FEN fields. The move counters may be left out and default to `0 1`.
+    /// Kings and rooks without castling rights, and pawns off their starting rank, are
+    /// marked as moved so that move generation matches the record.
+    pub(super) fn parse(fen: &str) -> Result<ParsedFen, FenError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 4 || fields.len() > 6 {
+            return Err(FenError::InvalidPlacement(format!(
+                "Expected 4 to 6 FEN fields: {}",
+                fen
+            )));
+        }
+
+        let mut board = Self::parse_placement(fields[0])?;
+        let player = match fields[1] {
+            "w" => Player::new(Color::White),
+            "b" => Player::new(Color::Black),
+            other => {
+                return Err(FenError::InvalidSideToMove(format!(
+                    "Invalid side to move: {}",
+                    other
+                )))
+            }
+        };
+        Self::apply_castling(&mut board, fields[2])?;
+        Self::apply_en_passant(&mut board, fields[3], player.color)?;
+
+        let halfmove_clock = match fields.get(4) {
+            Some(field) => field.parse().map_err(|_| {
+                FenError::InvalidCounter(format!("Invalid halfmove clock: {}", field))
+            })?,
+            None => 0,
+        };
+        if let Some(field) = fields.get(5) {
+            field.parse::<u32>().map_err(|_| {
+                FenError::InvalidCounter(format!("Invalid fullmove number: {}", field))
+            })?;
+        }
+
+        Ok(ParsedFen {
+            board,
+            player,
+            halfmove_clock,
+        })
+    }
+
+    /// The fullmove number of a FEN record, 1 when it is left out.
+    pub(super) fn fullmove_number(fen: &str) -> usize {
+        fen.split_whitespace()
+            .nth(5)
+            .and_then(|field| field.parse().ok())
+            .unwrap_or(1)
+    }
+}
+
+impl FenString {
+    fn parse_placement(placement: &str) -> Result<Board, FenError> {
+        let invalid = || FenError::InvalidPlacement(format!("Invalid placement: {}", placement));
+        let rows: Vec<&str> = placement.split('/').collect();
+        if rows.len() != 8 {
+            return Err(invalid());
+        }
+
+        let mut builder = BoardBuilder::new();
+        for (row, data) in rows.iter().enumerate() {
+            let mut column = 0;
+            for c in data.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    column += empty as i8;
+                    continue;
+                }
+
+                let kind = match c.to_ascii_lowercase() {
+                    'p' => PieceKind::Pawn,
+                    'n' => PieceKind::Knight,
+                    'b' => PieceKind::Bishop,
+                    'r' => PieceKind::Rook,
+                    'q' => PieceKind::Queen,
+                    'k' => PieceKind::King,
+                    _ => return Err(invalid()),
+                };
+                let color = match c.is_ascii_uppercase() {
+                    true => Color::White,
+                    false => Color::Black,
+                };
+                if column > 7 {
+                    return Err(invalid());
+                }
+                builder = builder.add_piece(Position::new(row as i8, column), kind, color);
+                column += 1;
+            }
+            if column != 8 {
+                return Err(invalid());
+            }
+        }
+
+        let mut board = builder.build();
+        for pos in board.piece_positions() {
+            if let Some(mut piece) = board[&pos] {
+                piece.has_moved = match PieceKind::from(&piece.piece_type) {
+                    PieceKind::Pawn => match piece.piece_color {
+                        Color::White => pos.row != 6,
+                        Color::Black => pos.row != 1,
+                    },
+                    PieceKind::King | PieceKind::Rook => true,
+                    _ => false,
+                };
+                board.set(&pos, Some(piece));
+            }
+        }
+
+        Ok(board)
+    }
+
+    fn apply_castling(board: &mut Board, castling: &str) -> Result<(), FenError> {
+        if castling == "-" {
+            return Ok(());
+        }
+
+        for c in castling.chars() {
+            let (row, rook_column, color) = match c {
+                'K' => (7, 7, Color::White),
+                'Q' => (7, 0, Color::White),
+                'k' => (0, 7, Color::Black),
+                'q' => (0, 0, Color::Black),
+                _ => {
+                    return Err(FenError::InvalidCastling(format!(
+                        "Invalid castling rights: {}",
+                        castling
+                    )))
+                }
+            };
+
+            for (column, kind) in [(4, PieceKind::King), (rook_column, PieceKind::Rook)] {
+                let pos = Position::new(row, column);
+                match board[&pos] {
+                    Some(mut piece)
+                        if piece.piece_color == color
+                            && PieceKind::from(&piece.piece_type) == kind =>
+                    {
+                        piece.has_moved = false;
+                        board.set(&pos, Some(piece));
+                    }
+                    _ => {
+                        return Err(FenError::InvalidCastling(format!(
+                            "No king and rook for castling right {}",
+                            c
+                        )))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_en_passant(board: &mut Board, square: &str, to_move: Color) -> Result<(), FenError> {
+        if square == "-" {
+            return Ok(());
+        }
+
+        let invalid =
+            || FenError::InvalidEnPassant(format!("Invalid en passant square: {}", square));
+        let target = Position::from_algebraic(square).ok_or_else(invalid)?;
+        let (expected_row, direction) = match to_move {
+            Color::White => (2, Direction::South),
+            Color::Black => (5, Direction::North),
+        };
+        if target.row != expected_row {
+            return Err(invalid());
+        }
+
+        // Replay the double step that allowed the capture
+        let moved = to_move.opponent();
+        let to = target + direction;
+        let from = Position::new(2 * target.row - to.row, target.column);
+        board.set_en_passant_square(&Move::new(MoveType::DoublePawn, from, to), &moved);
+        Ok(())
+    }
+
     fn piece_placement(board: &Board) -> String {
         let mut piece_placement = String::new();
         for (row, _) in board.fields.iter().enumerate() {
@@ -81,10 +286,10 @@ impl FenString {
 
     fn castling_rights(board: &Board) -> String {
         let mut castling_rights = String::new();
-        let can_white_short_castle = Self::can_short_castle(board, Color::White);
-        let can_white_long_castle = Self::can_long_castle(board, Color::White);
-        let can_black_short_castle = Self::can_short_castle(board, Color::Black);
-        let can_black_long_castle = Self::can_long_castle(board, Color::Black);
+        let can_white_short_castle = board.has_short_castling_rights(Color::White);
+        let can_white_long_castle = board.has_long_castling_rights(Color::White);
+        let can_black_short_castle = board.has_short_castling_rights(Color::Black);
+        let can_black_long_castle = board.has_long_castling_rights(Color::Black);
 
         if !(can_white_short_castle
             || can_white_long_castle
@@ -121,7 +326,7 @@ impl FenString {
             return en_passant;
         }
 
-        if let Some(pos) = board.get_en_passant_square(&player) {
+        if let Some(pos) = board.get_en_passant_square(&player.opponent()) {
             let rank = (8 - pos.row).to_string();
             let file = (b'a' + pos.column as u8) as char;
             en_passant.push(file);
@@ -162,48 +367,50 @@ impl FenString {
             false
         }
     }
+}
 
-    fn can_short_castle(board: &Board, player: Color) -> bool {
-        match player {
-            Color::White => Self::are_king_and_rook_unmoved(
-                board,
-                Position::from((7, 4)),
-                Position::from((7, 7)),
-            ),
-            Color::Black => Self::are_king_and_rook_unmoved(
-                board,
-                Position::from((0, 4)),
-                Position::from((0, 7)),
-            ),
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameState, San};
 
-    fn can_long_castle(board: &Board, player: Color) -> bool {
-        match player {
-            Color::White => Self::are_king_and_rook_unmoved(
-                board,
-                Position::from((7, 4)),
-                Position::from((7, 0)),
-            ),
-            Color::Black => Self::are_king_and_rook_unmoved(
-                board,
-                Position::from((0, 4)),
-                Position::from((0, 0)),
-            ),
-        }
+    #[test]
+    fn test_initial_position_round_trips() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let game_state = GameState::from_fen(start).unwrap();
+
+        assert_eq!(game_state.board, GameState::new().board);
+        assert_eq!(game_state.fen(), start);
+        assert_eq!(game_state.legal_moves().len(), 20);
     }
 
-    fn are_king_and_rook_unmoved(board: &Board, king_pos: Position, rook_pos: Position) -> bool {
-        let king = match board[&king_pos] {
-            None => return false,
-            Some(king) => king,
-        };
+    #[test]
+    fn test_rights_counters_and_en_passant() {
+        let fen = "r3k2r/8/8/3pP3/8/8/8/R3K2R w Kq d6 4 21";
+        let mut game_state = GameState::from_fen(fen).unwrap();
 
-        let rook = match board[&rook_pos] {
-            None => return false,
-            Some(rook) => rook,
-        };
+        assert_eq!(game_state.fen(), fen);
+        assert_eq!(game_state.fullmove_number(), 21);
+        assert_eq!(game_state.non_capture_or_pawn_move_counter, 4);
+        assert!(San::parse(&game_state, "O-O").is_some());
+        assert!(San::parse(&game_state, "O-O-O").is_none());
 
-        !king.has_moved && !rook.has_moved
+        let m = San::parse(&game_state, "exd6").unwrap();
+        game_state.make_move(m);
+        assert_eq!(game_state.fen(), "r3k2r/8/3P4/8/8/8/8/R3K2R b Kq - 0 21");
+    }
+
+    #[test]
+    fn test_rejects_malformed_records() {
+        let invalid = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+            "rnbqkbn1/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e4 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+        ];
+        for fen in invalid {
+            assert!(GameState::from_fen(fen).is_err(), "{}", fen);
+        }
     }
 }
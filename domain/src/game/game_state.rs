@@ -7,7 +7,7 @@ use crate::{
     pieces::{Piece, PieceKind},
 };
 
-use super::{FenString, InsufficientMaterial, PieceCounter, Player};
+use super::{Color, FenError, FenString, InsufficientMaterial, PieceCounter, Player};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameState {
@@ -17,6 +17,10 @@ pub struct GameState {
     pub state_history: HashMap<FenString, u16>,
     pub non_capture_or_pawn_move_counter: u8,
     pub result: Option<GameResult>,
+    /// The FEN record the game was set up from, when it did not start from the initial
+    /// position.
+    #[serde(default)]
+    pub setup: Option<String>,
     fen_string: FenString,
 }
 
@@ -28,8 +32,11 @@ impl Default for GameState {
 
 impl GameState {
     pub fn new() -> Self {
-        let board = Board::new();
-        let current_player = Player::default();
+        Self::from_board(Board::new(), Player::default())
+    }
+
+    /// A game starting from an arbitrary position, e.g. a composed problem.
+    pub fn from_board(board: Board, current_player: Player) -> Self {
         let fen_string = FenString::derive(&board, &current_player);
         let state_history = HashMap::from_iter([(fen_string.clone(), 1)]);
         Self {
@@ -39,10 +46,30 @@ impl GameState {
             state_history,
             non_capture_or_pawn_move_counter: 0,
             result: None,
+            setup: None,
             fen_string,
         }
     }
 
+    /// A game set up from a FEN record, e.g. an opening from a test suite.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let parsed = FenString::parse(fen)?;
+        let mut game_state = Self::from_board(parsed.board, parsed.player);
+        game_state.non_capture_or_pawn_move_counter = parsed.halfmove_clock;
+        game_state.setup = Some(fen.split_whitespace().collect::<Vec<_>>().join(" "));
+        Ok(game_state)
+    }
+
+    /// The current position as a full FEN record, move counters included.
+    pub fn fen(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.fen_string.as_str(),
+            self.non_capture_or_pawn_move_counter,
+            self.fullmove_number()
+        )
+    }
+
     pub fn legal_moves_for_piece(&self, from: Position) -> Option<(Piece, Vec<Move>)> {
         match self.board[&from] {
             None => None,
@@ -63,6 +90,10 @@ impl GameState {
         }
     }
 
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.board.legal_moves(&self.current_player)
+    }
+
     pub fn make_move(&mut self, m: Move) {
         let move_record = m.execute(&mut self.board);
         if move_record.piece_captured.is_some() || move_record.piece_moved == PieceKind::Pawn {
@@ -77,29 +108,41 @@ impl GameState {
         self.check_for_game_over();
     }
 
+    /// The position with the turn passed to the opponent, as after a null move. `None`
+    /// while the side to move is in check, as passing would leave the king en prise.
+    pub fn null_move(&self) -> Option<GameState> {
+        if self.board.is_in_check(self.current_player) {
+            return None;
+        }
+
+        let mut board = self.board.clone();
+        board.clear_en_passant_squares();
+        Some(Self::from_board(board, self.current_player.opponent()))
+    }
+
     pub fn resign(&mut self) {
-        self.result = Some(GameResult::win(self.current_player.opponent()));
+        self.result = Some(GameResult::resignation(self.current_player.opponent()));
     }
 
-    pub fn is_game_over(&self) -> bool {
-        self.result.is_some()
+    /// Ends the game as lost by the side to move, whose clock ran out.
+    pub fn lose_on_time(&mut self) {
+        self.result = Some(GameResult::timeout(self.current_player.opponent()));
     }
 
-    fn all_legal_moves_for_player(&self, player: &Player) -> Vec<Move> {
-        self.board
-            .piece_positions_for_player(player)
-            .iter()
-            .filter_map(|pos| {
-                self.board[pos].map(|piece| {
-                    piece
-                        .get_moves(piece.piece_color, piece.has_moved, *pos, &self.board)
-                        .into_iter()
-                        .filter(|m| m.is_legal(&self.board))
-                        .collect::<Vec<Move>>()
-                })
-            })
-            .flatten()
-            .collect()
+    /// The number of the move about to be played, as written in notation.
+    pub fn fullmove_number(&self) -> usize {
+        let first_move = self.setup.as_deref().map_or(1, FenString::fullmove_number);
+        let plies = self.move_history.len();
+        // A game set up with black to move starts with black's half of move 1
+        let plies = match (self.current_player.color, plies % 2) {
+            (Color::Black, 0) | (Color::White, 1) => plies + 1,
+            _ => plies,
+        };
+        first_move + plies / 2
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.result.is_some()
     }
 
     fn count_pieces(&self) -> PieceCounter {
@@ -123,10 +166,7 @@ impl GameState {
     }
 
     fn check_for_game_over(&mut self) {
-        if self
-            .all_legal_moves_for_player(&self.current_player)
-            .is_empty()
-        {
+        if self.legal_moves().is_empty() {
             if self.board.is_in_check(self.current_player) {
                 self.result = Some(GameResult::win(self.current_player.opponent()))
             } else {
@@ -159,7 +199,7 @@ impl GameState {
     fn threefold_repetition(&self) -> bool {
         self.state_history
             .get(&self.fen_string)
-            .map_or(false, |count| *count == 3)
+            .is_some_and(|count| *count == 3)
     }
 }
 
@@ -170,6 +210,8 @@ pub enum EndReason {
     FiftyMoveRule,
     InsufficientMaterial,
     ThreefoldRepetition,
+    Timeout,
+    Resignation,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -186,10 +228,67 @@ impl GameResult {
         }
     }
 
+    pub fn timeout(winner: Player) -> Self {
+        Self {
+            winner: Some(winner),
+            end_reason: EndReason::Timeout,
+        }
+    }
+
+    pub fn resignation(winner: Player) -> Self {
+        Self {
+            winner: Some(winner),
+            end_reason: EndReason::Resignation,
+        }
+    }
+
     pub fn draw(end_reason: EndReason) -> Self {
         Self {
             winner: None,
             end_reason,
         }
     }
+
+    /// The score as written in PGN: `1-0`, `0-1` or `1/2-1/2`.
+    pub fn score(&self) -> &'static str {
+        match self.winner.map(|winner| winner.color) {
+            Some(Color::White) => "1-0",
+            Some(Color::Black) => "0-1",
+            None => "1/2-1/2",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_move_passes_the_turn() {
+        let game_state =
+            GameState::from_fen("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3")
+                .unwrap();
+        assert!(game_state
+            .board
+            .get_en_passant_square(&Color::Black)
+            .is_some());
+        let passed = game_state.null_move().unwrap();
+
+        assert_eq!(passed.current_player.color, Color::Black);
+        assert_eq!(passed.board.get_en_passant_square(&Color::Black), None);
+
+        let in_check = GameState::from_fen("4k3/8/8/8/8/8/8/R3K2r w - - 0 1").unwrap();
+        assert!(in_check.null_move().is_none());
+    }
+
+    #[test]
+    fn test_score_of_results() {
+        let white = Player {
+            color: Color::White,
+        };
+        assert_eq!(GameResult::win(white).score(), "1-0");
+        assert_eq!(GameResult::timeout(white.opponent()).score(), "0-1");
+        assert_eq!(GameResult::draw(EndReason::Stalemate).score(), "1/2-1/2");
+        assert_eq!(GameResult::resignation(white).score(), "1-0");
+    }
 }
@@ -1,11 +1,16 @@
 pub(super) mod fen;
 pub mod game_state;
 pub(super) mod insufficient_material;
+pub mod pgn;
 pub(super) mod piece_counter;
 pub mod player;
+pub mod san;
 
+pub use fen::FenError;
 use fen::*;
 pub use game_state::*;
 use insufficient_material::*;
+pub use pgn::*;
 use piece_counter::*;
 pub use player::*;
+pub use san::*;
@@ -0,0 +1,420 @@
+use std::fmt::Display;
+
+use crate::moves::Move;
+
+use super::{Color, GameState, San};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgnError {
+    UnsupportedSetup(String),
+    InvalidSetup(String),
+    IllegalMove(String),
+}
+
+impl Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// A single game from a PGN file: its tag pairs, its mainline moves in SAN and its result.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pgn {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: Option<String>,
+    /// Annotations of the mainline moves, by ply. Moves past the end have none.
+    pub annotations: Vec<Annotation>,
+}
+
+/// Numeric annotation glyphs and a comment following a move.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Annotation {
+    pub nags: Vec<u8>,
+    pub comment: Option<String>,
+}
+
+impl Annotation {
+    pub const GOOD_MOVE: u8 = 1;
+    pub const MISTAKE: u8 = 2;
+    pub const BRILLIANT_MOVE: u8 = 3;
+    pub const BLUNDER: u8 = 4;
+    pub const INTERESTING_MOVE: u8 = 5;
+    pub const DUBIOUS_MOVE: u8 = 6;
+
+    pub fn is_empty(&self) -> bool {
+        self.nags.is_empty() && self.comment.is_none()
+    }
+}
+
+impl Pgn {
+    /// Parses every game in `text`. Comments, variations, NAGs and move numbers are skipped.
+    pub fn parse_all(text: &str) -> Vec<Self> {
+        let mut games = vec![];
+        let mut current = Self::default();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '[' => {
+                    if !current.moves.is_empty() || current.result.is_some() {
+                        games.push(std::mem::take(&mut current));
+                    }
+                    let tag: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                    if let Some(pair) = Self::parse_tag(&tag) {
+                        current.tags.push(pair);
+                    }
+                }
+                '{' => {
+                    let comment: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                    current.push_comment(comment.split_whitespace().collect::<Vec<_>>().join(" "));
+                }
+                ';' => {
+                    chars.by_ref().take_while(|c| *c != '\n').for_each(drop);
+                }
+                '(' => {
+                    let mut depth = 1;
+                    for c in chars.by_ref() {
+                        match c {
+                            '(' => depth += 1,
+                            ')' => depth -= 1,
+                            _ => {}
+                        }
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                }
+                c if c.is_whitespace() => {}
+                c => {
+                    let mut token = c.to_string();
+                    while let Some(next) = chars.peek() {
+                        if next.is_whitespace() || "[{;(".contains(*next) {
+                            break;
+                        }
+                        token.push(chars.next().unwrap_or_default());
+                    }
+                    current.push_token(&token);
+                }
+            }
+        }
+
+        if !current.moves.is_empty() || current.result.is_some() || !current.tags.is_empty() {
+            games.push(current);
+        }
+
+        games
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The position the game starts from: the `FEN` tag when there is one, the initial
+    /// position otherwise.
+    pub fn start_position(&self) -> Result<GameState, PgnError> {
+        match self.tag("FEN") {
+            Some(fen) => GameState::from_fen(fen)
+                .map_err(|e| PgnError::InvalidSetup(format!("Invalid FEN tag: {}", e))),
+            None => Ok(GameState::new()),
+        }
+    }
+
+    /// Replays the mainline from the starting position and returns the moves played.
+    pub fn mainline(&self) -> Result<Vec<Move>, PgnError> {
+        let mut game_state = self.start_position()?;
+        let mut moves = Vec::with_capacity(self.moves.len());
+
+        for san in &self.moves {
+            let m = San::parse(&game_state, san)
+                .ok_or_else(|| PgnError::IllegalMove(format!("Illegal move: {}", san)))?;
+            moves.push(m.clone());
+            game_state.make_move(m);
+        }
+
+        Ok(moves)
+    }
+
+    /// The annotation of the move at `ply`, if it has one.
+    pub fn annotation(&self, ply: usize) -> Option<&Annotation> {
+        self.annotations
+            .get(ply)
+            .filter(|annotation| !annotation.is_empty())
+    }
+
+    pub fn annotation_mut(&mut self, ply: usize) -> &mut Annotation {
+        if self.annotations.len() <= ply {
+            self.annotations.resize(ply + 1, Annotation::default());
+        }
+        &mut self.annotations[ply]
+    }
+}
+
+impl Pgn {
+    /// Records the moves of `game_state` after the given tags. `SetUp` and `FEN` tags are
+    /// added for games that did not start from the initial position, and the `Result` tag
+    /// is set to `result`, or added when missing.
+    pub fn from_game(
+        game_state: &GameState,
+        tags: Vec<(String, String)>,
+        result: Option<&str>,
+    ) -> Self {
+        let mut start = match &game_state.setup {
+            Some(fen) => GameState::from_fen(fen).unwrap_or_default(),
+            None => GameState::new(),
+        };
+        let mut moves = Vec::with_capacity(game_state.move_history.len());
+        for record in &game_state.move_history {
+            moves.push(San::derive(&start, &record.mv).to_string());
+            start.make_move(record.mv.clone());
+        }
+
+        let result = result.unwrap_or("*").to_string();
+        let mut tags = tags;
+        match tags.iter_mut().find(|(name, _)| name == "Result") {
+            Some((_, value)) => *value = result.clone(),
+            None => tags.push(("Result".to_string(), result.clone())),
+        }
+        if let Some(fen) = &game_state.setup {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), fen.clone()));
+        }
+
+        Self {
+            tags,
+            moves,
+            result: Some(result),
+            annotations: vec![],
+        }
+    }
+}
+
+impl Display for Pgn {
+    /// Export format: tag pairs, a blank line and the movetext wrapped at 80 columns.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.tags {
+            writeln!(f, "[{} \"{}\"]", name, value.replace('"', "\\\""))?;
+        }
+        writeln!(f)?;
+
+        let (first_move, black_starts) = match self.start_position() {
+            Ok(start) => (
+                start.fullmove_number(),
+                start.current_player.color == Color::Black,
+            ),
+            Err(_) => (1, false),
+        };
+        let mut tokens = vec![];
+        let mut after_comment = false;
+        for (i, san) in self.moves.iter().enumerate() {
+            let move_number = first_move + (i + usize::from(black_starts)) / 2;
+            // Black's move is numbered again when a comment interrupts the move pair
+            match ((i % 2 == 0) != black_starts, i == 0 || after_comment) {
+                (true, _) => tokens.push(format!("{}.", move_number)),
+                (false, true) => tokens.push(format!("{}...", move_number)),
+                (false, false) => {}
+            }
+            tokens.push(san.clone());
+
+            let annotation = self.annotation(i);
+            for nag in annotation.iter().flat_map(|annotation| &annotation.nags) {
+                tokens.push(format!("${}", nag));
+            }
+            let comment = annotation.and_then(|annotation| annotation.comment.as_deref());
+            after_comment = comment.is_some();
+            if let Some(comment) = comment {
+                let words: Vec<&str> = comment.split_whitespace().collect();
+                let last = words.len().saturating_sub(1);
+                tokens.extend(words.iter().enumerate().map(|(j, word)| {
+                    match (j == 0, j == last) {
+                        (true, true) => format!("{{{}}}", word),
+                        (true, false) => format!("{{{}", word),
+                        (false, true) => format!("{}}}", word),
+                        (false, false) => word.to_string(),
+                    }
+                }));
+            }
+        }
+        tokens.push(self.result.clone().unwrap_or_else(|| "*".to_string()));
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length > 0 && line_length + 1 + token.len() > 80 {
+                writeln!(f)?;
+                line_length = 0;
+            }
+            if line_length > 0 {
+                write!(f, " ")?;
+                line_length += 1;
+            }
+            write!(f, "{}", token)?;
+            line_length += token.len();
+        }
+        writeln!(f)
+    }
+}
+
+impl Pgn {
+    const RESULTS: [&'static str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+    fn parse_tag(tag: &str) -> Option<(String, String)> {
+        let (name, value) = tag.trim().split_once(char::is_whitespace)?;
+        let value = value.trim().trim_matches('"').replace("\\\"", "\"");
+        Some((name.to_string(), value))
+    }
+
+    fn push_token(&mut self, token: &str) {
+        if Self::RESULTS.contains(&token) {
+            self.result = Some(token.to_string());
+            return;
+        }
+
+        if let Some(nag) = token.strip_prefix('$') {
+            if let Some(annotation) = self.last_annotation() {
+                annotation.nags.extend(nag.parse::<u8>().ok());
+            }
+            return;
+        }
+
+        let san = match token.rfind('.') {
+            Some(i) => &token[i + 1..],
+            None => token,
+        };
+        if !san.is_empty() {
+            self.moves.push(san.to_string());
+        }
+    }
+
+    /// Comments before the first move are dropped.
+    fn push_comment(&mut self, comment: String) {
+        if comment.is_empty() {
+            return;
+        }
+        if let Some(annotation) = self.last_annotation() {
+            annotation.comment = Some(match annotation.comment.take() {
+                Some(previous) => format!("{} {}", previous, comment),
+                None => comment,
+            });
+        }
+    }
+
+    fn last_annotation(&mut self) -> Option<&mut Annotation> {
+        let ply = self.moves.len().checked_sub(1)?;
+        Some(self.annotation_mut(ply))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: &str = r#"
+[Event "Casual game"]
+[White "Anderssen, Adolf"]
+[Black "Kieseritzky, Lionel"]
+[Result "1-0"]
+
+1. e4 e5 2. f4 exf4 3. Bc4 Qh4+ {The famous Immortal Game} 4. Kf1 b5 5. Bxb5
+(5. Bb3 Nf6) Nf6 $1 6. Nf3 1-0
+
+[Event "Second"]
+[Result "1/2-1/2"]
+
+1.d4 d5 2.c4 c6 ; Slav
+3.Nf3 1/2-1/2
+"#;
+
+    #[test]
+    fn test_parse_all_games() {
+        let games = Pgn::parse_all(GAMES);
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("White"), Some("Anderssen, Adolf"));
+        assert_eq!(
+            games[0].moves,
+            vec!["e4", "e5", "f4", "exf4", "Bc4", "Qh4+", "Kf1", "b5", "Bxb5", "Nf6", "Nf3"]
+        );
+        assert_eq!(games[0].result.as_deref(), Some("1-0"));
+        assert_eq!(games[1].moves, vec!["d4", "d5", "c4", "c6", "Nf3"]);
+        assert_eq!(games[1].result.as_deref(), Some("1/2-1/2"));
+    }
+
+    #[test]
+    fn test_mainline_replays_moves() {
+        let games = Pgn::parse_all(GAMES);
+
+        assert_eq!(games[0].mainline().unwrap().len(), 11);
+
+        let broken = Pgn {
+            moves: vec!["e4".to_string(), "e4".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(broken.mainline(), Err(PgnError::IllegalMove(_))));
+    }
+
+    #[test]
+    fn test_mainline_from_fen_tag() {
+        let text = r#"
+[SetUp "1"]
+[FEN "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"]
+
+3. Bb5 a6 4. Ba4 *
+"#;
+        let games = Pgn::parse_all(text);
+        assert_eq!(games[0].mainline().unwrap().len(), 3);
+
+        let broken = Pgn {
+            tags: vec![("FEN".to_string(), "8/8/8 w - -".to_string())],
+            ..Default::default()
+        };
+        assert!(matches!(broken.mainline(), Err(PgnError::InvalidSetup(_))));
+    }
+
+    #[test]
+    fn test_annotations_round_trip() {
+        let text = "{Game comment} 1. e4 $1 {Best by test} e5 2. Qh5 $6 $2 Nc6 *";
+        let mut pgn = Pgn::parse_all(text).remove(0);
+
+        assert_eq!(pgn.moves, vec!["e4", "e5", "Qh5", "Nc6"]);
+        assert_eq!(
+            pgn.annotation(0),
+            Some(&Annotation {
+                nags: vec![Annotation::GOOD_MOVE],
+                comment: Some("Best by test".to_string()),
+            })
+        );
+        assert_eq!(pgn.annotation(1), None);
+        assert_eq!(pgn.annotation(2).unwrap().nags, vec![6, 2]);
+
+        pgn.annotation_mut(3).nags.push(Annotation::BLUNDER);
+        let exported = pgn.to_string();
+        assert!(exported.contains("1. e4 $1 {Best by test} 1... e5 2. Qh5 $6 $2 Nc6 $4 *"));
+        assert_eq!(Pgn::parse_all(&exported).remove(0), pgn);
+    }
+
+    #[test]
+    fn test_exported_game_parses_back() {
+        let fen = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2";
+        let mut game_state = GameState::from_fen(fen).unwrap();
+        for san in ["Nf3", "Nc6", "Bb5"] {
+            let m = San::parse(&game_state, san).unwrap();
+            game_state.make_move(m);
+        }
+
+        let tags = vec![("White".to_string(), "A \"quoted\" name".to_string())];
+        let pgn = Pgn::from_game(&game_state, tags, Some("1/2-1/2"));
+        let text = pgn.to_string();
+        assert!(text.contains("2. Nf3 Nc6 3. Bb5 1/2-1/2"));
+
+        let parsed = &Pgn::parse_all(&text)[0];
+        assert_eq!(parsed.tag("White"), Some("A \"quoted\" name"));
+        assert_eq!(parsed.tag("FEN"), Some(fen));
+        assert_eq!(parsed.moves, vec!["Nf3", "Nc6", "Bb5"]);
+        assert_eq!(parsed.mainline().unwrap().len(), 3);
+    }
+}
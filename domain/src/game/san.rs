@@ -0,0 +1,258 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::Position,
+    moves::{Move, MoveType},
+    pieces::{PieceKind, PromotionPiece},
+};
+
+use super::GameState;
+
+/// A move in Standard Algebraic Notation, e.g. `Nbd7`, `exd5`, `e8=Q+` or `O-O-O`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct San(String);
+
+impl San {
+    /// Renders `m` relative to `game_state`, which must be the position the move is played from.
+    pub fn derive(game_state: &GameState, m: &Move) -> Self {
+        let board = &game_state.board;
+        let piece_moved = board[&m.from].map(|piece| PieceKind::from(&piece.piece_type));
+
+        let mut inner = match (&m.move_type, piece_moved) {
+            (MoveType::ShortCastle, _) => "O-O".to_string(),
+            (MoveType::LongCastle, _) => "O-O-O".to_string(),
+            (_, Some(PieceKind::Pawn)) => Self::pawn_move(game_state, m),
+            (_, Some(kind)) => Self::piece_move(game_state, m, kind),
+            (_, None) => format!("{}{}", m.from, m.to),
+        };
+
+        let mut board_after = board.clone();
+        m.execute(&mut board_after);
+        let opponent = game_state.current_player.opponent();
+
+        if board_after.is_in_check(opponent) {
+            match board_after.legal_moves(&opponent).is_empty() {
+                true => inner.push('#'),
+                false => inner.push('+'),
+            }
+        }
+
+        Self(inner)
+    }
+
+    /// Resolves `san` against the legal moves of `game_state`. Check and annotation suffixes
+    /// are ignored, and castling may be written with zeros or capital letters.
+    pub fn parse(game_state: &GameState, san: &str) -> Option<Move> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+        let legal_moves = game_state.legal_moves();
+
+        match san {
+            "O-O" | "0-0" => {
+                return legal_moves
+                    .into_iter()
+                    .find(|m| m.move_type == MoveType::ShortCastle)
+            }
+            "O-O-O" | "0-0-0" => {
+                return legal_moves
+                    .into_iter()
+                    .find(|m| m.move_type == MoveType::LongCastle)
+            }
+            _ => {}
+        }
+
+        let (san, promotion) = Self::split_promotion(san)?;
+        let (kind, rest) = match san.chars().next()? {
+            'N' => (PieceKind::Knight, &san[1..]),
+            'B' => (PieceKind::Bishop, &san[1..]),
+            'R' => (PieceKind::Rook, &san[1..]),
+            'Q' => (PieceKind::Queen, &san[1..]),
+            'K' => (PieceKind::King, &san[1..]),
+            _ => (PieceKind::Pawn, san),
+        };
+
+        if rest.len() < 2 || !rest.is_ascii() {
+            return None;
+        }
+
+        let to = Position::from_algebraic(&rest[rest.len() - 2..])?;
+        let hint = rest[..rest.len() - 2].replace('x', "");
+        let (file_hint, rank_hint) = Self::disambiguation_hints(&hint)?;
+
+        let mut candidates = legal_moves.into_iter().filter(|m| {
+            m.to == to
+                && game_state.board[&m.from]
+                    .is_some_and(|piece| PieceKind::from(&piece.piece_type) == kind)
+                && file_hint.is_none_or(|column| m.from.column == column)
+                && rank_hint.is_none_or(|row| m.from.row == row)
+        });
+
+        let mut m = candidates.next()?;
+        if candidates.next().is_some() {
+            return None;
+        }
+
+        match (&m.move_type, promotion) {
+            (MoveType::Promotion(_), Some(piece)) => m.move_type = MoveType::Promotion(piece),
+            (MoveType::Promotion(_), None) | (_, Some(_)) => return None,
+            _ => {}
+        }
+
+        Some(m)
+    }
+
+    pub fn inner(self) -> String {
+        self.0
+    }
+}
+
+impl San {
+    fn pawn_move(game_state: &GameState, m: &Move) -> String {
+        let is_capture = game_state.board[&m.to].is_some() || m.move_type == MoveType::EnPassant;
+        let mut san = match is_capture {
+            true => format!("{}x{}", Self::file(&m.from), m.to),
+            false => m.to.to_string(),
+        };
+
+        if let MoveType::Promotion(piece) = &m.move_type {
+            san.push_str(&format!("={}", piece));
+        }
+
+        san
+    }
+
+    fn piece_move(game_state: &GameState, m: &Move, kind: PieceKind) -> String {
+        let rivals: Vec<Position> = game_state
+            .legal_moves()
+            .into_iter()
+            .filter(|other| {
+                other.to == m.to
+                    && other.from != m.from
+                    && game_state.board[&other.from]
+                        .is_some_and(|piece| PieceKind::from(&piece.piece_type) == kind)
+            })
+            .map(|other| other.from)
+            .collect();
+
+        let disambiguation = if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|pos| pos.column != m.from.column) {
+            Self::file(&m.from).to_string()
+        } else if rivals.iter().all(|pos| pos.row != m.from.row) {
+            (8 - m.from.row).to_string()
+        } else {
+            m.from.to_string()
+        };
+
+        format!(
+            "{}{}{}{}",
+            kind.to_string().to_uppercase(),
+            disambiguation,
+            if game_state.board[&m.to].is_some() {
+                "x"
+            } else {
+                ""
+            },
+            m.to
+        )
+    }
+
+    fn file(pos: &Position) -> char {
+        (b'a' + pos.column as u8) as char
+    }
+
+    fn split_promotion(san: &str) -> Option<(&str, Option<PromotionPiece>)> {
+        let (san, piece) = match san.split_once('=') {
+            Some((san, piece)) => (san, Some(piece)),
+            None => match san.char_indices().last() {
+                Some((i, c)) if "QRBN".contains(c) && i > 0 => (&san[..i], Some(&san[i..])),
+                _ => (san, None),
+            },
+        };
+
+        let promotion = match piece {
+            None => None,
+            Some("Q") => Some(PromotionPiece::Queen),
+            Some("R") => Some(PromotionPiece::Rook),
+            Some("B") => Some(PromotionPiece::Bishop),
+            Some("N") => Some(PromotionPiece::Knight),
+            Some(_) => return None,
+        };
+
+        Some((san, promotion))
+    }
+
+    fn disambiguation_hints(hint: &str) -> Option<(Option<i8>, Option<i8>)> {
+        let mut file = None;
+        let mut rank = None;
+
+        for c in hint.chars() {
+            match c {
+                'a'..='h' if file.is_none() => file = Some(c as i8 - b'a' as i8),
+                '1'..='8' if rank.is_none() => rank = Some(8 - (c as i8 - b'0' as i8)),
+                _ => return None,
+            }
+        }
+
+        Some((file, rank))
+    }
+}
+
+impl Display for San {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game_state: &mut GameState, moves: &[&str]) {
+        for san in moves {
+            let m = San::parse(game_state, san).unwrap_or_else(|| panic!("Illegal move {}", san));
+            game_state.make_move(m);
+        }
+    }
+
+    #[test]
+    fn test_san_round_trip() {
+        let mut game_state = GameState::new();
+        let moves = [
+            "e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O", "Bg4", "h3", "Bxf3",
+            "Qxf3", "Qd7",
+        ];
+
+        for san in moves {
+            let m = San::parse(&game_state, san).unwrap();
+            assert_eq!(San::derive(&game_state, &m).to_string(), san);
+            game_state.make_move(m);
+        }
+    }
+
+    #[test]
+    fn test_disambiguation() {
+        let mut game_state = GameState::new();
+        play(&mut game_state, &["Nf3", "d5", "d4", "Nf6", "Nbd2"]);
+
+        let last = game_state.move_history.last().unwrap();
+        assert_eq!(last.mv.from, Position::from_algebraic("b1").unwrap());
+
+        let mut game_state = GameState::new();
+        play(&mut game_state, &["e4", "e5", "Nc3", "Nc6"]);
+        assert!(San::parse(&game_state, "Ne2").is_none());
+
+        let m = San::parse(&game_state, "Nge2").unwrap();
+        assert_eq!(San::derive(&game_state, &m).to_string(), "Nge2");
+    }
+
+    #[test]
+    fn test_check_and_mate_suffix() {
+        let mut game_state = GameState::new();
+        play(&mut game_state, &["f3", "e5", "g4"]);
+
+        let mate = San::parse(&game_state, "Qh4").unwrap();
+        assert_eq!(San::derive(&game_state, &mate).to_string(), "Qh4#");
+    }
+}
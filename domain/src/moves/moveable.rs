@@ -56,9 +56,8 @@ pub(crate) trait Moveable {
         self.get_moves(color, has_moved, from, board)
             .iter()
             .any(|m| {
-                board[&m.to].map_or(false, |piece| {
-                    PieceKind::from(&piece.piece_type) == PieceKind::King
-                })
+                board[&m.to]
+                    .is_some_and(|piece| PieceKind::from(&piece.piece_type) == PieceKind::King)
             })
     }
 }
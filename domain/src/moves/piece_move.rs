@@ -110,7 +110,7 @@ impl Move {
     }
 
     fn is_move_legal(&self, board: &Board) -> bool {
-        board[&self.from].map_or(false, |piece| {
+        board[&self.from].is_some_and(|piece| {
             let player = Player::new(piece.piece_color);
             let mut cloned_board = board.clone();
             self.execute(&mut cloned_board);
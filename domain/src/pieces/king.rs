@@ -6,7 +6,7 @@ use crate::{
     moves::{Move, MoveType, Moveable},
 };
 
-use super::PieceType;
+use super::{PieceType, Rook};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct King;
@@ -99,7 +99,7 @@ impl King {
             Player::new(Color::Black)
         };
 
-        self.is_rook_unmoved(&rook_position, board)
+        self.is_rook_unmoved(&rook_position, from, board)
             && self.are_castling_squares_empty(between_positions, board)
             && !board.is_in_check(player)
             && !has_moved
@@ -118,16 +118,32 @@ impl King {
             Player::new(Color::Black)
         };
 
-        self.is_rook_unmoved(&rook_position, board)
+        self.is_rook_unmoved(&rook_position, from, board)
             && self.are_castling_squares_empty(between_positions, board)
             && !board.is_in_check(player)
             && !has_moved
     }
 
-    fn is_rook_unmoved(&self, position: &Position, board: &Board) -> bool {
+    /// Only a king on the e-file castles, and only with an unmoved rook of its own colour.
+    /// Composed positions can have other pieces in the corners.
+    fn is_rook_unmoved(
+        &self,
+        position: &Position,
+        king_position: &Position,
+        board: &Board,
+    ) -> bool {
+        let Some(king) = board[king_position] else {
+            return false;
+        };
+
         match board[position] {
             None => false,
-            Some(piece) => !piece.has_moved,
+            Some(piece) => {
+                king_position.column == 4
+                    && piece.piece_type == PieceType::Rook(Rook)
+                    && piece.piece_color == king.piece_color
+                    && !piece.has_moved
+            }
         }
     }
 
@@ -310,4 +326,27 @@ mod tests {
             assert!(result_positions.contains(&pos));
         }
     }
+
+    #[test]
+    fn test_king_off_e_file_does_not_castle() {
+        // Arrange
+        let mut board = Board::default();
+        let king = King;
+        let king_position = Position::from((0, 7));
+
+        board.set(
+            &king_position,
+            Some(Piece::new(PieceType::King(King), Color::Black)),
+        );
+        board.set(
+            &Position::from((0, 0)),
+            Some(Piece::new(PieceType::Rook(Rook), Color::White)),
+        );
+
+        // Act
+        let moves = king.get_moves(Color::Black, false, king_position, &board);
+
+        // Assert
+        assert!(moves.iter().all(|m| m.move_type == MoveType::Normal));
+    }
 }
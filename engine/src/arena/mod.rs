@@ -0,0 +1,9 @@
+pub mod openings;
+pub mod player;
+pub mod runner;
+pub mod sprt;
+
+pub use openings::*;
+pub use player::*;
+pub use runner::*;
+pub use sprt::*;
@@ -0,0 +1,127 @@
+use domain::game::{GameState, Pgn};
+
+use crate::error::EngineError;
+
+/// Reads an opening suite, either EPD with one position per line or PGN whose games are
+/// played out to their last move. The format is told apart by the first non-empty line:
+/// PGN starts with a tag pair or a move number.
+pub fn parse_openings(text: &str) -> Result<Vec<GameState>, EngineError> {
+    let first_line = text.lines().map(str::trim).find(|line| !line.is_empty());
+    let openings = match first_line {
+        None => vec![],
+        Some(line) if line.starts_with('[') || line.starts_with("1.") => parse_pgn(text)?,
+        Some(_) => parse_epd(text)?,
+    };
+
+    if openings.is_empty() {
+        return Err(EngineError::InvalidOpenings(
+            "The opening suite has no positions".to_string(),
+        ));
+    }
+    Ok(openings)
+}
+
+fn parse_pgn(text: &str) -> Result<Vec<GameState>, EngineError> {
+    Pgn::parse_all(text)
+        .iter()
+        .enumerate()
+        .map(|(i, pgn)| {
+            let invalid = |e| EngineError::InvalidOpenings(format!("Game {}: {}", i + 1, e));
+            let mut game_state = pgn.start_position().map_err(invalid)?;
+            for m in pgn.mainline().map_err(invalid)? {
+                game_state.make_move(m);
+            }
+            Ok(game_state)
+        })
+        .collect()
+}
+
+fn parse_epd(text: &str) -> Result<Vec<GameState>, EngineError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            parse_epd_record(line)
+                .map_err(|e| EngineError::InvalidOpenings(format!("Line {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// EPD records are the first four FEN fields followed by operations. The `hmvc` and
+/// `fmvn` operations supply the move counters.
+pub(crate) fn parse_epd_record(line: &str) -> Result<GameState, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 4 {
+        return Err("Expected four FEN fields".to_string());
+    }
+
+    let fen = format!(
+        "{} {} {}",
+        fields[..4].join(" "),
+        epd_operation(line, "hmvc").unwrap_or_else(|| "0".to_string()),
+        epd_operation(line, "fmvn").unwrap_or_else(|| "1".to_string()),
+    );
+    GameState::from_fen(&fen).map_err(|e| e.to_string())
+}
+
+/// The operand of the EPD operation `opcode`, without quotes.
+pub(crate) fn epd_operation(line: &str, opcode: &str) -> Option<String> {
+    let fields: Vec<&str> = line.splitn(5, char::is_whitespace).collect();
+    fields.get(4)?.split(';').find_map(|operation| {
+        let (name, operand) = operation.trim().split_once(char::is_whitespace)?;
+        (name == opcode).then(|| operand.trim().trim_matches('"').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::game::Color;
+
+    use super::*;
+
+    #[test]
+    fn test_epd_suite() {
+        let text = "\
+# two openings
+rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - id \"open\"; fmvn 2;
+rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - hmvc 0; fmvn 2; c0 \"Sicilian\";
+";
+        let openings = parse_openings(text).unwrap();
+
+        assert_eq!(openings.len(), 2);
+        assert_eq!(openings[0].fullmove_number(), 2);
+        assert_eq!(
+            epd_operation(text.lines().nth(2).unwrap(), "c0").as_deref(),
+            Some("Sicilian")
+        );
+        assert_eq!(
+            openings[1].fen(),
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+        );
+    }
+
+    #[test]
+    fn test_pgn_suite() {
+        let text = "\
+[Event \"Ruy Lopez\"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bb5 *
+
+[Event \"Queen's Gambit\"]
+
+1. d4 d5 2. c4 *
+";
+        let openings = parse_openings(text).unwrap();
+
+        assert_eq!(openings.len(), 2);
+        assert_eq!(openings[0].move_history.len(), 5);
+        assert_eq!(openings[1].current_player.color, Color::Black);
+    }
+
+    #[test]
+    fn test_rejects_broken_suites() {
+        assert!(parse_openings("").is_err());
+        assert!(parse_openings("rnbqkbnr/pppppppp w KQkq\n").is_err());
+        assert!(parse_openings("1. e4 e4 *").is_err());
+    }
+}
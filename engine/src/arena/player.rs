@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use domain::{
+    game::{Color, GameState},
+    moves::Move,
+};
+
+use crate::{
+    error::EngineError,
+    search::{SearchLimits, Searcher},
+    skill::SkillLevel,
+    uci::{parse_uci_move, GoParams, UciEngine},
+};
+
+/// Longest a UCI engine may think past its own time before the game is lost.
+const UCI_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// How long a UCI engine may think when the match has no clock and no move time.
+const UCI_UNTIMED_LIMIT: Duration = Duration::from_secs(300);
+
+/// One side of a match.
+pub trait MatchPlayer: Send {
+    fn name(&self) -> &str;
+
+    fn new_game(&mut self) -> Result<(), EngineError>;
+
+    /// The move to play in `game_state`. The clocks and move time of `params` come from
+    /// the match; search limits of the player's own configuration are added to them.
+    fn best_move(&mut self, game_state: &GameState, params: &GoParams)
+        -> Result<Move, EngineError>;
+}
+
+/// How to create a match player: the internal searcher or an external UCI executable.
+///
+/// Written as `internal[:key=value,...]` or `uci:program[,key=value,...]`. `name`, `depth`
+/// and `nodes` are understood by both. The internal searcher also takes its UCI options,
+/// `Hash`, `Threads` and `EvalFile`, and a skill `level` by preset name, and every other
+/// key is sent to a UCI engine with `setoption`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineSpec {
+    pub name: String,
+    pub kind: EngineKind,
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    /// Plays at this skill level instead of full strength. Internal engines only.
+    pub level: Option<SkillLevel>,
+    pub options: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineKind {
+    Internal,
+    Uci(String),
+}
+
+impl EngineSpec {
+    pub fn parse(spec: &str) -> Result<Self, EngineError> {
+        let invalid =
+            |reason: &str| EngineError::InvalidEngineSpec(format!("{}: {}", reason, spec));
+
+        let (kind, settings) = match spec.split_once(':') {
+            Some(("internal", settings)) => (EngineKind::Internal, settings),
+            None if spec == "internal" => (EngineKind::Internal, ""),
+            Some(("uci", rest)) => {
+                let (program, settings) = rest.split_once(',').unwrap_or((rest, ""));
+                if program.is_empty() {
+                    return Err(invalid("Missing UCI program"));
+                }
+                (EngineKind::Uci(program.to_string()), settings)
+            }
+            _ => return Err(invalid("Expected internal or uci:<program>")),
+        };
+
+        let mut engine = Self {
+            name: match &kind {
+                EngineKind::Internal => "internal".to_string(),
+                EngineKind::Uci(program) => program
+                    .rsplit(['/', '\\'])
+                    .next()
+                    .unwrap_or(program)
+                    .to_string(),
+            },
+            kind,
+            depth: None,
+            nodes: None,
+            level: None,
+            options: vec![],
+        };
+
+        for setting in settings.split(',').filter(|s| !s.is_empty()) {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| invalid("Expected key=value"))?;
+            match key {
+                "name" => engine.name = value.to_string(),
+                "depth" => engine.depth = Some(value.parse().map_err(|_| invalid("Bad depth"))?),
+                "nodes" => engine.nodes = Some(value.parse().map_err(|_| invalid("Bad nodes"))?),
+                "level" if engine.kind == EngineKind::Internal => {
+                    engine.level =
+                        Some(SkillLevel::from_name(value).ok_or_else(|| invalid("Bad level"))?)
+                }
+                _ => engine.options.push((key.to_string(), value.to_string())),
+            }
+        }
+
+        Ok(engine)
+    }
+
+    /// Starts the player, spawning the engine process for UCI players.
+    pub fn create(&self) -> Result<Box<dyn MatchPlayer>, EngineError> {
+        match &self.kind {
+            EngineKind::Internal => {
+                let mut searcher = Searcher::default();
+                for (name, value) in &self.options {
+                    searcher.set_option(name, value)?;
+                }
+                Ok(Box::new(InternalPlayer {
+                    spec: self.clone(),
+                    searcher,
+                }))
+            }
+            EngineKind::Uci(program) => {
+                let mut engine = UciEngine::spawn(program, Vec::<String>::new())?;
+                for (name, value) in &self.options {
+                    engine.set_option(name, value)?;
+                }
+                engine.is_ready()?;
+                Ok(Box::new(UciPlayer {
+                    spec: self.clone(),
+                    engine,
+                }))
+            }
+        }
+    }
+}
+
+impl EngineSpec {
+    fn go_params(&self, params: &GoParams) -> GoParams {
+        GoParams {
+            depth: self.depth.or(params.depth),
+            nodes: self.nodes.or(params.nodes),
+            ..params.clone()
+        }
+    }
+}
+
+struct InternalPlayer {
+    spec: EngineSpec,
+    searcher: Searcher,
+}
+
+impl MatchPlayer for InternalPlayer {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        self.searcher.new_game();
+        Ok(())
+    }
+
+    fn best_move(
+        &mut self,
+        game_state: &GameState,
+        params: &GoParams,
+    ) -> Result<Move, EngineError> {
+        let params = self.spec.go_params(params);
+        let limits = SearchLimits::from_go(&params, game_state.current_player.color);
+        let best_move = match &self.spec.level {
+            Some(level) => level.pick_move(
+                &mut self.searcher,
+                game_state,
+                limits.clock,
+                &mut rand::thread_rng(),
+            ),
+            None => self.searcher.search(game_state, &limits).best_move,
+        };
+        best_move.ok_or_else(|| EngineError::InvalidMove("No move in a finished game".to_string()))
+    }
+}
+
+struct UciPlayer {
+    spec: EngineSpec,
+    engine: UciEngine,
+}
+
+impl MatchPlayer for UciPlayer {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        self.engine.new_game()?;
+        self.engine.is_ready()
+    }
+
+    fn best_move(
+        &mut self,
+        game_state: &GameState,
+        params: &GoParams,
+    ) -> Result<Move, EngineError> {
+        let params = self.spec.go_params(params);
+        let own_time = match game_state.current_player.color {
+            Color::White => params.wtime,
+            Color::Black => params.btime,
+        };
+        let timeout = params
+            .movetime
+            .or(own_time)
+            .map_or(UCI_UNTIMED_LIMIT, |time| time + UCI_GRACE_PERIOD);
+
+        self.engine.set_position(game_state)?;
+        self.engine.go(&params)?;
+        let best_move = self.engine.wait_for_best_move(timeout)?;
+        parse_uci_move(game_state, &best_move.best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_specs() {
+        let internal = EngineSpec::parse("internal:name=base,depth=3,Threads=2").unwrap();
+        assert_eq!(internal.kind, EngineKind::Internal);
+        assert_eq!(internal.name, "base");
+        assert_eq!(internal.depth, Some(3));
+        assert_eq!(
+            internal.options,
+            vec![("Threads".to_string(), "2".to_string())]
+        );
+
+        let uci = EngineSpec::parse("uci:/usr/bin/stockfish,Hash=64,nodes=1000").unwrap();
+        assert_eq!(uci.kind, EngineKind::Uci("/usr/bin/stockfish".to_string()));
+        assert_eq!(uci.name, "stockfish");
+        assert_eq!(uci.nodes, Some(1000));
+
+        let club = EngineSpec::parse("internal:level=club").unwrap();
+        assert_eq!(club.level, Some(SkillLevel::from_name("Club").unwrap()));
+
+        assert!(EngineSpec::parse("internal:depth=x").is_err());
+        assert!(EngineSpec::parse("internal:level=grandmaster").is_err());
+        assert!(EngineSpec::parse("internal:depth").is_err());
+        assert!(EngineSpec::parse("uci:").is_err());
+        assert!(EngineSpec::parse("stockfish").is_err());
+    }
+}
@@ -0,0 +1,359 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use domain::game::{Color, GameState, Pgn};
+
+use crate::{error::EngineError, uci::GoParams};
+
+use super::{EngineSpec, MatchPlayer, MatchScore, Sprt, SprtDecision};
+
+/// Games still running at this many plies are adjudicated as draws.
+const DEFAULT_MAX_PLIES: usize = 400;
+
+/// Time allowed to each side, either a clock with an increment or a fixed time per move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeControl {
+    pub base: Option<Duration>,
+    pub increment: Duration,
+    pub movetime: Option<Duration>,
+}
+
+impl TimeControl {
+    /// Parses `seconds[+increment]`, e.g. `10+0.1`.
+    pub fn parse(tc: &str) -> Result<Self, EngineError> {
+        let seconds = |value: &str| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                .map(Duration::from_secs_f64)
+                .ok_or_else(|| {
+                    EngineError::InvalidOptionValue(format!("Invalid time control: {}", tc))
+                })
+        };
+
+        let (base, increment) = tc.split_once('+').unwrap_or((tc, "0"));
+        Ok(Self {
+            base: Some(seconds(base)?),
+            increment: seconds(increment)?,
+            movetime: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchConfig {
+    pub engines: [EngineSpec; 2],
+    pub openings: Vec<GameState>,
+    /// Games to play. Every opening is played twice, once with each engine as white.
+    pub games: usize,
+    /// Games played at the same time.
+    pub concurrency: usize,
+    pub time_control: TimeControl,
+    pub max_plies: usize,
+    pub sprt: Option<Sprt>,
+}
+
+impl MatchConfig {
+    pub fn new(engines: [EngineSpec; 2], openings: Vec<GameState>, games: usize) -> Self {
+        Self {
+            engines,
+            openings,
+            games,
+            concurrency: 1,
+            time_control: TimeControl::default(),
+            max_plies: DEFAULT_MAX_PLIES,
+            sprt: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl Outcome {
+    pub fn result(&self) -> &'static str {
+        match self {
+            Outcome::WhiteWins => "1-0",
+            Outcome::BlackWins => "0-1",
+            Outcome::Draw => "1/2-1/2",
+        }
+    }
+
+    fn win(color: Color) -> Self {
+        match color {
+            Color::White => Outcome::WhiteWins,
+            Color::Black => Outcome::BlackWins,
+        }
+    }
+}
+
+/// A finished game of a match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// Position of the game in the match schedule, starting at 0.
+    pub index: usize,
+    pub white: String,
+    pub black: String,
+    /// Whether the first engine played white.
+    pub first_is_white: bool,
+    pub game_state: GameState,
+    pub outcome: Outcome,
+    /// The PGN `Termination` of the game.
+    pub termination: &'static str,
+}
+
+impl GameRecord {
+    pub fn to_pgn(&self) -> Pgn {
+        let tags = [
+            ("Event", "Engine match".to_string()),
+            ("Site", "?".to_string()),
+            ("Round", (self.index + 1).to_string()),
+            ("White", self.white.clone()),
+            ("Black", self.black.clone()),
+            ("Result", self.outcome.result().to_string()),
+            ("Termination", self.termination.to_string()),
+        ];
+        let tags = tags
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        Pgn::from_game(&self.game_state, tags, Some(self.outcome.result()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchReport {
+    pub score: MatchScore,
+    pub games_played: usize,
+    /// The SPRT verdict when the match ran one, `Continue` if the games ran out first.
+    pub decision: Option<SprtDecision>,
+}
+
+/// Plays a match between the two engines of `config`, reporting every finished game to
+/// `on_game` together with the running score of the first engine.
+///
+/// Every worker thread starts its own pair of players and pulls the next game from a
+/// shared schedule. Games are paired: game `2k` and `2k + 1` start from the same opening
+/// with colours reversed. The match stops early once the SPRT reaches a decision.
+pub fn run_match<F>(config: &MatchConfig, mut on_game: F) -> Result<MatchReport, EngineError>
+where
+    F: FnMut(&GameRecord, &MatchScore),
+{
+    if config.openings.is_empty() {
+        return Err(EngineError::InvalidOpenings(
+            "The opening suite has no positions".to_string(),
+        ));
+    }
+
+    let next_game = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+    let workers = config.concurrency.clamp(1, config.games.max(1));
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let sender = sender.clone();
+                let (next_game, stop) = (&next_game, &stop);
+                scope.spawn(move || -> Result<(), EngineError> {
+                    let mut players = [config.engines[0].create()?, config.engines[1].create()?];
+                    loop {
+                        let index = next_game.fetch_add(1, Ordering::Relaxed);
+                        if index >= config.games || stop.load(Ordering::Relaxed) {
+                            return Ok(());
+                        }
+                        let record = play_game(config, &mut players, index)?;
+                        if sender.send(record).is_err() {
+                            return Ok(());
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(sender);
+
+        let mut score = MatchScore::default();
+        let mut games_played = 0;
+        let mut decision = config.sprt.map(|_| SprtDecision::Continue);
+        for record in receiver {
+            let first_color = match record.first_is_white {
+                true => Color::White,
+                false => Color::Black,
+            };
+            match record.outcome {
+                Outcome::Draw => score.draws += 1,
+                outcome if outcome == Outcome::win(first_color) => score.wins += 1,
+                _ => score.losses += 1,
+            }
+            games_played += 1;
+            on_game(&record, &score);
+
+            if let Some(sprt) = &config.sprt {
+                decision = Some(sprt.decide(&score));
+                if decision != Some(SprtDecision::Continue) {
+                    stop.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+
+        for handle in handles {
+            handle.join().expect("Match worker panicked")?;
+        }
+
+        Ok(MatchReport {
+            score,
+            games_played,
+            decision,
+        })
+    })
+}
+
+/// Plays one game. A player that fails to answer, answers with an illegal move or runs
+/// out of time loses the game.
+fn play_game(
+    config: &MatchConfig,
+    players: &mut [Box<dyn MatchPlayer>; 2],
+    index: usize,
+) -> Result<GameRecord, EngineError> {
+    let opening = &config.openings[(index / 2) % config.openings.len()];
+    let first_is_white = index.is_multiple_of(2);
+    let white_player = usize::from(!first_is_white);
+    let player_for = |color: Color| match color {
+        Color::White => white_player,
+        Color::Black => 1 - white_player,
+    };
+
+    for player in players.iter_mut() {
+        player.new_game()?;
+    }
+
+    let tc = &config.time_control;
+    let mut remaining = [tc.base; 2];
+    let mut game_state = opening.clone();
+    let plies_at_start = game_state.move_history.len();
+
+    let (outcome, termination) = loop {
+        if let Some(result) = &game_state.result {
+            break match result.winner {
+                Some(winner) => (Outcome::win(winner.color), "normal"),
+                None => (Outcome::Draw, "normal"),
+            };
+        }
+        if game_state.move_history.len() - plies_at_start >= config.max_plies {
+            break (Outcome::Draw, "adjudication");
+        }
+
+        let color = game_state.current_player.color;
+        let side = player_for(color);
+        let params = GoParams {
+            movetime: tc.movetime,
+            wtime: remaining[player_for(Color::White)],
+            btime: remaining[player_for(Color::Black)],
+            winc: tc.base.map(|_| tc.increment),
+            binc: tc.base.map(|_| tc.increment),
+            ..GoParams::default()
+        };
+
+        let start = Instant::now();
+        let answer = players[side].best_move(&game_state, &params);
+        let elapsed = start.elapsed();
+
+        if let Some(time) = &mut remaining[side] {
+            if elapsed > *time {
+                break (Outcome::win(color.opponent()), "time forfeit");
+            }
+            *time = *time - elapsed + tc.increment;
+        }
+
+        let m = match answer {
+            Ok(m) => m,
+            Err(_) => break (Outcome::win(color.opponent()), "rules infraction"),
+        };
+        let is_legal = game_state
+            .legal_moves_for_piece(m.from)
+            .is_some_and(|(_, legal)| legal.iter().any(|legal| legal.to == m.to));
+        if !is_legal {
+            break (Outcome::win(color.opponent()), "rules infraction");
+        }
+        game_state.make_move(m);
+    };
+
+    Ok(GameRecord {
+        index,
+        white: players[white_player].name().to_string(),
+        black: players[1 - white_player].name().to_string(),
+        first_is_white,
+        game_state,
+        outcome,
+        termination,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_control() {
+        assert_eq!(
+            TimeControl::parse("10+0.1").unwrap(),
+            TimeControl {
+                base: Some(Duration::from_secs(10)),
+                increment: Duration::from_millis(100),
+                movetime: None,
+            }
+        );
+        assert_eq!(
+            TimeControl::parse("60").unwrap().base,
+            Some(Duration::from_secs(60))
+        );
+        assert!(TimeControl::parse("fast").is_err());
+        assert!(TimeControl::parse("-1+0").is_err());
+    }
+
+    #[test]
+    fn test_short_match_is_paired_and_recorded() {
+        let engines = [
+            EngineSpec::parse("internal:name=one,depth=1").unwrap(),
+            EngineSpec::parse("internal:name=two,depth=1").unwrap(),
+        ];
+        let opening = GameState::from_fen("4k3/8/8/8/8/8/3QK3/8 w - - 0 1").unwrap();
+        let mut config = MatchConfig::new(engines, vec![opening], 2);
+        config.max_plies = 6;
+        config.concurrency = 2;
+
+        let mut records = vec![];
+        let report = run_match(&config, |record, _| records.push(record.clone())).unwrap();
+
+        assert_eq!(report.games_played, 2);
+        assert_eq!(report.score.games(), 2);
+        assert_eq!(report.decision, None);
+        records.sort_by_key(|record| record.index);
+        assert_eq!(
+            (records[0].white.as_str(), records[0].black.as_str()),
+            ("one", "two")
+        );
+        assert_eq!(
+            (records[1].white.as_str(), records[1].black.as_str()),
+            ("two", "one")
+        );
+
+        let pgn = records[0].to_pgn();
+        assert_eq!(pgn.tag("FEN"), Some("4k3/8/8/8/8/8/3QK3/8 w - - 0 1"));
+        assert_eq!(
+            pgn.mainline().unwrap().len(),
+            records[0].game_state.move_history.len()
+        );
+    }
+}
@@ -0,0 +1,165 @@
+/// Wins, draws and losses of the first engine of a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MatchScore {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchScore {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Points per game, between 0 and 1.
+    pub fn mean(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games().max(1) as f64
+    }
+
+    /// The Elo difference the score corresponds to, and the half width of its 95%
+    /// confidence interval. `None` until the score is neither perfect nor zero.
+    pub fn elo(&self) -> Option<(f64, f64)> {
+        let mean = self.mean();
+        if self.games() == 0 || mean <= 0.0 || mean >= 1.0 {
+            return None;
+        }
+
+        let stderr = (self.variance() / self.games() as f64).sqrt();
+        let low = (mean - 1.96 * stderr).max(f64::EPSILON);
+        let high = (mean + 1.96 * stderr).min(1.0 - f64::EPSILON);
+        Some((
+            score_to_elo(mean),
+            (score_to_elo(high) - score_to_elo(low)) / 2.0,
+        ))
+    }
+}
+
+impl MatchScore {
+    /// Variance of the points of a single game.
+    fn variance(&self) -> f64 {
+        let mean = self.mean();
+        let games = self.games().max(1) as f64;
+        (self.wins as f64 * (1.0 - mean).powi(2)
+            + self.draws as f64 * (0.5 - mean).powi(2)
+            + self.losses as f64 * mean.powi(2))
+            / games
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtDecision {
+    Continue,
+    /// The first engine is most likely no more than `elo0` stronger.
+    AcceptH0,
+    /// The first engine is most likely at least `elo1` stronger.
+    AcceptH1,
+}
+
+/// A sequential probability ratio test between the hypotheses that the first engine is
+/// `elo0` (H0) or `elo1` (H1) Elo stronger, with false positive rate `alpha` and false
+/// negative rate `beta`.
+///
+/// The log-likelihood ratio uses the normal approximation of the trinomial game outcome,
+/// the same approximation testing frameworks commonly use for logistic Elo bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Default for Sprt {
+    fn default() -> Self {
+        Self {
+            elo0: 0.0,
+            elo1: 5.0,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+}
+
+impl Sprt {
+    /// The lower and upper LLR bounds, at which H0 and H1 are accepted.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    pub fn llr(&self, score: &MatchScore) -> f64 {
+        let variance = score.variance();
+        if score.games() == 0 || variance <= 0.0 {
+            return 0.0;
+        }
+
+        let s0 = elo_to_score(self.elo0);
+        let s1 = elo_to_score(self.elo1);
+        score.games() as f64 * (s1 - s0) * (2.0 * score.mean() - s0 - s1) / (2.0 * variance)
+    }
+
+    pub fn decide(&self, score: &MatchScore) -> SprtDecision {
+        let llr = self.llr(score);
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtDecision::AcceptH1
+        } else if llr <= lower {
+            SprtDecision::AcceptH0
+        } else {
+            SprtDecision::Continue
+        }
+    }
+}
+
+fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+fn score_to_elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(wins: u32, draws: u32, losses: u32) -> MatchScore {
+        MatchScore {
+            wins,
+            draws,
+            losses,
+        }
+    }
+
+    #[test]
+    fn test_elo_and_error_bars() {
+        assert_eq!(score(0, 0, 0).elo(), None);
+        assert_eq!(score(3, 0, 0).elo(), None);
+
+        let (even, margin) = score(40, 20, 40).elo().unwrap();
+        assert!(even.abs() < 1e-9);
+        assert!(margin > 50.0 && margin < 80.0);
+
+        // 75% is about +191 Elo
+        let (elo, _) = score(60, 30, 10).elo().unwrap();
+        assert!((elo - 190.8).abs() < 0.5);
+
+        let (_, wider) = score(4, 2, 4).elo().unwrap();
+        assert!(wider > margin);
+    }
+
+    #[test]
+    fn test_sprt_bounds_and_decisions() {
+        let sprt = Sprt::default();
+        let (lower, upper) = sprt.bounds();
+        assert!((lower + 2.944).abs() < 1e-3);
+        assert!((upper - 2.944).abs() < 1e-3);
+
+        assert_eq!(sprt.decide(&score(5, 10, 5)), SprtDecision::Continue);
+        assert_eq!(sprt.decide(&score(300, 200, 100)), SprtDecision::AcceptH1);
+        assert_eq!(sprt.decide(&score(100, 200, 300)), SprtDecision::AcceptH0);
+        assert_eq!(sprt.llr(&score(0, 10, 0)), 0.0);
+    }
+}
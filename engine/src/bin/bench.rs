@@ -0,0 +1,46 @@
+//! Runs the search bench with one thread and with the requested number of threads, and
+//! reports the speed-up in nodes per second and time-to-depth.
+//!
+//! Usage: `bench [depth] [threads]`
+
+use engine::search::{bench, BenchReport, Searcher};
+
+const DEFAULT_DEPTH: u32 = 5;
+
+fn run(depth: u32, threads: usize) -> BenchReport {
+    let mut searcher = Searcher::default();
+    searcher.set_threads(threads);
+    let report = bench(&mut searcher, depth);
+
+    println!(
+        "threads {:>3}  positions {}  nodes {:>10}  time {:>8} ms  nps {:>9}",
+        threads,
+        report.positions,
+        report.nodes,
+        report.elapsed.as_millis(),
+        report.nps()
+    );
+    report
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let depth = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_DEPTH);
+    let threads = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |threads| threads.get()));
+
+    let single = run(depth, 1);
+    if threads > 1 {
+        let multi = run(depth, threads);
+        println!(
+            "nps speed-up {:.2}x  time-to-depth speed-up {:.2}x",
+            multi.nps() as f64 / single.nps().max(1) as f64,
+            single.elapsed.as_secs_f64() / multi.elapsed.as_secs_f64().max(f64::EPSILON)
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Plays an engine match between two configurations of the internal engine or external
+//! UCI engines, and reports the Elo difference of the first engine with error bars.
+//!
+//! Usage: `match --engine1 <spec> --engine2 <spec> --openings <file.epd|file.pgn>
+//! [--games N] [--concurrency N] [--tc base+inc | --movetime ms] [--max-plies N]
+//! [--pgn out.pgn] [--sprt elo0=0,elo1=5,alpha=0.05,beta=0.05]`
+//!
+//! Engine specs are `internal[:key=value,...]` or `uci:program[,key=value,...]`, e.g.
+//! `internal:name=new,depth=4,Threads=2`, `internal:level=club` or
+//! `uci:/usr/bin/stockfish,Hash=64`.
+
+use std::{fs, io::Write, process::ExitCode, time::Duration};
+
+use engine::arena::{
+    parse_openings, run_match, EngineSpec, GameRecord, MatchConfig, MatchScore, Sprt, SprtDecision,
+    TimeControl,
+};
+
+const DEFAULT_GAMES: usize = 100;
+
+struct Args {
+    engines: [String; 2],
+    openings: String,
+    games: usize,
+    concurrency: usize,
+    time_control: TimeControl,
+    max_plies: Option<usize>,
+    pgn: Option<String>,
+    sprt: Option<Sprt>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut engines = [None, None];
+    let mut openings = None;
+    let mut args = Args {
+        engines: Default::default(),
+        openings: String::new(),
+        games: DEFAULT_GAMES,
+        concurrency: 1,
+        time_control: TimeControl::default(),
+        max_plies: None,
+        pgn: None,
+        sprt: None,
+    };
+
+    let mut argv = std::env::args().skip(1);
+    while let Some(flag) = argv.next() {
+        let value = argv
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", flag))?;
+        let number = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid number for {}: {}", flag, value))
+        };
+
+        match flag.as_str() {
+            "--engine1" => engines[0] = Some(value),
+            "--engine2" => engines[1] = Some(value),
+            "--openings" => openings = Some(value),
+            "--games" => args.games = number(&value)?,
+            "--concurrency" => args.concurrency = number(&value)?,
+            "--tc" => args.time_control = TimeControl::parse(&value).map_err(|e| e.to_string())?,
+            "--movetime" => {
+                args.time_control = TimeControl {
+                    movetime: Some(Duration::from_millis(number(&value)? as u64)),
+                    ..TimeControl::default()
+                }
+            }
+            "--max-plies" => args.max_plies = Some(number(&value)?),
+            "--pgn" => args.pgn = Some(value),
+            "--sprt" => args.sprt = Some(parse_sprt(&value)?),
+            _ => return Err(format!("Unknown argument: {}", flag)),
+        }
+    }
+
+    match (engines, openings) {
+        ([Some(first), Some(second)], Some(openings)) => {
+            args.engines = [first, second];
+            args.openings = openings;
+            Ok(args)
+        }
+        _ => Err("--engine1, --engine2 and --openings are required".to_string()),
+    }
+}
+
+fn parse_sprt(value: &str) -> Result<Sprt, String> {
+    let mut sprt = Sprt::default();
+    for setting in value.split(',').filter(|s| !s.is_empty()) {
+        let (key, number) = setting
+            .split_once('=')
+            .and_then(|(key, number)| Some((key, number.parse::<f64>().ok()?)))
+            .ok_or_else(|| format!("Invalid SPRT setting: {}", setting))?;
+        match key {
+            "elo0" => sprt.elo0 = number,
+            "elo1" => sprt.elo1 = number,
+            "alpha" => sprt.alpha = number,
+            "beta" => sprt.beta = number,
+            _ => return Err(format!("Unknown SPRT setting: {}", key)),
+        }
+    }
+    Ok(sprt)
+}
+
+fn format_elo(score: &MatchScore) -> String {
+    match score.elo() {
+        Some((elo, margin)) => format!("{:+.1} +/- {:.1}", elo, margin),
+        None => "n/a".to_string(),
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+    let engines = [
+        EngineSpec::parse(&args.engines[0]).map_err(|e| e.to_string())?,
+        EngineSpec::parse(&args.engines[1]).map_err(|e| e.to_string())?,
+    ];
+    let unlimited = args.time_control == TimeControl::default()
+        && engines.iter().any(|engine| {
+            engine.depth.is_none() && engine.nodes.is_none() && engine.level.is_none()
+        });
+    if unlimited {
+        return Err(
+            "Give --tc, --movetime or a depth, nodes or level limit for both engines".to_string(),
+        );
+    }
+
+    let text = fs::read_to_string(&args.openings)
+        .map_err(|e| format!("Cannot read {}: {}", args.openings, e))?;
+    let openings = parse_openings(&text).map_err(|e| e.to_string())?;
+
+    let mut config = MatchConfig::new(engines, openings, args.games);
+    config.concurrency = args.concurrency;
+    config.time_control = args.time_control;
+    config.sprt = args.sprt;
+    if let Some(max_plies) = args.max_plies {
+        config.max_plies = max_plies;
+    }
+
+    let mut pgn_file = match &args.pgn {
+        Some(path) => {
+            Some(fs::File::create(path).map_err(|e| format!("Cannot create {}: {}", path, e))?)
+        }
+        None => None,
+    };
+
+    let names = [
+        config.engines[0].name.clone(),
+        config.engines[1].name.clone(),
+    ];
+    let report = run_match(&config, |record: &GameRecord, score: &MatchScore| {
+        println!(
+            "Game {} ({} vs {}): {} {{{}}}  Score of {} vs {}: {} - {} - {}  Elo {}",
+            record.index + 1,
+            record.white,
+            record.black,
+            record.outcome.result(),
+            record.termination,
+            names[0],
+            names[1],
+            score.wins,
+            score.losses,
+            score.draws,
+            format_elo(score)
+        );
+        if let Some(file) = &mut pgn_file {
+            if let Err(e) = writeln!(file, "{}", record.to_pgn()) {
+                eprintln!("Cannot write game {}: {}", record.index + 1, e);
+            }
+        }
+    })
+    .map_err(|e| e.to_string())?;
+
+    let score = &report.score;
+    println!(
+        "Finished {} games. {} vs {}: {} - {} - {} ({:.1}%)  Elo {}",
+        report.games_played,
+        names[0],
+        names[1],
+        score.wins,
+        score.losses,
+        score.draws,
+        score.mean() * 100.0,
+        format_elo(score)
+    );
+    if let (Some(sprt), Some(decision)) = (&config.sprt, report.decision) {
+        let (lower, upper) = sprt.bounds();
+        let verdict = match decision {
+            SprtDecision::AcceptH0 => "H0 accepted",
+            SprtDecision::AcceptH1 => "H1 accepted",
+            SprtDecision::Continue => "inconclusive",
+        };
+        println!(
+            "SPRT elo0={} elo1={}: LLR {:.2} ({:.2}, {:.2}) {}",
+            sprt.elo0,
+            sprt.elo1,
+            sprt.llr(score),
+            lower,
+            upper,
+            verdict
+        );
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Tunes the evaluation parameters on labelled positions and writes them to a parameter
+//! file, which the engine loads with its `EvalFile` option.
+//!
+//! Usage: `tune <positions.epd|games.pgn> [--epochs N] [--rate R] [--init params.txt]
+//! [--out params.txt]`
+
+use std::{fs, process::ExitCode};
+
+use engine::{
+    eval::EvalParams,
+    tuning::{parse_labelled_positions, Tuner},
+};
+
+const DEFAULT_EPOCHS: usize = 500;
+const DEFAULT_RATE: f64 = 1.0;
+const DEFAULT_OUT: &str = "eval_params.txt";
+const REPORT_EVERY: usize = 25;
+const USAGE: &str = "Usage: tune <positions> [--epochs N] [--rate R] [--init F] [--out F]";
+
+fn run() -> Result<(), String> {
+    let mut argv = std::env::args().skip(1);
+    let data = argv.next().ok_or(USAGE)?;
+    let mut epochs = DEFAULT_EPOCHS;
+    let mut rate = DEFAULT_RATE;
+    let mut params = EvalParams::default();
+    let mut out = DEFAULT_OUT.to_string();
+
+    while let Some(flag) = argv.next() {
+        let value = argv
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", flag))?;
+        let invalid = || format!("Invalid value for {}: {}", flag, value);
+        match flag.as_str() {
+            "--epochs" => epochs = value.parse().map_err(|_| invalid())?,
+            "--rate" => rate = value.parse().map_err(|_| invalid())?,
+            "--init" => params = EvalParams::load(&value).map_err(|e| e.to_string())?,
+            "--out" => out = value,
+            _ => return Err(format!("Unknown argument: {}", flag)),
+        }
+    }
+
+    let text = fs::read_to_string(&data).map_err(|e| format!("Cannot read {}: {}", data, e))?;
+    let positions = parse_labelled_positions(&text).map_err(|e| e.to_string())?;
+    println!("Loaded {} positions", positions.len());
+
+    let mut tuner = Tuner::new(&positions, &params);
+    let scaling = tuner.fit_scaling();
+    println!("K = {:.4}, initial error {:.6}", scaling, tuner.error());
+
+    for epoch in 1..=epochs {
+        tuner.epoch(rate);
+        if epoch % REPORT_EVERY == 0 || epoch == epochs {
+            println!("Epoch {:>5}  error {:.6}", epoch, tuner.error());
+        }
+    }
+
+    fs::write(&out, tuner.params().to_string())
+        .map_err(|e| format!("Cannot write {}: {}", out, e))?;
+    println!("Wrote {}", out);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use domain::{
+    board::Position,
+    game::GameState,
+    moves::{Move, MoveType},
+    pieces::{PieceKind, PromotionPiece},
+};
+
+pub const ENTRY_SIZE: usize = 16;
+
+/// A single 16 byte record of a Polyglot `.bin` book. All fields are stored big-endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookEntry {
+    pub key: u64,
+    pub raw_move: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl BookEntry {
+    pub fn from_bytes(bytes: &[u8; ENTRY_SIZE]) -> Self {
+        Self {
+            key: u64::from_be_bytes(bytes[0..8].try_into().unwrap_or_default()),
+            raw_move: u16::from_be_bytes([bytes[8], bytes[9]]),
+            weight: u16::from_be_bytes([bytes[10], bytes[11]]),
+            learn: u32::from_be_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0; ENTRY_SIZE];
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.raw_move.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.learn.to_be_bytes());
+        bytes
+    }
+
+    /// Packs `m` into the Polyglot move format. Castling is stored as the king capturing
+    /// its own rook, e.g. `e1h1` for white short castling.
+    pub fn encode_move(m: &Move) -> u16 {
+        let to = match m.move_type {
+            MoveType::ShortCastle => Position::new(m.to.row, 7),
+            MoveType::LongCastle => Position::new(m.to.row, 0),
+            _ => m.to,
+        };
+
+        let promotion = match &m.move_type {
+            MoveType::Promotion(PromotionPiece::Knight) => 1,
+            MoveType::Promotion(PromotionPiece::Bishop) => 2,
+            MoveType::Promotion(PromotionPiece::Rook) => 3,
+            MoveType::Promotion(PromotionPiece::Queen) => 4,
+            _ => 0,
+        };
+
+        (promotion << 12) | (Self::square(&m.from) << 6) | Self::square(&to)
+    }
+
+    /// Resolves the stored move against the legal moves of `game_state`.
+    pub fn decode_move(&self, game_state: &GameState) -> Option<Move> {
+        let to = Self::position((self.raw_move & 0x3F) as u8);
+        let from = Self::position(((self.raw_move >> 6) & 0x3F) as u8);
+        let promotion = match (self.raw_move >> 12) & 0x7 {
+            0 => None,
+            1 => Some(PromotionPiece::Knight),
+            2 => Some(PromotionPiece::Bishop),
+            3 => Some(PromotionPiece::Rook),
+            4 => Some(PromotionPiece::Queen),
+            _ => return None,
+        };
+
+        let is_king = game_state.board[&from]
+            .is_some_and(|piece| PieceKind::from(&piece.piece_type) == PieceKind::King);
+        let to = match (is_king, from.column, to.column) {
+            (true, 4, 7) if from.row == to.row => Position::new(to.row, 6),
+            (true, 4, 0) if from.row == to.row => Position::new(to.row, 2),
+            _ => to,
+        };
+
+        let mut m = game_state
+            .legal_moves()
+            .into_iter()
+            .find(|m| m.from == from && m.to == to)?;
+
+        if let (MoveType::Promotion(_), Some(piece)) = (&m.move_type, promotion) {
+            m.move_type = MoveType::Promotion(piece);
+        }
+
+        Some(m)
+    }
+}
+
+impl BookEntry {
+    fn square(pos: &Position) -> u16 {
+        (8 * (7 - pos.row) + pos.column) as u16
+    }
+
+    fn position(square: u8) -> Position {
+        Position::new(7 - (square / 8) as i8, (square % 8) as i8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::game::San;
+
+    use super::*;
+
+    #[test]
+    fn test_entry_round_trip() {
+        let entry = BookEntry {
+            key: 0x463b96181691fc9c,
+            raw_move: 0x031c,
+            weight: 12,
+            learn: 0,
+        };
+
+        assert_eq!(BookEntry::from_bytes(&entry.to_bytes()), entry);
+    }
+
+    #[test]
+    fn test_castling_is_encoded_as_king_takes_rook() {
+        let mut game_state = GameState::new();
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5"] {
+            let m = San::parse(&game_state, san).unwrap();
+            game_state.make_move(m);
+        }
+
+        let castle = San::parse(&game_state, "O-O").unwrap();
+        let raw_move = BookEntry::encode_move(&castle);
+
+        // e1 = 4, h1 = 7
+        assert_eq!(raw_move, (4 << 6) | 7);
+
+        let entry = BookEntry {
+            key: 0,
+            raw_move,
+            weight: 1,
+            learn: 0,
+        };
+        assert_eq!(entry.decode_move(&game_state), Some(castle));
+    }
+}
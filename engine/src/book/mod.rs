@@ -0,0 +1,7 @@
+pub mod entry;
+pub mod reader;
+pub mod writer;
+
+pub use entry::*;
+pub use reader::*;
+pub use writer::*;
@@ -0,0 +1,129 @@
+use std::{fs, path::Path};
+
+use domain::{game::GameState, moves::Move};
+use rand::Rng;
+
+use crate::{error::EngineError, zobrist::polyglot_key};
+
+use super::{BookEntry, ENTRY_SIZE};
+
+/// How a move is chosen when a position has several book moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BookPolicy {
+    /// Always play the move with the highest weight.
+    Best,
+    /// Play each move with a probability proportional to its weight.
+    #[default]
+    Weighted,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookMove {
+    pub mv: Move,
+    pub weight: u16,
+}
+
+/// A Polyglot opening book held in memory, with its entries sorted by key.
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    entries: Vec<BookEntry>,
+}
+
+impl Book {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, EngineError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EngineError> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(EngineError::InvalidBook(format!(
+                "Book size {} is not a multiple of {}",
+                bytes.len(),
+                ENTRY_SIZE
+            )));
+        }
+
+        let mut entries: Vec<BookEntry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .filter_map(|chunk| chunk.try_into().ok())
+            .map(|chunk: &[u8; ENTRY_SIZE]| BookEntry::from_bytes(chunk))
+            .collect();
+
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Self { entries })
+    }
+
+    pub(super) fn from_entries(mut entries: Vec<BookEntry>) -> Self {
+        entries.sort_by(|a, b| a.key.cmp(&b.key).then(b.weight.cmp(&a.weight)));
+        Self { entries }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.to_bytes())
+            .collect()
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), EngineError> {
+        Ok(fs::write(path, self.to_bytes())?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the legal book moves for the position, heaviest first.
+    pub fn moves(&self, game_state: &GameState) -> Vec<BookMove> {
+        let key = polyglot_key(game_state);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+
+        let mut moves: Vec<BookMove> = self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter(|entry| entry.weight > 0)
+            .filter_map(|entry| {
+                entry.decode_move(game_state).map(|mv| BookMove {
+                    mv,
+                    weight: entry.weight,
+                })
+            })
+            .collect();
+
+        moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.weight));
+        moves
+    }
+
+    pub fn pick<R: Rng>(
+        &self,
+        game_state: &GameState,
+        policy: BookPolicy,
+        rng: &mut R,
+    ) -> Option<Move> {
+        let moves = self.moves(game_state);
+
+        match policy {
+            BookPolicy::Best => moves.into_iter().next().map(|book_move| book_move.mv),
+            BookPolicy::Weighted => {
+                let total: u32 = moves.iter().map(|book_move| book_move.weight as u32).sum();
+                if total == 0 {
+                    return None;
+                }
+
+                let mut roll = rng.gen_range(0..total);
+                moves.into_iter().find_map(|book_move| {
+                    if roll < book_move.weight as u32 {
+                        Some(book_move.mv)
+                    } else {
+                        roll -= book_move.weight as u32;
+                        None
+                    }
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use domain::game::{Color, GameState, Pgn, PgnError, San};
+
+use crate::zobrist::polyglot_key;
+
+use super::{Book, BookEntry};
+
+#[derive(Debug, Clone, Copy, Default)]
+struct MoveStats {
+    games: u32,
+    score: u32,
+}
+
+/// Collects opening statistics from PGN games and turns them into a Polyglot book.
+///
+/// Every move played within the first `max_ply` half-moves is scored from the mover's point
+/// of view (2 for a win, 1 for a draw). Moves seen in fewer than `min_games` games, or that
+/// never scored, are left out of the book.
+#[derive(Debug, Clone)]
+pub struct BookBuilder {
+    max_ply: usize,
+    min_games: u32,
+    stats: HashMap<(u64, u16), MoveStats>,
+}
+
+impl BookBuilder {
+    pub fn new(max_ply: usize, min_games: u32) -> Self {
+        Self {
+            max_ply,
+            min_games,
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Adds every game in `text` and returns how many of them could be replayed.
+    pub fn add_pgn(&mut self, text: &str) -> usize {
+        Pgn::parse_all(text)
+            .iter()
+            .filter(|pgn| self.add_game(pgn).is_ok())
+            .count()
+    }
+
+    pub fn add_game(&mut self, pgn: &Pgn) -> Result<(), PgnError> {
+        if pgn.tag("FEN").is_some() {
+            return Err(PgnError::UnsupportedSetup(
+                "Games starting from a custom position are not supported".to_string(),
+            ));
+        }
+
+        let result = pgn.result.as_deref().or_else(|| pgn.tag("Result"));
+        let mut game_state = GameState::new();
+        let mut played = vec![];
+
+        for san in pgn.moves.iter().take(self.max_ply) {
+            let m = San::parse(&game_state, san)
+                .ok_or_else(|| PgnError::IllegalMove(format!("Illegal move: {}", san)))?;
+            let score = Self::score(result, game_state.current_player.color);
+            played.push((polyglot_key(&game_state), BookEntry::encode_move(&m), score));
+            game_state.make_move(m);
+        }
+
+        for (key, raw_move, score) in played {
+            let stats = self.stats.entry((key, raw_move)).or_default();
+            stats.games += 1;
+            stats.score += score;
+        }
+
+        Ok(())
+    }
+
+    pub fn build(&self) -> Book {
+        let kept: Vec<(&(u64, u16), &MoveStats)> = self
+            .stats
+            .iter()
+            .filter(|(_, stats)| stats.games >= self.min_games && stats.score > 0)
+            .collect();
+
+        let max_score = kept
+            .iter()
+            .map(|(_, stats)| stats.score)
+            .max()
+            .unwrap_or_default();
+        let scale = |score: u32| match max_score > u16::MAX as u32 {
+            true => ((score as u64 * u16::MAX as u64) / max_score as u64).max(1) as u16,
+            false => score as u16,
+        };
+
+        Book::from_entries(
+            kept.into_iter()
+                .map(|(&(key, raw_move), stats)| BookEntry {
+                    key,
+                    raw_move,
+                    weight: scale(stats.score),
+                    learn: 0,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl BookBuilder {
+    fn score(result: Option<&str>, mover: Color) -> u32 {
+        match (result, mover) {
+            (Some("1-0"), Color::White) | (Some("0-1"), Color::Black) => 2,
+            (Some("1/2-1/2"), _) => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::book::BookPolicy;
+
+    use super::*;
+
+    const GAMES: &str = r#"
+[Result "1-0"]
+1. e4 e5 2. Nf3 Nc6 1-0
+
+[Result "1-0"]
+1. e4 c5 2. Nf3 d6 1-0
+
+[Result "1/2-1/2"]
+1. d4 d5 2. c4 e6 1/2-1/2
+
+[Result "0-1"]
+1. c4 e5 0-1
+"#;
+
+    fn play(moves: &[&str]) -> GameState {
+        let mut game_state = GameState::new();
+        for san in moves {
+            let m = San::parse(&game_state, san).unwrap();
+            game_state.make_move(m);
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_build_and_read_back() {
+        let mut builder = BookBuilder::new(4, 1);
+        assert_eq!(builder.add_pgn(GAMES), 4);
+
+        let book = Book::from_bytes(&builder.build().to_bytes()).unwrap();
+        let start = GameState::new();
+        let moves = book.moves(&start);
+
+        // e4 scored two wins, d4 a draw and c4 a loss
+        assert_eq!(moves.len(), 2);
+        assert_eq!(San::derive(&start, &moves[0].mv).to_string(), "e4");
+        assert_eq!(moves[0].weight, 4);
+        assert_eq!(San::derive(&start, &moves[1].mv).to_string(), "d4");
+        assert_eq!(moves[1].weight, 1);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(
+            book.pick(&start, BookPolicy::Best, &mut rng),
+            Some(moves[0].mv.clone())
+        );
+    }
+
+    #[test]
+    fn test_depth_and_min_games_limits() {
+        let mut builder = BookBuilder::new(2, 2);
+        builder.add_pgn(GAMES);
+        let book = builder.build();
+
+        // Only 1. e4 was played in two games; replies were seen once each
+        assert_eq!(book.len(), 1);
+        assert!(book.moves(&play(&["e4"])).is_empty());
+        assert!(book.moves(&play(&["e4", "e5"])).is_empty());
+    }
+
+    #[test]
+    fn test_weighted_pick_varies_openings() {
+        let mut builder = BookBuilder::new(1, 1);
+        builder.add_pgn(GAMES);
+        let book = builder.build();
+        let start = GameState::new();
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let picks: Vec<String> = (0..50)
+            .filter_map(|_| book.pick(&start, BookPolicy::Weighted, &mut rng))
+            .map(|m| San::derive(&start, &m).to_string())
+            .collect();
+
+        assert_eq!(picks.len(), 50);
+        assert!(picks.iter().any(|san| san == "e4"));
+        assert!(picks.iter().any(|san| san == "d4"));
+        assert!(picks.iter().all(|san| san != "c4"));
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum EngineError {
+    Io(String),
+    Timeout(String),
+    Disconnected(String),
+    UnknownOption(String),
+    InvalidOptionValue(String),
+    InvalidMove(String),
+    InvalidBook(String),
+    InvalidTablebase(String),
+    MissingTable(String),
+    InvalidOpenings(String),
+    InvalidEngineSpec(String),
+    InvalidParams(String),
+    InvalidDataset(String),
+}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+impl From<std::io::Error> for EngineError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value.to_string())
+    }
+}
@@ -0,0 +1,87 @@
+use domain::{board::Board, game::Color};
+
+use super::{EvalParams, Phased};
+
+/// Phase weight of each piece kind, indexed by `PieceType::as_index`. A full board of
+/// minor and major pieces adds up to `MAX_PHASE`.
+pub(crate) const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+pub(crate) const MAX_PHASE: i32 = 24;
+
+/// Static evaluation of `board` in centipawns from the point of view of `side_to_move`.
+pub fn evaluate(board: &Board, side_to_move: Color, params: &EvalParams) -> i32 {
+    let mut score = Phased::default();
+    let mut phase = 0;
+    let mut bishops = [0; 2];
+
+    for (row, fields) in board.fields.iter().enumerate() {
+        for (column, piece) in fields.iter().enumerate() {
+            let Some(piece) = piece else {
+                continue;
+            };
+
+            let kind = piece.piece_type.as_index();
+            let (sign, square, color) = match piece.piece_color {
+                Color::White => (1, 8 * row + column, 0),
+                Color::Black => (-1, 8 * (7 - row) + column, 1),
+            };
+
+            let material = params.material[kind];
+            let pst = params.pst[kind][square];
+            score.mg += sign * (material.mg + pst.mg);
+            score.eg += sign * (material.eg + pst.eg);
+            phase += PHASE_WEIGHTS[kind];
+
+            if kind == 2 {
+                bishops[color] += 1;
+            }
+        }
+    }
+
+    for (color, sign) in [(0, 1), (1, -1)] {
+        if bishops[color] >= 2 {
+            score.mg += sign * params.bishop_pair.mg;
+            score.eg += sign * params.bishop_pair.eg;
+        }
+    }
+
+    let phase = phase.min(MAX_PHASE);
+    let blended = (score.mg * phase + score.eg * (MAX_PHASE - phase)) / MAX_PHASE;
+
+    match side_to_move {
+        Color::White => blended,
+        Color::Black => -blended,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::{
+        board::{BoardBuilder, Position},
+        pieces::PieceKind,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_start_position_is_balanced() {
+        let params = EvalParams::default();
+        let board = Board::new();
+
+        assert_eq!(evaluate(&board, Color::White, &params), 0);
+        assert_eq!(evaluate(&board, Color::Black, &params), 0);
+    }
+
+    #[test]
+    fn test_extra_material_is_seen_from_both_sides() {
+        let params = EvalParams::default();
+        let board = BoardBuilder::new()
+            .add_piece(Position::new(7, 6), PieceKind::King, Color::White)
+            .add_piece(Position::new(4, 3), PieceKind::Rook, Color::White)
+            .add_piece(Position::new(0, 6), PieceKind::King, Color::Black)
+            .build();
+
+        let white = evaluate(&board, Color::White, &params);
+        assert!(white > 400);
+        assert_eq!(evaluate(&board, Color::Black, &params), -white);
+    }
+}
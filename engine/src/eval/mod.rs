@@ -0,0 +1,5 @@
+pub mod evaluate;
+pub mod params;
+
+pub use evaluate::*;
+pub use params::*;
@@ -0,0 +1,257 @@
+use std::{fmt::Display, path::Path};
+
+use crate::error::EngineError;
+
+const PIECE_NAMES: [&str; 6] = ["pawn", "knight", "bishop", "rook", "queen", "king"];
+
+/// A middlegame and an endgame value, blended by the game phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Phased {
+    pub mg: i32,
+    pub eg: i32,
+}
+
+impl Phased {
+    pub const fn new(mg: i32, eg: i32) -> Self {
+        Self { mg, eg }
+    }
+}
+
+/// Everything the evaluator is parameterised by, in centipawns. Piece-square tables are
+/// written from white's point of view with rank 8 first, the same order as `Board::fields`,
+/// and are indexed by `PieceType::as_index`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalParams {
+    pub material: [Phased; 6],
+    pub pst: [[Phased; 64]; 6],
+    pub bishop_pair: Phased,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        let same = |table: [i32; 64]| table.map(|value| Phased::new(value, value));
+        let mut king = [Phased::default(); 64];
+        for (square, phased) in king.iter_mut().enumerate() {
+            *phased = Phased::new(KING_MG[square], KING_EG[square]);
+        }
+
+        Self {
+            material: [
+                Phased::new(100, 100),
+                Phased::new(320, 320),
+                Phased::new(330, 330),
+                Phased::new(500, 500),
+                Phased::new(900, 900),
+                Phased::new(0, 0),
+            ],
+            pst: [
+                same(PAWN),
+                same(KNIGHT),
+                same(BISHOP),
+                same(ROOK),
+                same(QUEEN),
+                king,
+            ],
+            bishop_pair: Phased::new(30, 50),
+        }
+    }
+}
+
+impl EvalParams {
+    /// Reads parameters in the text format written by `Display`: a `material` line with
+    /// a `mg/eg` pair per piece kind, a `bishop_pair` line, and a `pst <piece>` header per
+    /// piece followed by its 64 pairs. Lines starting with `#` are comments.
+    pub fn parse(text: &str) -> Result<Self, EngineError> {
+        let mut params = Self::default();
+        let mut tokens = text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(str::split_whitespace);
+
+        let mut seen_tables = [false; 6];
+        while let Some(section) = tokens.next() {
+            match section {
+                "material" => {
+                    for value in params.material.iter_mut() {
+                        *value = Self::parse_pair(tokens.next())?;
+                    }
+                }
+                "bishop_pair" => params.bishop_pair = Self::parse_pair(tokens.next())?,
+                "pst" => {
+                    let name = tokens.next().unwrap_or_default();
+                    let kind = PIECE_NAMES
+                        .iter()
+                        .position(|piece| *piece == name)
+                        .ok_or_else(|| {
+                            EngineError::InvalidParams(format!("Unknown piece: {}", name))
+                        })?;
+                    for value in params.pst[kind].iter_mut() {
+                        *value = Self::parse_pair(tokens.next())?;
+                    }
+                    seen_tables[kind] = true;
+                }
+                _ => {
+                    return Err(EngineError::InvalidParams(format!(
+                        "Unknown section: {}",
+                        section
+                    )))
+                }
+            }
+        }
+
+        if !seen_tables.iter().all(|seen| *seen) {
+            return Err(EngineError::InvalidParams(
+                "Every piece needs a piece-square table".to_string(),
+            ));
+        }
+        Ok(params)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl EvalParams {
+    fn parse_pair(token: Option<&str>) -> Result<Phased, EngineError> {
+        let token = token.unwrap_or_default();
+        token
+            .split_once('/')
+            .and_then(|(mg, eg)| Some(Phased::new(mg.parse().ok()?, eg.parse().ok()?)))
+            .ok_or_else(|| EngineError::InvalidParams(format!("Expected mg/eg: '{}'", token)))
+    }
+}
+
+impl Display for EvalParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pair = |value: &Phased| format!("{}/{}", value.mg, value.eg);
+
+        writeln!(
+            f,
+            "# Evaluation parameters in centipawns, middlegame/endgame"
+        )?;
+        writeln!(f, "# material: {}", PIECE_NAMES.join(" "))?;
+        let material: Vec<_> = self.material.iter().map(pair).collect();
+        writeln!(f, "material {}", material.join(" "))?;
+        writeln!(f, "bishop_pair {}", pair(&self.bishop_pair))?;
+
+        for (name, table) in PIECE_NAMES.iter().zip(&self.pst) {
+            writeln!(f, "\npst {}", name)?;
+            for rank in table.chunks(8) {
+                let row: Vec<_> = rank
+                    .iter()
+                    .map(|value| format!("{:>9}", pair(value)))
+                    .collect();
+                writeln!(f, "{}", row.join(""))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+const PAWN: [i32; 64] = [
+     0,  0,   0,   0,   0,   0,  0,  0,
+    50, 50,  50,  50,  50,  50, 50, 50,
+    10, 10,  20,  30,  30,  20, 10, 10,
+     5,  5,  10,  25,  25,  10,  5,  5,
+     0,  0,   0,  20,  20,   0,  0,  0,
+     5, -5, -10,   0,   0, -10, -5,  5,
+     5, 10,  10, -20, -20,  10, 10,  5,
+     0,  0,   0,   0,   0,   0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN: [i32; 64] = [
+    -20, -10, -10, -5, -5, -10, -10, -20,
+    -10,   0,   0,  0,  0,   0,   0, -10,
+    -10,   0,   5,  5,  5,   5,   0, -10,
+     -5,   0,   5,  5,  5,   5,   0,  -5,
+      0,   0,   5,  5,  5,   5,   0,  -5,
+    -10,   5,   5,  5,  5,   5,   0, -10,
+    -10,   0,   5,  0,  0,   0,   0, -10,
+    -20, -10, -10, -5, -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MG: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_EG: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_format_round_trips() {
+        let mut params = EvalParams::default();
+        params.material[1] = Phased::new(305, 290);
+        params.pst[5][63] = Phased::new(-7, 12);
+
+        let text = params.to_string();
+        assert_eq!(EvalParams::parse(&text).unwrap(), params);
+    }
+
+    #[test]
+    fn test_rejects_incomplete_files() {
+        assert!(EvalParams::parse("material 1/1 2/2").is_err());
+        assert!(EvalParams::parse("bishop_pair 30").is_err());
+        assert!(EvalParams::parse("pst dragon").is_err());
+        assert!(EvalParams::parse("bishop_pair 30/50").is_err());
+    }
+}
@@ -0,0 +1,13 @@
+pub mod arena;
+pub mod book;
+pub mod error;
+pub mod eval;
+pub mod movegen;
+pub mod problem;
+pub mod review;
+pub mod search;
+pub mod skill;
+pub mod tablebase;
+pub mod tuning;
+pub mod uci;
+pub mod zobrist;
@@ -0,0 +1,45 @@
+use domain::{
+    board::Board,
+    game::{Color, Player},
+    moves::{Move, MoveType},
+    pieces::{PieceKind, PromotionPiece},
+};
+
+const PROMOTION_PIECES: [PromotionPiece; 4] = [
+    PromotionPiece::Queen,
+    PromotionPiece::Rook,
+    PromotionPiece::Bishop,
+    PromotionPiece::Knight,
+];
+
+/// Returns the legal moves of `color`. The domain only generates queen promotions, so each
+/// promotion is expanded into all four promotion pieces here.
+pub fn legal_moves(board: &Board, color: Color) -> Vec<Move> {
+    board
+        .legal_moves(&Player::new(color))
+        .into_iter()
+        .flat_map(|m| match m.move_type {
+            MoveType::Promotion(_) => PROMOTION_PIECES
+                .iter()
+                .map(|piece| Move::new(MoveType::Promotion(piece.clone()), m.from, m.to))
+                .collect(),
+            _ => vec![m],
+        })
+        .collect()
+}
+
+pub fn is_capture(board: &Board, m: &Move) -> bool {
+    board[&m.to].is_some() || m.move_type == MoveType::EnPassant
+}
+
+/// Captures and pawn moves reset the fifty-move counter.
+pub fn is_zeroing(board: &Board, m: &Move) -> bool {
+    is_capture(board, m)
+        || board[&m.from].is_some_and(|piece| PieceKind::from(&piece.piece_type) == PieceKind::Pawn)
+}
+
+pub fn make_move(board: &Board, m: &Move) -> Board {
+    let mut board = board.clone();
+    m.execute(&mut board);
+    board
+}
@@ -0,0 +1,5 @@
+pub mod solution;
+pub mod solver;
+
+pub use solution::*;
+pub use solver::*;
@@ -0,0 +1,101 @@
+use domain::{
+    game::{Color, GameState, San},
+    moves::Move,
+};
+
+/// What a problem asks for. The number is how many moves the side to move has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stipulation {
+    /// The side to move mates in N against any defence.
+    Mate(u32),
+    /// The side to move helps the other side mate it on the other side's Nth move.
+    Helpmate(u32),
+    /// The side to move forces the other side to mate it within N moves.
+    Selfmate(u32),
+}
+
+impl Stipulation {
+    pub fn moves(&self) -> u32 {
+        match self {
+            Stipulation::Mate(n) | Stipulation::Helpmate(n) | Stipulation::Selfmate(n) => *n,
+        }
+    }
+}
+
+/// A move and everything that may follow it in the solution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variation {
+    pub mv: Move,
+    pub replies: Vec<Variation>,
+}
+
+/// The solution tree of a problem. The first level holds the key moves. Below a key come
+/// every defence, and below each defence the continuations that keep the stipulation.
+/// Helpmates have no defences: each level is the next cooperating move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub stipulation: Stipulation,
+    pub variations: Vec<Variation>,
+}
+
+impl Solution {
+    pub fn is_solved(&self) -> bool {
+        !self.variations.is_empty()
+    }
+
+    pub fn keys(&self) -> Vec<&Move> {
+        self.variations
+            .iter()
+            .map(|variation| &variation.mv)
+            .collect()
+    }
+
+    /// Whether the problem has more than one solution. For a directmate or selfmate that is
+    /// a second key; for a helpmate, a second complete line of play.
+    pub fn is_cooked(&self) -> bool {
+        match self.stipulation {
+            Stipulation::Helpmate(_) => Self::count_lines(&self.variations) > 1,
+            _ => self.variations.len() > 1,
+        }
+    }
+
+    /// The tree in SAN, one move per line, indented by level.
+    pub fn render(&self, game_state: &GameState) -> String {
+        let mut text = String::new();
+        Self::render_level(game_state, &self.variations, 0, &mut text);
+        text
+    }
+}
+
+impl Solution {
+    fn count_lines(variations: &[Variation]) -> usize {
+        variations
+            .iter()
+            .map(|variation| match variation.replies.is_empty() {
+                true => 1,
+                false => Self::count_lines(&variation.replies),
+            })
+            .sum()
+    }
+
+    fn render_level(
+        game_state: &GameState,
+        variations: &[Variation],
+        depth: usize,
+        text: &mut String,
+    ) {
+        for variation in variations {
+            let san = San::derive(game_state, &variation.mv);
+            let move_number = game_state.fullmove_number();
+            let number = match game_state.current_player.color {
+                Color::White => format!("{}.", move_number),
+                Color::Black => format!("{}...", move_number),
+            };
+            text.push_str(&format!("{}{} {}\n", "  ".repeat(depth), number, san));
+
+            let mut next = game_state.clone();
+            next.make_move(variation.mv.clone());
+            Self::render_level(&next, &variation.replies, depth + 1, text);
+        }
+    }
+}
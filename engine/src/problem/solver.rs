@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+
+use domain::{
+    board::Board,
+    game::{Color, GameState, Player},
+    moves::Move,
+};
+
+use crate::{
+    movegen::{legal_moves, make_move},
+    zobrist::position_key,
+};
+
+use super::{Solution, Stipulation, Variation};
+
+/// Exhaustive solver for mate problems.
+///
+/// Every move of the side to move is tried at every level, so the solution is a proof: a
+/// problem without a solution has none in the given number of moves. Results are memoised
+/// per position and remaining moves, and the last mating move is only looked for among
+/// checks, which keeps small problems fast.
+#[derive(Debug, Default)]
+pub struct Solver {
+    memo: HashMap<(u64, u32, Goal), bool>,
+    nodes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Goal {
+    Mate,
+    Helpmate,
+    Selfmate,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Positions visited by the last `solve`.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub fn solve(&mut self, game_state: &GameState, stipulation: Stipulation) -> Solution {
+        self.memo.clear();
+        self.nodes = 0;
+
+        let board = &game_state.board;
+        let color = game_state.current_player.color;
+        let variations = match stipulation {
+            Stipulation::Mate(n) => self.mate_tree(board, color, n, false),
+            Stipulation::Helpmate(n) => self.helpmate_tree(board, color, n),
+            Stipulation::Selfmate(n) => self.selfmate_tree(board, color, n),
+        };
+
+        Solution {
+            stipulation,
+            variations,
+        }
+    }
+}
+
+impl Solver {
+    fn is_mated(board: &Board, color: Color) -> bool {
+        board.is_in_check(Player::new(color)) && legal_moves(board, color).is_empty()
+    }
+
+    fn gives_check(board: &Board, m: &Move, color: Color) -> bool {
+        make_move(board, m).is_in_check(Player::new(color.opponent()))
+    }
+
+    /// The moves worth trying for `color`. On the last move of a mate only checks can work.
+    fn candidates(board: &Board, color: Color, last_move_mates: bool) -> Vec<Move> {
+        let moves = legal_moves(board, color);
+        match last_move_mates {
+            true => moves
+                .into_iter()
+                .filter(|m| Self::gives_check(board, m, color))
+                .collect(),
+            false => moves,
+        }
+    }
+
+    fn memoised(
+        &mut self,
+        board: &Board,
+        color: Color,
+        n: u32,
+        goal: Goal,
+        solve: impl FnOnce(&mut Self) -> bool,
+    ) -> bool {
+        let key = (position_key(board, color), n, goal);
+        if let Some(&result) = self.memo.get(&key) {
+            return result;
+        }
+
+        self.nodes += 1;
+        let result = solve(self);
+        self.memo.insert(key, result);
+        result
+    }
+
+    /// Whether `attacker`, to move, mates within `n` moves against any defence.
+    fn mates_in(&mut self, board: &Board, attacker: Color, n: u32) -> bool {
+        if n == 0 {
+            return false;
+        }
+
+        self.memoised(board, attacker, n, Goal::Mate, |solver| {
+            Self::candidates(board, attacker, n == 1)
+                .iter()
+                .any(|m| solver.defence_fails(&make_move(board, m), attacker.opponent(), n))
+        })
+    }
+
+    /// Whether the defender, to move after the attacker's move, loses with `n - 1` attacking
+    /// moves to come.
+    fn defence_fails(&mut self, board: &Board, defender: Color, n: u32) -> bool {
+        let defences = legal_moves(board, defender);
+        if defences.is_empty() {
+            return board.is_in_check(Player::new(defender));
+        }
+
+        n > 1
+            && defences
+                .iter()
+                .all(|d| self.mates_in(&make_move(board, d), defender.opponent(), n - 1))
+    }
+
+    /// At the root every move that mates within `n` is a key. Deeper down only the fastest
+    /// continuations are listed, so the tree stays readable.
+    fn mate_tree(
+        &mut self,
+        board: &Board,
+        attacker: Color,
+        n: u32,
+        fastest: bool,
+    ) -> Vec<Variation> {
+        let n = match fastest {
+            true => match (1..=n).find(|&k| self.mates_in(board, attacker, k)) {
+                Some(k) => k,
+                None => return vec![],
+            },
+            false => n,
+        };
+
+        Self::candidates(board, attacker, n == 1)
+            .into_iter()
+            .filter_map(|m| {
+                let next = make_move(board, &m);
+                if !self.defence_fails(&next, attacker.opponent(), n) {
+                    return None;
+                }
+
+                let replies = legal_moves(&next, attacker.opponent())
+                    .into_iter()
+                    .map(|d| Variation {
+                        replies: self.mate_tree(&make_move(&next, &d), attacker, n - 1, true),
+                        mv: d,
+                    })
+                    .collect();
+                Some(Variation { mv: m, replies })
+            })
+            .collect()
+    }
+
+    /// Whether `helper`, to move, and the other side can play so that the other side mates
+    /// `helper` with its `n`th move.
+    fn helpmate_in(&mut self, board: &Board, helper: Color, n: u32) -> bool {
+        self.memoised(board, helper, n, Goal::Helpmate, |solver| {
+            legal_moves(board, helper).iter().any(|h| {
+                let next = make_move(board, h);
+                Self::candidates(&next, helper.opponent(), n == 1)
+                    .iter()
+                    .any(|w| solver.helpmate_continues(&make_move(&next, w), helper, n))
+            })
+        })
+    }
+
+    fn helpmate_continues(&mut self, board: &Board, helper: Color, n: u32) -> bool {
+        match n {
+            1 => Self::is_mated(board, helper),
+            _ => self.helpmate_in(board, helper, n - 1),
+        }
+    }
+
+    fn helpmate_tree(&mut self, board: &Board, helper: Color, n: u32) -> Vec<Variation> {
+        if n == 0 {
+            return vec![];
+        }
+
+        legal_moves(board, helper)
+            .into_iter()
+            .filter_map(|h| {
+                let next = make_move(board, &h);
+                let replies: Vec<Variation> = Self::candidates(&next, helper.opponent(), n == 1)
+                    .into_iter()
+                    .filter_map(|w| {
+                        let after = make_move(&next, &w);
+                        if !self.helpmate_continues(&after, helper, n) {
+                            return None;
+                        }
+                        Some(Variation {
+                            replies: self.helpmate_tree(&after, helper, n - 1),
+                            mv: w,
+                        })
+                    })
+                    .collect();
+
+                (!replies.is_empty()).then_some(Variation { mv: h, replies })
+            })
+            .collect()
+    }
+
+    /// Whether `attacker`, to move, forces the other side to mate it within `n` moves.
+    fn selfmates_in(&mut self, board: &Board, attacker: Color, n: u32) -> bool {
+        if n == 0 {
+            return false;
+        }
+
+        self.memoised(board, attacker, n, Goal::Selfmate, |solver| {
+            legal_moves(board, attacker)
+                .iter()
+                .any(|m| solver.selfmate_forced(&make_move(board, m), attacker, n))
+        })
+    }
+
+    /// Whether every reply of the defender, to move, either mates the attacker or leaves
+    /// the attacker a selfmate in `n - 1`. A defender without moves has escaped.
+    fn selfmate_forced(&mut self, board: &Board, attacker: Color, n: u32) -> bool {
+        let defender = attacker.opponent();
+        let replies = Self::candidates(board, defender, false);
+
+        !replies.is_empty()
+            && replies.iter().all(|d| {
+                let after = make_move(board, d);
+                Self::is_mated(&after, attacker) || self.selfmates_in(&after, attacker, n - 1)
+            })
+    }
+
+    fn selfmate_tree(&mut self, board: &Board, attacker: Color, n: u32) -> Vec<Variation> {
+        if n == 0 {
+            return vec![];
+        }
+
+        legal_moves(board, attacker)
+            .into_iter()
+            .filter_map(|m| {
+                let next = make_move(board, &m);
+                if !self.selfmate_forced(&next, attacker, n) {
+                    return None;
+                }
+
+                let replies = legal_moves(&next, attacker.opponent())
+                    .into_iter()
+                    .map(|d| {
+                        let after = make_move(&next, &d);
+                        let continuations = match Self::is_mated(&after, attacker) {
+                            true => vec![],
+                            false => {
+                                let k = (1..n)
+                                    .find(|&k| self.selfmates_in(&after, attacker, k))
+                                    .unwrap_or(n - 1);
+                                self.selfmate_tree(&after, attacker, k)
+                            }
+                        };
+                        Variation {
+                            mv: d,
+                            replies: continuations,
+                        }
+                    })
+                    .collect();
+                Some(Variation { mv: m, replies })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::{
+        board::{BoardBuilder, Position},
+        game::San,
+        pieces::PieceKind,
+    };
+
+    use super::*;
+
+    fn problem(pieces: &[(i8, i8, PieceKind, Color)], to_move: Color) -> GameState {
+        let board = pieces
+            .iter()
+            .fold(
+                BoardBuilder::new(),
+                |builder, (row, column, kind, color)| {
+                    builder.add_piece(Position::new(*row, *column), kind.clone(), *color)
+                },
+            )
+            .build();
+        GameState::from_board(board, Player::new(to_move))
+    }
+
+    fn keys(game_state: &GameState, solution: &Solution) -> Vec<String> {
+        solution
+            .keys()
+            .into_iter()
+            .map(|m| San::derive(game_state, m).to_string())
+            .collect()
+    }
+
+    /// Black Kh8 against white Kg6 and Ra1.
+    fn rook_mate(white_king: (i8, i8)) -> GameState {
+        problem(
+            &[
+                (0, 7, PieceKind::King, Color::Black),
+                (white_king.0, white_king.1, PieceKind::King, Color::White),
+                (7, 0, PieceKind::Rook, Color::White),
+            ],
+            Color::White,
+        )
+    }
+
+    #[test]
+    fn test_mate_in_one() {
+        let game_state = rook_mate((2, 6));
+        let solution = Solver::new().solve(&game_state, Stipulation::Mate(1));
+
+        assert_eq!(keys(&game_state, &solution), vec!["Ra8#"]);
+        assert!(!solution.is_cooked());
+        assert!(solution.variations[0].replies.is_empty());
+    }
+
+    #[test]
+    fn test_second_key_is_a_cook() {
+        let game_state = problem(
+            &[
+                (0, 7, PieceKind::King, Color::Black),
+                (2, 6, PieceKind::King, Color::White),
+                (7, 0, PieceKind::Rook, Color::White),
+                (7, 1, PieceKind::Rook, Color::White),
+            ],
+            Color::White,
+        );
+        let solution = Solver::new().solve(&game_state, Stipulation::Mate(1));
+
+        let mut keys = keys(&game_state, &solution);
+        keys.sort();
+        assert_eq!(keys, vec!["Ra8#", "Rb8#"]);
+        assert!(solution.is_cooked());
+    }
+
+    #[test]
+    fn test_mate_in_two_lists_every_defence() {
+        let game_state = rook_mate((2, 5));
+        let mut solver = Solver::new();
+
+        assert!(!solver.solve(&game_state, Stipulation::Mate(1)).is_solved());
+
+        let solution = solver.solve(&game_state, Stipulation::Mate(2));
+        let keys = keys(&game_state, &solution);
+        assert!(keys.contains(&"Kg6".to_string()));
+
+        let key = solution
+            .variations
+            .iter()
+            .find(|variation| variation.mv.to == Position::new(2, 6))
+            .unwrap();
+        let defences: Vec<&Variation> = key.replies.iter().collect();
+        assert_eq!(defences.len(), 1);
+        assert_eq!(defences[0].replies.len(), 1);
+        assert!(solution.render(&game_state).contains("    2. Ra8#"));
+    }
+
+    #[test]
+    fn test_helpmate_in_one() {
+        let mut game_state = rook_mate((2, 6));
+        game_state.current_player = Player::new(Color::Black);
+        let solution = Solver::new().solve(&game_state, Stipulation::Helpmate(1));
+
+        assert_eq!(solution.render(&game_state), "1... Kg8\n  2. Ra8#\n");
+        assert!(!solution.is_cooked());
+    }
+
+    #[test]
+    fn test_selfmate_in_one() {
+        // White Kb1, Ba1, Bc1 and Rh8 against black Kb3 and a3. Rh4 takes the fourth
+        // rank from the black king, so black's only move left is a2#.
+        let game_state = problem(
+            &[
+                (7, 1, PieceKind::King, Color::White),
+                (7, 0, PieceKind::Bishop, Color::White),
+                (7, 2, PieceKind::Bishop, Color::White),
+                (0, 7, PieceKind::Rook, Color::White),
+                (5, 1, PieceKind::King, Color::Black),
+                (5, 0, PieceKind::Pawn, Color::Black),
+            ],
+            Color::White,
+        );
+        let solution = Solver::new().solve(&game_state, Stipulation::Selfmate(1));
+
+        assert_eq!(keys(&game_state, &solution), vec!["Rh4"]);
+        assert!(!solution.is_cooked());
+        assert_eq!(solution.render(&game_state), "1. Rh4\n  1... a2#\n");
+    }
+
+    #[test]
+    fn test_unsound_selfmate_has_no_solution() {
+        let game_state = rook_mate((2, 6));
+        let solution = Solver::new().solve(&game_state, Stipulation::Selfmate(1));
+
+        assert!(!solution.is_solved());
+    }
+}
@@ -0,0 +1,125 @@
+use domain::game::Annotation;
+
+use crate::{
+    search::{is_mate_score, to_uci_score},
+    uci::Score,
+};
+
+/// Win probability losses, in percentage points, from which a move counts as an
+/// inaccuracy, a mistake and a blunder.
+const INACCURACY: f64 = 5.0;
+const MISTAKE: f64 = 10.0;
+const BLUNDER: f64 = 15.0;
+
+/// How a move compares to the best move in its position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveClass {
+    Best,
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl MoveClass {
+    /// Classifies a move by the win probability it gave away.
+    pub fn from_loss(win_loss: f64) -> Self {
+        match win_loss {
+            loss if loss >= BLUNDER => MoveClass::Blunder,
+            loss if loss >= MISTAKE => MoveClass::Mistake,
+            loss if loss >= INACCURACY => MoveClass::Inaccuracy,
+            _ => MoveClass::Good,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MoveClass::Best => "Best move",
+            MoveClass::Good => "Good move",
+            MoveClass::Inaccuracy => "Inaccuracy",
+            MoveClass::Mistake => "Mistake",
+            MoveClass::Blunder => "Blunder",
+        }
+    }
+
+    /// The annotation glyph written after the move, e.g. `??` for a blunder.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            MoveClass::Best | MoveClass::Good => "",
+            MoveClass::Inaccuracy => "?!",
+            MoveClass::Mistake => "?",
+            MoveClass::Blunder => "??",
+        }
+    }
+
+    /// The PGN numeric annotation glyph matching `symbol`.
+    pub fn nag(&self) -> Option<u8> {
+        match self {
+            MoveClass::Best | MoveClass::Good => None,
+            MoveClass::Inaccuracy => Some(Annotation::DUBIOUS_MOVE),
+            MoveClass::Mistake => Some(Annotation::MISTAKE),
+            MoveClass::Blunder => Some(Annotation::BLUNDER),
+        }
+    }
+}
+
+/// The chance in percent that the side a search score belongs to wins, using the
+/// logistic curve online review tools fit to rated games.
+pub fn win_probability(score: i32) -> f64 {
+    if is_mate_score(score) {
+        return if score > 0 { 100.0 } else { 0.0 };
+    }
+    let score = score.clamp(-1000, 1000) as f64;
+    50.0 + 50.0 * (2.0 / (1.0 + (-0.00368208 * score).exp()) - 1.0)
+}
+
+/// Accuracy of a single move between 0 and 100, falling off exponentially with the win
+/// probability it lost.
+pub fn move_accuracy(win_loss: f64) -> f64 {
+    (103.1668 * (-0.04354 * win_loss.max(0.0)).exp() - 3.1669 + 1.0).clamp(0.0, 100.0)
+}
+
+/// A search score as shown to players: pawns with a sign, or moves to mate.
+pub fn eval_text(score: i32) -> String {
+    match to_uci_score(score) {
+        Score::Centipawns(cp) => format!("{:+.2}", cp as f64 / 100.0),
+        Score::Mate(moves) if moves > 0 => format!("#{}", moves),
+        Score::Mate(moves) => format!("#-{}", -moves),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::MATE;
+
+    #[test]
+    fn test_win_probability_curve() {
+        assert_eq!(win_probability(0), 50.0);
+        assert!((win_probability(100) + win_probability(-100) - 100.0).abs() < 1e-9);
+        assert!(win_probability(300) > 75.0 && win_probability(300) < 80.0);
+        assert_eq!(win_probability(MATE - 3), 100.0);
+        assert_eq!(win_probability(-(MATE - 3)), 0.0);
+    }
+
+    #[test]
+    fn test_classes_and_accuracy() {
+        assert_eq!(MoveClass::from_loss(1.0), MoveClass::Good);
+        assert_eq!(MoveClass::from_loss(7.0), MoveClass::Inaccuracy);
+        assert_eq!(MoveClass::from_loss(12.0), MoveClass::Mistake);
+        assert_eq!(MoveClass::from_loss(40.0), MoveClass::Blunder);
+        assert_eq!(MoveClass::Blunder.nag(), Some(Annotation::BLUNDER));
+
+        assert_eq!(move_accuracy(0.0), 100.0);
+        assert!(move_accuracy(10.0) < move_accuracy(5.0));
+        assert_eq!(move_accuracy(100.0), 0.0);
+    }
+
+    #[test]
+    fn test_eval_text() {
+        assert_eq!(eval_text(35), "+0.35");
+        assert_eq!(eval_text(-120), "-1.20");
+        assert_eq!(eval_text(MATE - 3), "#2");
+        assert_eq!(eval_text(-(MATE - 2)), "#-1");
+    }
+}
@@ -0,0 +1,5 @@
+pub mod classify;
+pub mod reviewer;
+
+pub use classify::*;
+pub use reviewer::*;
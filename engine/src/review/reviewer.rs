@@ -0,0 +1,282 @@
+use domain::{
+    game::{Color, EndReason, GameState, Pgn, San},
+    moves::Move,
+};
+
+use crate::search::{SearchLimits, Searcher, MATE};
+
+use super::{eval_text, move_accuracy, win_probability, MoveClass};
+
+/// At most this many moves are marked as the critical moments of a game.
+const MAX_CRITICAL_MOMENTS: usize = 3;
+/// Only moves losing at least this much win probability can be critical.
+const CRITICAL_LOSS: f64 = 10.0;
+
+/// The review of a single move. Scores are search scores from white's point of view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveReview {
+    pub ply: usize,
+    pub color: Color,
+    pub mv: Move,
+    pub san: String,
+    pub best_move: Option<Move>,
+    pub best_san: Option<String>,
+    pub eval_before: i32,
+    pub eval_after: i32,
+    /// Centipawns given away by the move, with mates counted as a thousand.
+    pub centipawn_loss: i32,
+    /// Win probability given away by the move, in percentage points.
+    pub win_loss: f64,
+    pub class: MoveClass,
+    pub accuracy: f64,
+    /// Whether the move is one of the turning points of the game.
+    pub critical: bool,
+}
+
+impl MoveReview {
+    /// The comment written for the move in an annotated PGN, empty for good moves.
+    pub fn comment(&self) -> Option<String> {
+        let mut parts = vec![];
+        if self.critical {
+            parts.push("Critical moment.".to_string());
+        }
+        if matches!(
+            self.class,
+            MoveClass::Inaccuracy | MoveClass::Mistake | MoveClass::Blunder
+        ) {
+            parts.push(format!(
+                "{} ({} -> {}).",
+                self.class.name(),
+                eval_text(self.eval_before),
+                eval_text(self.eval_after)
+            ));
+            if let Some(best) = &self.best_san {
+                parts.push(format!("{} was best.", best));
+            }
+        }
+
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameReview {
+    pub moves: Vec<MoveReview>,
+    pub depth: u32,
+}
+
+impl GameReview {
+    /// Mean accuracy of the moves `color` played, `None` if it played none.
+    pub fn accuracy(&self, color: Color) -> Option<f64> {
+        let accuracies: Vec<f64> = self
+            .moves
+            .iter()
+            .filter(|review| review.color == color)
+            .map(|review| review.accuracy)
+            .collect();
+        (!accuracies.is_empty()).then(|| accuracies.iter().sum::<f64>() / accuracies.len() as f64)
+    }
+
+    /// How many moves of `color` fell into `class`.
+    pub fn count(&self, color: Color, class: MoveClass) -> usize {
+        self.moves
+            .iter()
+            .filter(|review| review.color == color && review.class == class)
+            .count()
+    }
+
+    /// Writes the classification of every move into `pgn` as NAGs and comments. `pgn`
+    /// should hold the reviewed game, e.g. from `Pgn::from_game`.
+    pub fn annotate(&self, pgn: &mut Pgn) {
+        for review in &self.moves {
+            let annotation = pgn.annotation_mut(review.ply);
+            annotation.nags.extend(review.class.nag());
+            if let Some(comment) = review.comment() {
+                annotation.comment = Some(comment);
+            }
+        }
+
+        for color in [Color::White, Color::Black] {
+            if let Some(accuracy) = self.accuracy(color) {
+                let name = match color {
+                    Color::White => "WhiteAccuracy",
+                    Color::Black => "BlackAccuracy",
+                };
+                pgn.tags
+                    .push((name.to_string(), format!("{:.1}", accuracy)));
+            }
+        }
+    }
+}
+
+/// Reviews every move of `game_state` by searching each position of the game to `depth`.
+/// `on_progress` is called with the number of positions searched and the total.
+pub fn review_game<F>(
+    searcher: &mut Searcher,
+    game_state: &GameState,
+    depth: u32,
+    mut on_progress: F,
+) -> GameReview
+where
+    F: FnMut(usize, usize),
+{
+    let mut position = match &game_state.setup {
+        Some(fen) => GameState::from_fen(fen).unwrap_or_default(),
+        None => GameState::new(),
+    };
+    let played: Vec<Move> = game_state
+        .move_history
+        .iter()
+        .map(|record| record.mv.clone())
+        .collect();
+
+    // Searching every position once gives the score before and after each move
+    searcher.new_game();
+    let total = played.len() + 1;
+    let mut positions = Vec::with_capacity(total);
+    for (i, m) in played.iter().enumerate() {
+        positions.push((position.clone(), evaluate(searcher, &position, depth)));
+        on_progress(i + 1, total);
+        position.make_move(m.clone());
+    }
+    let last = evaluate(searcher, &position, depth);
+    on_progress(total, total);
+
+    let mut moves: Vec<MoveReview> = played
+        .iter()
+        .enumerate()
+        .map(|(ply, m)| {
+            let (before, (score, best_move)) = &positions[ply];
+            let after = positions
+                .get(ply + 1)
+                .map_or(last.0, |(_, (score, _))| *score);
+            review_move(ply, before, m, *score, best_move.clone(), -after)
+        })
+        .collect();
+
+    let mut critical: Vec<usize> = (0..moves.len())
+        .filter(|i| moves[*i].win_loss >= CRITICAL_LOSS)
+        .collect();
+    critical.sort_by(|a, b| moves[*b].win_loss.total_cmp(&moves[*a].win_loss));
+    for i in critical.into_iter().take(MAX_CRITICAL_MOMENTS) {
+        moves[i].critical = true;
+    }
+
+    GameReview { moves, depth }
+}
+
+/// The score of `position` for its side to move and the best move. Checkmates and drawn
+/// positions are scored without a search. A game lost on time or resigned is searched
+/// like any other position, as the board itself decides nothing.
+fn evaluate(searcher: &mut Searcher, position: &GameState, depth: u32) -> (i32, Option<Move>) {
+    match position.result.as_ref().map(|result| &result.end_reason) {
+        Some(EndReason::Checkmate) => (-MATE, None),
+        Some(
+            EndReason::Stalemate
+            | EndReason::FiftyMoveRule
+            | EndReason::InsufficientMaterial
+            | EndReason::ThreefoldRepetition,
+        ) => (0, None),
+        Some(EndReason::Timeout | EndReason::Resignation) | None => {
+            let result = searcher.search(position, &SearchLimits::depth(depth));
+            (result.score, result.best_move)
+        }
+    }
+}
+
+/// `before` and `after` are scores for the side making the move.
+fn review_move(
+    ply: usize,
+    position: &GameState,
+    m: &Move,
+    before: i32,
+    best_move: Option<Move>,
+    after: i32,
+) -> MoveReview {
+    let color = position.current_player.color;
+    let is_best = best_move.as_ref() == Some(m);
+    // The search may find more after the move than it saw before it
+    let after = if is_best { after.max(before) } else { after };
+
+    let win_loss = (win_probability(before) - win_probability(after)).max(0.0);
+    let clamp = |score: i32| score.clamp(-1000, 1000);
+    let class = match is_best {
+        true => MoveClass::Best,
+        false => MoveClass::from_loss(win_loss),
+    };
+    let white_view = |score: i32| match color {
+        Color::White => score,
+        Color::Black => -score,
+    };
+
+    MoveReview {
+        ply,
+        color,
+        mv: m.clone(),
+        san: San::derive(position, m).to_string(),
+        best_san: best_move
+            .as_ref()
+            .map(|best| San::derive(position, best).to_string()),
+        best_move,
+        eval_before: white_view(before),
+        eval_after: white_view(after),
+        centipawn_loss: (clamp(before) - clamp(after)).max(0),
+        win_loss,
+        class,
+        accuracy: move_accuracy(win_loss),
+        critical: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(moves: &[&str]) -> GameState {
+        let mut game_state = GameState::new();
+        for san in moves {
+            let m = San::parse(&game_state, san).unwrap();
+            game_state.make_move(m);
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_finds_the_blunder_of_a_short_game() {
+        // Fool's mate: 2. g4?? allows mate in one
+        let game_state = play(&["f3", "e5", "g4", "Qh4#"]);
+        let review = review_game(&mut Searcher::default(), &game_state, 2, |_, _| {});
+
+        assert_eq!(review.moves.len(), 4);
+        let blunder = &review.moves[2];
+        assert_eq!(blunder.san, "g4");
+        assert_eq!(blunder.class, MoveClass::Blunder);
+        assert!(blunder.critical);
+        assert_eq!(review.moves[3].class, MoveClass::Best);
+        assert_eq!(review.count(Color::White, MoveClass::Blunder), 1);
+        assert!(review.accuracy(Color::White) < review.accuracy(Color::Black));
+
+        let mut pgn = Pgn::from_game(&game_state, vec![], Some("0-1"));
+        review.annotate(&mut pgn);
+        let text = pgn.to_string();
+        assert!(text.contains("2. g4 $4 {Critical moment. Blunder"));
+        assert!(text.contains("[BlackAccuracy \""));
+    }
+
+    #[test]
+    fn test_only_checkmate_is_scored_as_mate() {
+        let mut searcher = Searcher::default();
+        let mated = play(&["f3", "e5", "g4", "Qh4#"]);
+        assert_eq!(evaluate(&mut searcher, &mated, 2), (-MATE, None));
+
+        let mut lost_on_time = play(&["e4", "e5"]);
+        lost_on_time.lose_on_time();
+        let (score, best_move) = evaluate(&mut searcher, &lost_on_time, 2);
+        assert!(score.abs() < 200);
+        assert!(best_move.is_some());
+
+        let mut resigned = play(&["e4", "e5"]);
+        resigned.resign();
+        assert_eq!(evaluate(&mut searcher, &resigned, 2).0, score);
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use domain::{
+    game::{Color, GameState, San},
+    moves::Move,
+};
+
+use crate::uci::ScoreBound;
+
+/// One MultiPV line as reported while the search runs. Every completed line of every
+/// iteration is reported, so the same `multipv` slot is refreshed as the depth grows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisLine {
+    /// Rank of the line, starting at 1 for the best.
+    pub multipv: usize,
+    pub depth: u32,
+    pub seldepth: u32,
+    pub score: i32,
+    pub bound: ScoreBound,
+    pub pv: Vec<Move>,
+    /// Nodes searched by all threads when the line was found.
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+impl AnalysisLine {
+    pub fn nps(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1);
+        (self.nodes as u128 * 1_000_000 / micros) as u64
+    }
+
+    /// The principal variation in SAN, played out from `game_state`.
+    pub fn san(&self, game_state: &GameState) -> Vec<San> {
+        san_line(game_state, &self.pv)
+    }
+
+    /// The principal variation with move numbers, e.g. `12... Nf6 13. Bg5 Be7`.
+    pub fn san_text(&self, game_state: &GameState) -> String {
+        numbered_san_line(game_state, &self.pv)
+    }
+}
+
+/// Renders `moves` in SAN, each relative to the position it is played from. Rendering
+/// stops at the first move that is not legal in its position.
+pub fn san_line(game_state: &GameState, moves: &[Move]) -> Vec<San> {
+    let mut game_state = game_state.clone();
+    let mut line = Vec::with_capacity(moves.len());
+
+    for m in moves {
+        let is_legal = game_state
+            .legal_moves_for_piece(m.from)
+            .is_some_and(|(_, legal)| legal.iter().any(|legal| legal.to == m.to));
+        if !is_legal {
+            break;
+        }
+        line.push(San::derive(&game_state, m));
+        game_state.make_move(m.clone());
+    }
+
+    line
+}
+
+/// Like `san_line`, with a move number before every white move and before the first move
+/// when black starts the line.
+pub fn numbered_san_line(game_state: &GameState, moves: &[Move]) -> String {
+    let first_move = game_state.fullmove_number();
+    let black_starts = game_state.current_player.color == Color::Black;
+
+    san_line(game_state, moves)
+        .into_iter()
+        .enumerate()
+        .map(|(i, san)| {
+            let move_number = first_move + (i + usize::from(black_starts)) / 2;
+            let white_moves = (i % 2 == 0) != black_starts;
+            match (white_moves, i) {
+                (true, _) => format!("{}. {}", move_number, san),
+                (false, 0) => format!("{}... {}", move_number, san),
+                (false, _) => san.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(moves: &[&str]) -> (GameState, Vec<Move>) {
+        let mut game_state = GameState::new();
+        let mut played = vec![];
+        for san in moves {
+            let m = San::parse(&game_state, san).unwrap();
+            played.push(m.clone());
+            game_state.make_move(m);
+        }
+        (game_state, played)
+    }
+
+    #[test]
+    fn test_numbered_line_from_either_side() {
+        let (_, moves) = play(&["e4", "e5", "Nf3", "Nc6"]);
+        assert_eq!(
+            numbered_san_line(&GameState::new(), &moves),
+            "1. e4 e5 2. Nf3 Nc6"
+        );
+
+        let (after_e4, _) = play(&["e4"]);
+        assert_eq!(
+            numbered_san_line(&after_e4, &moves[1..]),
+            "1... e5 2. Nf3 Nc6"
+        );
+    }
+
+    #[test]
+    fn test_line_stops_at_illegal_move() {
+        let (_, moves) = play(&["e4", "e5"]);
+        let san = san_line(&GameState::new(), &[moves[0].clone(), moves[0].clone()]);
+        assert_eq!(san.len(), 1);
+    }
+}
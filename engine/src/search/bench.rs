@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use domain::game::{GameState, San};
+
+use super::{SearchLimits, Searcher};
+
+/// Openings and middlegames, as SAN moves from the start position.
+const BENCH_LINES: [&str; 8] = [
+    "",
+    "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7",
+    "d4 Nf6 c4 e6 Nc3 Bb4 e3 O-O Bd3 d5",
+    "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6 Be3 e5 Nb3 Be6",
+    "d4 d5 c4 c6 Nf3 Nf6 Nc3 dxc4 a4 Bf5 e3 e6 Bxc4 Bb4 O-O O-O",
+    "e4 e6 d4 d5 Nc3 Bb4 e5 c5 a3 Bxc3+ bxc3 Ne7 Qg4 Qc7",
+    "c4 e5 Nc3 Nf6 Nf3 Nc6 g3 d5 cxd5 Nxd5 Bg2 Nb6 O-O Be7 d3 O-O",
+    "e4 e5 Nf3 Nc6 Bc4 Bc5 c3 Nf6 d4 exd4 cxd4 Bb4+ Bd2 Bxd2+ Nbxd2 d5 exd5 Nxd5",
+];
+
+/// Totals of a fixed depth search over every bench position. With a fixed depth the
+/// elapsed time is the time-to-depth, which is what extra threads should bring down.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BenchReport {
+    pub positions: usize,
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+impl BenchReport {
+    pub fn nps(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1);
+        (self.nodes as u128 * 1_000_000 / micros) as u64
+    }
+}
+
+pub fn bench_positions() -> Vec<GameState> {
+    BENCH_LINES
+        .iter()
+        .map(|line| {
+            line.split_whitespace()
+                .fold(GameState::new(), |mut game_state, san| {
+                    let m = San::parse(&game_state, san).expect("Bench lines are legal");
+                    game_state.make_move(m);
+                    game_state
+                })
+        })
+        .collect()
+}
+
+/// Searches every bench position to `depth` with a fresh table each time.
+pub fn bench(searcher: &mut Searcher, depth: u32) -> BenchReport {
+    let limits = SearchLimits::depth(depth);
+
+    bench_positions()
+        .iter()
+        .fold(BenchReport::default(), |mut report, game_state| {
+            searcher.new_game();
+            let result = searcher.search(game_state, &limits);
+
+            report.positions += 1;
+            report.nodes += result.nodes;
+            report.elapsed += result.elapsed;
+            report
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_thread_bench_is_deterministic() {
+        let mut searcher = Searcher::default();
+        let first = bench(&mut searcher, 1);
+        let second = bench(&mut searcher, 1);
+
+        assert_eq!(first.positions, BENCH_LINES.len());
+        assert_eq!(first.nodes, second.nodes);
+    }
+}
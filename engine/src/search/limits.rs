@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use domain::game::Color;
+
+use crate::uci::GoParams;
+
+use super::Clock;
+
+/// When a search should stop. Unset limits do not apply; with none set the search runs
+/// until it is stopped or reaches the maximum depth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+    /// The clock of the side to move, from which the time manager budgets the move.
+    pub clock: Option<Clock>,
+    /// How many best lines to search for.
+    pub multipv: usize,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            depth: None,
+            nodes: None,
+            movetime: None,
+            clock: None,
+            multipv: 1,
+        }
+    }
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u32) -> Self {
+        Self {
+            depth: Some(depth),
+            ..Self::default()
+        }
+    }
+
+    /// The limits of a UCI `go` command for `side_to_move`. `infinite` leaves the search
+    /// unlimited, and a clock is only used when the side to move has time on it.
+    pub fn from_go(params: &GoParams, side_to_move: Color) -> Self {
+        if params.infinite {
+            return Self::default();
+        }
+
+        let (remaining, increment) = match side_to_move {
+            Color::White => (params.wtime, params.winc),
+            Color::Black => (params.btime, params.binc),
+        };
+
+        Self {
+            depth: params.depth,
+            nodes: params.nodes,
+            movetime: params.movetime,
+            clock: remaining.map(|remaining| Clock {
+                remaining,
+                increment: increment.unwrap_or_default(),
+                movestogo: params.movestogo,
+            }),
+            multipv: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_go_picks_the_side_to_move() {
+        let params = GoParams {
+            wtime: Some(Duration::from_secs(60)),
+            btime: Some(Duration::from_secs(30)),
+            binc: Some(Duration::from_secs(1)),
+            movestogo: Some(20),
+            ..GoParams::default()
+        };
+
+        let limits = SearchLimits::from_go(&params, Color::Black);
+        assert_eq!(
+            limits.clock,
+            Some(Clock {
+                remaining: Duration::from_secs(30),
+                increment: Duration::from_secs(1),
+                movestogo: Some(20),
+            })
+        );
+
+        let infinite = GoParams {
+            infinite: true,
+            ..params
+        };
+        assert_eq!(
+            SearchLimits::from_go(&infinite, Color::White),
+            SearchLimits::default()
+        );
+    }
+}
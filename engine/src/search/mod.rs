@@ -0,0 +1,17 @@
+pub mod analysis;
+pub mod bench;
+pub mod limits;
+pub mod score;
+pub mod searcher;
+pub mod time;
+pub mod tt;
+mod worker;
+
+pub use analysis::*;
+pub use bench::*;
+pub use limits::*;
+pub use score::*;
+pub use searcher::*;
+pub use time::*;
+pub use tt::*;
+use worker::*;
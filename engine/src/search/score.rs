@@ -0,0 +1,58 @@
+use crate::uci::Score;
+
+pub const MAX_PLY: usize = 128;
+pub const INFINITY: i32 = 32_001;
+pub const MATE: i32 = 32_000;
+/// Scores beyond this bound are mates, found at most `MAX_PLY` plies away.
+pub const MATE_BOUND: i32 = MATE - MAX_PLY as i32;
+/// Tablebase wins rank below every mate but above any static evaluation.
+pub const TB_WIN: i32 = 20_000;
+
+pub fn mated_in(ply: usize) -> i32 {
+    -MATE + ply as i32
+}
+
+pub fn is_mate_score(score: i32) -> bool {
+    score.abs() >= MATE_BOUND
+}
+
+/// Converts a search score to centipawns or moves to mate, as reported over UCI.
+pub fn to_uci_score(score: i32) -> Score {
+    match score {
+        score if score >= MATE_BOUND => Score::Mate((MATE - score + 1) / 2),
+        score if score <= -MATE_BOUND => Score::Mate(-(MATE + score) / 2),
+        score => Score::Centipawns(score),
+    }
+}
+
+/// Mate scores are stored relative to the node that found them, so they stay correct when
+/// the position is reached again at another ply.
+pub(super) fn score_to_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score >= MATE_BOUND => score + ply as i32,
+        score if score <= -MATE_BOUND => score - ply as i32,
+        score => score,
+    }
+}
+
+pub(super) fn score_from_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score >= MATE_BOUND => score - ply as i32,
+        score if score <= -MATE_BOUND => score + ply as i32,
+        score => score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mate_scores_count_moves() {
+        assert_eq!(to_uci_score(MATE - 1), Score::Mate(1));
+        assert_eq!(to_uci_score(MATE - 3), Score::Mate(2));
+        assert_eq!(to_uci_score(mated_in(2)), Score::Mate(-1));
+        assert_eq!(to_uci_score(35), Score::Centipawns(35));
+        assert_eq!(score_from_tt(score_to_tt(MATE - 5, 3), 3), MATE - 5);
+    }
+}
@@ -0,0 +1,434 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use domain::{game::GameState, moves::Move};
+
+use crate::{
+    error::EngineError,
+    eval::EvalParams,
+    tablebase::Tablebase,
+    uci::{OptionKind, UciOption},
+};
+
+use super::{AnalysisLine, SearchLimits, Shared, TranspositionTable, Worker};
+
+const DEFAULT_HASH_MB: usize = 16;
+const MAX_HASH_MB: usize = 4096;
+const MAX_THREADS: usize = 256;
+
+/// One of the best lines found at the root. The first move of `pv` is the candidate move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PvLine {
+    pub score: i32,
+    pub pv: Vec<Move>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u32,
+    pub seldepth: u32,
+    /// Nodes searched by all threads.
+    pub nodes: u64,
+    pub elapsed: Duration,
+    /// The best lines of the last completed iteration, best first.
+    pub lines: Vec<PvLine>,
+}
+
+impl SearchResult {
+    pub fn nps(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1);
+        (self.nodes as u128 * 1_000_000 / micros) as u64
+    }
+}
+
+/// Iterative deepening alpha-beta search over the domain board, with a quiescence search,
+/// a transposition table and optional tablebase probing.
+///
+/// With more than one thread the search is a Lazy SMP: every thread searches the same root
+/// and they only cooperate through the shared transposition table.
+pub struct Searcher {
+    tt: Arc<TranspositionTable>,
+    hash_mb: usize,
+    threads: usize,
+    params: EvalParams,
+    tablebase: Option<Arc<Tablebase>>,
+    stop: Arc<AtomicBool>,
+    eval_noise: i32,
+    noise_seed: u64,
+}
+
+impl Default for Searcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASH_MB)
+    }
+}
+
+impl Searcher {
+    pub fn new(hash_mb: usize) -> Self {
+        Self {
+            tt: Arc::new(TranspositionTable::new(hash_mb)),
+            hash_mb,
+            threads: 1,
+            params: EvalParams::default(),
+            tablebase: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            eval_noise: 0,
+            noise_seed: 0,
+        }
+    }
+
+    pub fn set_params(&mut self, params: EvalParams) {
+        self.params = params;
+        self.tt.clear();
+    }
+
+    pub fn set_tablebase(&mut self, tablebase: Option<Arc<Tablebase>>) {
+        self.tablebase = tablebase;
+    }
+
+    /// Adds a pseudo-random offset of up to `amplitude` centipawns to every evaluation.
+    /// The offset only depends on the position and `seed`, so transpositions agree.
+    /// Changing either clears the transposition table, whose scores carry the old noise.
+    pub fn set_eval_noise(&mut self, amplitude: i32, seed: u64) {
+        let amplitude = amplitude.max(0);
+        if (amplitude, seed) == (self.eval_noise, self.noise_seed) {
+            return;
+        }
+        self.eval_noise = amplitude;
+        self.noise_seed = seed;
+        self.tt.clear();
+    }
+
+    pub fn eval_noise(&self) -> i32 {
+        self.eval_noise
+    }
+
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.clamp(1, MAX_THREADS);
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Resizes the transposition table, which also clears it.
+    pub fn set_hash(&mut self, hash_mb: usize) {
+        self.hash_mb = hash_mb.clamp(1, MAX_HASH_MB);
+        self.tt = Arc::new(TranspositionTable::new(self.hash_mb));
+    }
+
+    /// The options this searcher understands, as a UCI engine would announce them.
+    pub fn options(&self) -> Vec<UciOption> {
+        vec![
+            UciOption {
+                name: "Hash".to_string(),
+                kind: OptionKind::Spin {
+                    default: DEFAULT_HASH_MB as i64,
+                    min: 1,
+                    max: MAX_HASH_MB as i64,
+                },
+            },
+            UciOption {
+                name: "Threads".to_string(),
+                kind: OptionKind::Spin {
+                    default: 1,
+                    min: 1,
+                    max: MAX_THREADS as i64,
+                },
+            },
+            UciOption {
+                name: "EvalFile".to_string(),
+                kind: OptionKind::String {
+                    default: String::new(),
+                },
+            },
+        ]
+    }
+
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), EngineError> {
+        let option = self
+            .options()
+            .into_iter()
+            .find(|option| option.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| EngineError::UnknownOption(format!("Unknown option: {}", name)))?;
+        let value = option.validate(value)?;
+
+        match option.name.as_str() {
+            "Hash" => self.set_hash(value.parse().unwrap_or_default()),
+            "Threads" => self.set_threads(value.parse().unwrap_or_default()),
+            // An empty path goes back to the built-in parameters
+            _ => match value.is_empty() {
+                true => self.set_params(EvalParams::default()),
+                false => self.set_params(EvalParams::load(&value)?),
+            },
+        }
+        Ok(())
+    }
+
+    /// A flag that stops the running search when set from another thread.
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    pub fn new_game(&mut self) {
+        self.tt.clear();
+    }
+
+    pub fn hashfull(&self) -> u32 {
+        self.tt.hashfull()
+    }
+
+    pub fn search(&mut self, game_state: &GameState, limits: &SearchLimits) -> SearchResult {
+        self.search_with(game_state, limits, |_| {})
+    }
+
+    /// Searches like `search`, handing every line to `on_line` as soon as it is complete.
+    pub fn search_with<F>(
+        &mut self,
+        game_state: &GameState,
+        limits: &SearchLimits,
+        mut on_line: F,
+    ) -> SearchResult
+    where
+        F: FnMut(&AnalysisLine),
+    {
+        self.stop.store(false, Ordering::Relaxed);
+
+        let shared = Shared {
+            tt: &self.tt,
+            params: &self.params,
+            tablebase: self.tablebase.as_deref(),
+            stop: &self.stop,
+            nodes: AtomicU64::new(0),
+            limits,
+            start: Instant::now(),
+            eval_noise: self.eval_noise,
+            noise_seed: self.noise_seed,
+        };
+
+        let board = &game_state.board;
+        let color = game_state.current_player.color;
+        let halfmove = game_state.non_capture_or_pawn_move_counter as u32;
+
+        let mut result = thread::scope(|scope| {
+            let shared = &shared;
+            let helpers: Vec<_> = (1..self.threads)
+                .map(|id| {
+                    scope.spawn(move || {
+                        Worker::new(id, shared).iterate(board, color, halfmove, &mut |_| {})
+                    })
+                })
+                .collect();
+
+            let result = Worker::new(0, shared).iterate(board, color, halfmove, &mut on_line);
+            shared.stop.store(true, Ordering::Relaxed);
+            for helper in helpers {
+                helper.join().expect("Search thread panicked");
+            }
+            result
+        });
+
+        result.nodes = shared.nodes.load(Ordering::Relaxed);
+        result.elapsed = shared.start.elapsed();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::game::San;
+
+    use super::*;
+    use crate::search::{to_uci_score, Clock, MATE};
+    use crate::uci::Score;
+
+    fn play(moves: &[&str]) -> GameState {
+        let mut game_state = GameState::new();
+        for san in moves {
+            let m = San::parse(&game_state, san).unwrap();
+            game_state.make_move(m);
+        }
+        game_state
+    }
+
+    #[test]
+    fn test_finds_mate_in_one() {
+        // Scholar's mate: Qxf7#
+        let game_state = play(&["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6"]);
+        let mut searcher = Searcher::default();
+        let result = searcher.search(&game_state, &SearchLimits::depth(2));
+
+        let best = result.best_move.unwrap();
+        assert_eq!(San::derive(&game_state, &best).to_string(), "Qxf7#");
+        assert_eq!(result.score, MATE - 1);
+        assert_eq!(to_uci_score(result.score), Score::Mate(1));
+    }
+
+    #[test]
+    fn test_takes_hanging_queen() {
+        let game_state = play(&["e4", "Nf6", "Qh5"]);
+        let mut searcher = Searcher::default();
+        let result = searcher.search(&game_state, &SearchLimits::depth(3));
+
+        let best = result.best_move.unwrap();
+        assert_eq!(San::derive(&game_state, &best).to_string(), "Nxh5");
+        assert!(result.score > 500);
+    }
+
+    #[test]
+    fn test_multipv_lines_are_distinct_and_sorted() {
+        let game_state = GameState::new();
+        let mut searcher = Searcher::default();
+        let limits = SearchLimits {
+            depth: Some(2),
+            multipv: 3,
+            ..SearchLimits::default()
+        };
+        let result = searcher.search(&game_state, &limits);
+
+        assert_eq!(result.lines.len(), 3);
+        assert!(result.lines.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_ne!(result.lines[0].pv[0], result.lines[1].pv[0]);
+        assert_ne!(result.lines[1].pv[0], result.lines[2].pv[0]);
+        assert_eq!(result.best_move.as_ref(), Some(&result.lines[0].pv[0]));
+    }
+
+    #[test]
+    fn test_node_limit_still_returns_a_move() {
+        let mut searcher = Searcher::default();
+        let limits = SearchLimits {
+            nodes: Some(1),
+            ..SearchLimits::default()
+        };
+        let result = searcher.search(&GameState::new(), &limits);
+
+        assert!(result.best_move.is_some());
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn test_unchanged_eval_noise_keeps_the_hash_table() {
+        let mut searcher = Searcher::new(1);
+        let limits = SearchLimits {
+            nodes: Some(20_000),
+            ..SearchLimits::default()
+        };
+        searcher.set_eval_noise(50, 7);
+        searcher.search(&GameState::new(), &limits);
+        assert!(searcher.tt.hashfull() > 0);
+
+        searcher.set_eval_noise(50, 7);
+        assert!(searcher.tt.hashfull() > 0);
+
+        searcher.set_eval_noise(50, 8);
+        assert_eq!(searcher.tt.hashfull(), 0);
+    }
+
+    #[test]
+    fn test_single_thread_is_deterministic() {
+        let game_state = play(&["e4", "e5", "Nf3", "Nc6"]);
+        let limits = SearchLimits {
+            nodes: Some(2_000),
+            ..SearchLimits::default()
+        };
+
+        let first = Searcher::default().search(&game_state, &limits);
+        let second = Searcher::default().search(&game_state, &limits);
+        assert_eq!(first.best_move, second.best_move);
+        assert_eq!(first.lines, second.lines);
+        assert_eq!(first.nodes, second.nodes);
+    }
+
+    #[test]
+    fn test_helper_threads_add_nodes_and_agree_on_mate() {
+        let game_state = play(&["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6"]);
+        let mut searcher = Searcher::default();
+        searcher.set_option("threads", "4").unwrap();
+        assert_eq!(searcher.threads(), 4);
+
+        let result = searcher.search(&game_state, &SearchLimits::depth(3));
+        let best = result.best_move.unwrap();
+        assert_eq!(San::derive(&game_state, &best).to_string(), "Qxf7#");
+        assert!(result.nodes > 0);
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_invalid_options() {
+        let mut searcher = Searcher::default();
+        assert!(searcher.set_option("Threads", "0").is_err());
+        assert!(searcher.set_option("Contempt", "10").is_err());
+        assert!(searcher.set_option("Hash", "32").is_ok());
+        assert!(searcher
+            .set_option("EvalFile", "/nonexistent/params.txt")
+            .is_err());
+        assert!(searcher.set_option("EvalFile", "").is_ok());
+    }
+
+    #[test]
+    fn test_clock_plays_forced_reply_at_once() {
+        let game_state = play(&["e4", "f5", "Qh5+"]);
+        let limits = SearchLimits {
+            clock: Some(Clock {
+                remaining: Duration::from_secs(600),
+                ..Clock::default()
+            }),
+            ..SearchLimits::default()
+        };
+        let result = Searcher::default().search(&game_state, &limits);
+
+        assert_eq!(result.depth, 1);
+        let best = result.best_move.unwrap();
+        assert_eq!(San::derive(&game_state, &best).to_string(), "g6");
+    }
+
+    #[test]
+    fn test_clock_bounds_the_search() {
+        let limits = SearchLimits {
+            clock: Some(Clock {
+                remaining: Duration::from_secs(2),
+                ..Clock::default()
+            }),
+            ..SearchLimits::default()
+        };
+        let result = Searcher::default().search(&GameState::new(), &limits);
+
+        assert!(result.best_move.is_some());
+        assert!(result.elapsed < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_streams_every_line_of_every_iteration() {
+        let game_state = play(&["e4", "e5"]);
+        let limits = SearchLimits {
+            depth: Some(3),
+            multipv: 2,
+            ..SearchLimits::default()
+        };
+
+        let mut streamed = vec![];
+        let result = Searcher::default()
+            .search_with(&game_state, &limits, |line| streamed.push(line.clone()));
+
+        assert_eq!(streamed.len(), 6);
+        assert_eq!(
+            streamed
+                .iter()
+                .map(|l| (l.depth, l.multipv))
+                .collect::<Vec<_>>(),
+            vec![(1, 1), (1, 2), (2, 1), (2, 2), (3, 1), (3, 2)]
+        );
+        assert!(streamed.windows(2).all(|w| w[0].nodes <= w[1].nodes));
+
+        let last = streamed.last().unwrap();
+        assert_eq!(Some(&last.pv), result.lines.iter().map(|l| &l.pv).nth(1));
+        assert!(last.san_text(&game_state).starts_with("2. "));
+    }
+}
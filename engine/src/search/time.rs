@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use domain::moves::Move;
+
+/// Moves left to plan for when the time control does not say.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+/// Never plan for more moves than this, even when the time control says so.
+const MAX_MOVES_TO_GO: u32 = 50;
+/// Kept in reserve for communication and scheduling delays.
+const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+
+/// The clock of the side to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Clock {
+    pub remaining: Duration,
+    pub increment: Duration,
+    /// Moves until the next time control, if the time control has one.
+    pub movestogo: Option<u32>,
+}
+
+/// Splits the time left on the clock into budgets for one move.
+///
+/// The soft budget is what a move normally gets: no new iteration starts after it runs out.
+/// It is stretched while the best move keeps changing or the score is falling, and shrunk
+/// when the search has settled. The hard budget aborts the search mid-iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeManager {
+    soft: Duration,
+    hard: Duration,
+    stable_iterations: u32,
+    previous_best: Option<Move>,
+    previous_score: Option<i32>,
+    scale: f64,
+}
+
+impl TimeManager {
+    pub fn new(clock: &Clock) -> Self {
+        let available = clock.remaining.saturating_sub(MOVE_OVERHEAD);
+        let moves_to_go = clock
+            .movestogo
+            .unwrap_or(DEFAULT_MOVES_TO_GO)
+            .clamp(1, MAX_MOVES_TO_GO);
+
+        let hard = available.mul_f64(if moves_to_go == 1 { 0.9 } else { 0.5 });
+        let soft = (available / moves_to_go + clock.increment.mul_f64(0.75)).min(hard / 3);
+
+        Self {
+            soft,
+            hard,
+            stable_iterations: 0,
+            previous_best: None,
+            previous_score: None,
+            scale: 1.0,
+        }
+    }
+
+    pub fn soft(&self) -> Duration {
+        self.soft.mul_f64(self.scale).min(self.hard)
+    }
+
+    pub fn hard(&self) -> Duration {
+        self.hard
+    }
+
+    /// Records the best move and score of a completed iteration.
+    pub fn update(&mut self, best_move: &Move, score: i32) {
+        let changed = self
+            .previous_best
+            .as_ref()
+            .is_some_and(|previous| previous != best_move);
+        self.stable_iterations = if changed {
+            0
+        } else {
+            self.stable_iterations + 1
+        };
+
+        let instability = if changed {
+            1.8
+        } else {
+            (1.2 - 0.1 * self.stable_iterations as f64).max(0.6)
+        };
+        let drop = self
+            .previous_score
+            .map_or(0, |previous| (previous - score).clamp(0, 100));
+        let falling = 1.0 + drop as f64 / 100.0;
+
+        self.scale = instability * falling;
+        self.previous_best = Some(best_move.clone());
+        self.previous_score = Some(score);
+    }
+
+    /// Whether another iteration is worth starting after `elapsed`.
+    pub fn should_stop(&self, elapsed: Duration) -> bool {
+        elapsed >= self.soft()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::{board::Position, moves::MoveType};
+
+    use super::*;
+
+    fn mv(from: (i8, i8), to: (i8, i8)) -> Move {
+        Move::new(MoveType::Normal, Position::from(from), Position::from(to))
+    }
+
+    fn clock(secs: u64, inc_ms: u64, movestogo: Option<u32>) -> Clock {
+        Clock {
+            remaining: Duration::from_secs(secs),
+            increment: Duration::from_millis(inc_ms),
+            movestogo,
+        }
+    }
+
+    #[test]
+    fn test_budgets_fit_the_clock() {
+        let tm = TimeManager::new(&clock(60, 0, None));
+        assert!(tm.soft() > Duration::from_millis(1500));
+        assert!(tm.soft() < Duration::from_millis(2500));
+        assert!(tm.hard() > tm.soft());
+        assert!(tm.hard() < Duration::from_secs(60));
+
+        let last_move = TimeManager::new(&clock(10, 0, Some(1)));
+        assert!(last_move.soft() > Duration::from_secs(2));
+        assert!(last_move.hard() < Duration::from_secs(10));
+
+        let flagging = TimeManager::new(&clock(0, 0, None));
+        assert_eq!(flagging.hard(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_increment_adds_time() {
+        let without = TimeManager::new(&clock(60, 0, None));
+        let with = TimeManager::new(&clock(60, 2000, None));
+        assert!(with.soft() > without.soft());
+    }
+
+    #[test]
+    fn test_instability_extends_and_stability_shrinks() {
+        let mut tm = TimeManager::new(&clock(60, 0, None));
+        let base = tm.soft();
+
+        let (e4, d4) = (mv((6, 4), (4, 4)), mv((6, 3), (4, 3)));
+        tm.update(&e4, 30);
+        tm.update(&d4, 30);
+        assert!(tm.soft() > base);
+
+        for _ in 0..8 {
+            tm.update(&d4, 30);
+        }
+        assert!(tm.soft() < base);
+
+        tm.update(&d4, -50);
+        let dropped = tm.soft();
+        tm.update(&d4, -50);
+        assert!(dropped > tm.soft());
+    }
+}
@@ -0,0 +1,181 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use domain::{
+    board::Position,
+    moves::{Move, MoveType},
+    pieces::PromotionPiece,
+};
+
+use crate::uci::ScoreBound;
+
+const OCCUPIED: u64 = 1 << 26;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TtEntry {
+    pub key: u64,
+    pub depth: u32,
+    pub score: i32,
+    pub bound: ScoreBound,
+    pub best_move: Option<Move>,
+}
+
+/// A fixed size hash table of search results, indexed by Zobrist key and shared between
+/// search threads. Newer entries always replace older ones.
+///
+/// Entries are packed into a single word and stored next to `key ^ data`, so a slot torn
+/// by two threads writing at once fails the key check instead of returning a mixed entry.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    checked_key: AtomicU64,
+    data: AtomicU64,
+}
+
+impl TranspositionTable {
+    pub fn new(size_mb: usize) -> Self {
+        let bytes = size_mb.max(1) * 1024 * 1024;
+        let count = (bytes / std::mem::size_of::<Slot>()).max(1);
+        // Round down to a power of two so the key can be masked
+        let count = 1 << (usize::BITS - 1 - count.leading_zeros());
+
+        Self {
+            slots: (0..count).map(|_| Slot::default()).collect(),
+        }
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TtEntry> {
+        let slot = &self.slots[self.index(key)];
+        let data = slot.data.load(Ordering::Relaxed);
+        let checked_key = slot.checked_key.load(Ordering::Relaxed);
+
+        if data & OCCUPIED == 0 || checked_key ^ data != key {
+            return None;
+        }
+
+        Some(TtEntry {
+            key,
+            depth: ((data >> 16) & 0xFF) as u32,
+            score: (data >> 32) as u16 as i16 as i32,
+            bound: match (data >> 24) & 0b11 {
+                1 => ScoreBound::Lower,
+                2 => ScoreBound::Upper,
+                _ => ScoreBound::Exact,
+            },
+            best_move: decode_move(data as u16),
+        })
+    }
+
+    pub fn store(&self, entry: TtEntry) {
+        let bound = match entry.bound {
+            ScoreBound::Exact => 0,
+            ScoreBound::Lower => 1,
+            ScoreBound::Upper => 2,
+        };
+        let data = entry.best_move.as_ref().map_or(0, encode_move) as u64
+            | (entry.depth.min(0xFF) as u64) << 16
+            | bound << 24
+            | OCCUPIED
+            | (entry.score as i16 as u16 as u64) << 32;
+
+        let slot = &self.slots[self.index(entry.key)];
+        slot.checked_key.store(entry.key ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.checked_key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Occupancy in permille, sampled from the first thousand slots.
+    pub fn hashfull(&self) -> u32 {
+        let sample = self.slots.len().min(1000);
+        let used = self.slots[..sample]
+            .iter()
+            .filter(|slot| slot.data.load(Ordering::Relaxed) & OCCUPIED != 0)
+            .count();
+        (used * 1000 / sample) as u32
+    }
+}
+
+impl TranspositionTable {
+    fn index(&self, key: u64) -> usize {
+        (key as usize) & (self.slots.len() - 1)
+    }
+}
+
+/// Packs a move into 16 bits: origin and target square, then the move type. A zero move
+/// type means there is no move.
+fn encode_move(m: &Move) -> u16 {
+    let square = |pos: &Position| (8 * pos.row + pos.column) as u16;
+    let kind = match &m.move_type {
+        MoveType::Normal => 1,
+        MoveType::ShortCastle => 2,
+        MoveType::LongCastle => 3,
+        MoveType::DoublePawn => 4,
+        MoveType::EnPassant => 5,
+        MoveType::Promotion(PromotionPiece::Queen) => 6,
+        MoveType::Promotion(PromotionPiece::Rook) => 7,
+        MoveType::Promotion(PromotionPiece::Bishop) => 8,
+        MoveType::Promotion(PromotionPiece::Knight) => 9,
+    };
+
+    square(&m.from) | square(&m.to) << 6 | kind << 12
+}
+
+fn decode_move(code: u16) -> Option<Move> {
+    let position = |square: u16| Position::new((square / 8) as i8, (square % 8) as i8);
+    let move_type = match code >> 12 {
+        1 => MoveType::Normal,
+        2 => MoveType::ShortCastle,
+        3 => MoveType::LongCastle,
+        4 => MoveType::DoublePawn,
+        5 => MoveType::EnPassant,
+        6 => MoveType::Promotion(PromotionPiece::Queen),
+        7 => MoveType::Promotion(PromotionPiece::Rook),
+        8 => MoveType::Promotion(PromotionPiece::Bishop),
+        9 => MoveType::Promotion(PromotionPiece::Knight),
+        _ => return None,
+    };
+
+    Some(Move::new(
+        move_type,
+        position(code & 0x3F),
+        position((code >> 6) & 0x3F),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::MATE;
+
+    #[test]
+    fn test_entries_round_trip() {
+        let tt = TranspositionTable::new(1);
+        let entry = TtEntry {
+            key: 0xDEAD_BEEF_1234_5678,
+            depth: 7,
+            score: -(MATE - 5),
+            bound: ScoreBound::Upper,
+            best_move: Some(Move::new(
+                MoveType::Promotion(PromotionPiece::Knight),
+                Position::new(1, 4),
+                Position::new(0, 5),
+            )),
+        };
+
+        tt.store(entry.clone());
+        assert_eq!(tt.probe(entry.key), Some(entry.clone()));
+        assert_eq!(tt.probe(entry.key ^ 1 << 40), None);
+
+        tt.clear();
+        assert_eq!(tt.probe(entry.key), None);
+    }
+}
@@ -0,0 +1,585 @@
+use std::{
+    cmp::Reverse,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use domain::{
+    board::{Board, Position},
+    game::{Color, Player},
+    moves::{Move, MoveType},
+    pieces::{PieceType, PromotionPiece},
+};
+
+use crate::{
+    eval::{evaluate, EvalParams},
+    movegen::{is_capture, is_zeroing, legal_moves, make_move},
+    tablebase::{Tablebase, Wdl},
+    uci::ScoreBound,
+    zobrist::position_key,
+};
+
+use super::{
+    mated_in, score_from_tt, score_to_tt, AnalysisLine, PvLine, SearchLimits, SearchResult,
+    TimeManager, TranspositionTable, TtEntry, INFINITY, MAX_PLY, TB_WIN,
+};
+
+pub(super) const MAX_DEPTH: u32 = 64;
+/// How often, in nodes, the clock is looked at and the node count is published.
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// Helper threads skip some iterations so that they spread over neighbouring depths instead
+/// of all searching the same one. Helper `i` uses entry `(i - 1) % 20`.
+const SKIP_SIZE: [u32; 20] = [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4];
+const SKIP_PHASE: [u32; 20] = [0, 1, 0, 1, 2, 3, 0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5, 6, 7];
+
+/// What every thread of one search reads from and writes to.
+pub(super) struct Shared<'a> {
+    pub tt: &'a TranspositionTable,
+    pub params: &'a EvalParams,
+    pub tablebase: Option<&'a Tablebase>,
+    pub stop: &'a AtomicBool,
+    pub nodes: AtomicU64,
+    pub limits: &'a SearchLimits,
+    pub start: Instant,
+    pub eval_noise: i32,
+    pub noise_seed: u64,
+}
+
+/// The state of one search thread. Worker 0 is the main thread: it alone honours the search
+/// limits, and its result is the one reported. The others only fill the shared table.
+pub(super) struct Worker<'a> {
+    id: usize,
+    shared: &'a Shared<'a>,
+    nodes: u64,
+    published_nodes: u64,
+    seldepth: usize,
+    completed_depth: u32,
+    stopped: bool,
+    time: Option<TimeManager>,
+    /// When the search is aborted, whether or not the iteration is complete.
+    deadline: Option<Duration>,
+    path: Vec<u64>,
+    pv: Vec<Vec<Move>>,
+    killers: Vec<[Option<Move>; 2]>,
+}
+
+impl<'a> Worker<'a> {
+    pub fn new(id: usize, shared: &'a Shared<'a>) -> Self {
+        Self {
+            id,
+            shared,
+            nodes: 0,
+            published_nodes: 0,
+            seldepth: 0,
+            completed_depth: 0,
+            stopped: false,
+            time: None,
+            deadline: None,
+            path: Vec::with_capacity(MAX_PLY),
+            pv: vec![vec![]; MAX_PLY + 1],
+            killers: vec![[None, None]; MAX_PLY + 1],
+        }
+    }
+
+    /// Runs iterative deepening from the root until a limit is reached or, for helper
+    /// threads, until the main thread raises the stop flag.
+    pub fn iterate(
+        &mut self,
+        board: &Board,
+        color: Color,
+        halfmove: u32,
+        on_line: &mut dyn FnMut(&AnalysisLine),
+    ) -> SearchResult {
+        let limits = self.shared.limits;
+        let mut root_moves = self.root_moves(board, color);
+
+        if self.id == 0 {
+            self.time = limits.clock.as_ref().map(TimeManager::new);
+            self.deadline = match (limits.movetime, &self.time) {
+                (Some(movetime), Some(time)) => Some(movetime.min(time.hard())),
+                (movetime, time) => movetime.or(time.as_ref().map(TimeManager::hard)),
+            };
+        }
+
+        let mut result = SearchResult::default();
+        let max_depth = limits.depth.unwrap_or(MAX_DEPTH).clamp(1, MAX_DEPTH);
+        let multipv = match self.id {
+            0 => limits.multipv.max(1).min(root_moves.len()),
+            _ => 1.min(root_moves.len()),
+        };
+
+        for depth in 1..=max_depth {
+            if self.skips(depth) {
+                continue;
+            }
+
+            let mut lines: Vec<PvLine> = vec![];
+
+            while lines.len() < multipv {
+                let excluded: Vec<&Move> = lines.iter().map(|line| &line.pv[0]).collect();
+                match self.search_root(board, color, halfmove, depth, &root_moves, &excluded) {
+                    Some(line) => {
+                        if self.id == 0 {
+                            on_line(&self.analysis_line(depth, lines.len() + 1, &line));
+                        }
+                        lines.push(line);
+                    }
+                    None => break,
+                }
+            }
+
+            if self.stopped || lines.is_empty() {
+                break;
+            }
+
+            lines.sort_by_key(|line| Reverse(line.score));
+            for line in lines.iter().rev() {
+                if let Some(i) = root_moves.iter().position(|m| *m == line.pv[0]) {
+                    let m = root_moves.remove(i);
+                    root_moves.insert(0, m);
+                }
+            }
+
+            self.completed_depth = depth;
+            result = SearchResult {
+                best_move: lines.first().map(|line| line.pv[0].clone()),
+                score: lines.first().map_or(0, |line| line.score),
+                depth,
+                seldepth: self.seldepth as u32,
+                nodes: self.total_nodes(),
+                elapsed: self.shared.start.elapsed(),
+                lines,
+            };
+
+            if self.id == 0 && (self.limit_reached() || self.out_of_time(&result, &root_moves)) {
+                break;
+            }
+        }
+
+        self.publish_nodes();
+        result
+    }
+}
+
+impl Worker<'_> {
+    fn analysis_line(&self, depth: u32, multipv: usize, line: &PvLine) -> AnalysisLine {
+        AnalysisLine {
+            multipv,
+            depth,
+            seldepth: self.seldepth as u32,
+            score: line.score,
+            bound: ScoreBound::Exact,
+            pv: line.pv.clone(),
+            nodes: self.total_nodes(),
+            elapsed: self.shared.start.elapsed(),
+        }
+    }
+
+    fn skips(&self, depth: u32) -> bool {
+        if self.id == 0 {
+            return false;
+        }
+        let i = (self.id - 1) % SKIP_SIZE.len();
+        !((depth + SKIP_PHASE[i]) / SKIP_SIZE[i]).is_multiple_of(2)
+    }
+
+    /// Root moves in search order. When the root is in the tablebase, moves that throw away
+    /// the tablebase outcome are dropped and the fastest wins come first.
+    fn root_moves(&self, board: &Board, color: Color) -> Vec<Move> {
+        let ranked = self
+            .shared
+            .tablebase
+            .filter(|tablebase| tablebase.covers(board))
+            .and_then(|tablebase| tablebase.root_moves(board, color).ok())
+            .filter(|ranked| !ranked.is_empty());
+
+        match ranked {
+            Some(ranked) => {
+                let best = ranked[0].wdl;
+                ranked
+                    .into_iter()
+                    .filter(|root_move| root_move.wdl == best)
+                    .map(|root_move| root_move.mv)
+                    .collect()
+            }
+            None => legal_moves(board, color),
+        }
+    }
+
+    fn count_node(&mut self) {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(TIME_CHECK_INTERVAL) {
+            self.publish_nodes();
+        }
+    }
+
+    fn publish_nodes(&mut self) {
+        let unpublished = self.nodes - self.published_nodes;
+        self.shared.nodes.fetch_add(unpublished, Ordering::Relaxed);
+        self.published_nodes = self.nodes;
+    }
+
+    /// Nodes searched by all threads so far.
+    fn total_nodes(&self) -> u64 {
+        self.shared.nodes.load(Ordering::Relaxed) + self.nodes - self.published_nodes
+    }
+
+    fn limit_reached(&self) -> bool {
+        let limits = self.shared.limits;
+        self.shared.stop.load(Ordering::Relaxed)
+            || limits
+                .nodes
+                .is_some_and(|nodes| self.total_nodes() >= nodes)
+            || self
+                .deadline
+                .is_some_and(|deadline| self.shared.start.elapsed() >= deadline)
+    }
+
+    /// Under a clock, a forced reply is played at once, and a new iteration is only started
+    /// while the soft budget lasts.
+    fn out_of_time(&mut self, result: &SearchResult, root_moves: &[Move]) -> bool {
+        let Some(time) = &mut self.time else {
+            return false;
+        };
+        if let Some(best_move) = &result.best_move {
+            time.update(best_move, result.score);
+        }
+
+        root_moves.len() == 1 || time.should_stop(self.shared.start.elapsed())
+    }
+
+    /// The main thread never cuts its first iteration short, so there is always a move to
+    /// play. Helpers only stop when told to.
+    fn should_stop(&mut self) -> bool {
+        if self.stopped {
+            return true;
+        }
+
+        self.stopped = match self.id {
+            0 if self.completed_depth == 0 => false,
+            0 => {
+                let limits = self.shared.limits;
+                let check_clock = self.nodes.is_multiple_of(TIME_CHECK_INTERVAL);
+                self.shared.stop.load(Ordering::Relaxed)
+                    || limits
+                        .nodes
+                        .is_some_and(|nodes| self.total_nodes() >= nodes)
+                    || (check_clock
+                        && self
+                            .deadline
+                            .is_some_and(|deadline| self.shared.start.elapsed() >= deadline))
+            }
+            _ => self.shared.stop.load(Ordering::Relaxed),
+        };
+
+        self.stopped
+    }
+
+    fn search_root(
+        &mut self,
+        board: &Board,
+        color: Color,
+        halfmove: u32,
+        depth: u32,
+        root_moves: &[Move],
+        excluded: &[&Move],
+    ) -> Option<PvLine> {
+        let mut alpha = -INFINITY;
+        let mut best: Option<PvLine> = None;
+
+        self.path.push(position_key(board, color));
+
+        for m in root_moves.iter().filter(|m| !excluded.contains(m)) {
+            let next = make_move(board, m);
+            let halfmove = if is_zeroing(board, m) {
+                0
+            } else {
+                halfmove + 1
+            };
+            let score = -self.negamax(
+                &next,
+                color.opponent(),
+                depth - 1,
+                1,
+                -INFINITY,
+                -alpha,
+                halfmove,
+            );
+
+            if self.stopped {
+                self.path.pop();
+                return None;
+            }
+
+            if score > alpha {
+                alpha = score;
+                let mut pv = vec![m.clone()];
+                pv.extend(self.pv[1].iter().cloned());
+                best = Some(PvLine { score, pv });
+            }
+        }
+
+        self.path.pop();
+        best
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        board: &Board,
+        color: Color,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        halfmove: u32,
+    ) -> i32 {
+        self.pv[ply].clear();
+        self.count_node();
+        self.seldepth = self.seldepth.max(ply);
+
+        if self.should_stop() {
+            return 0;
+        }
+
+        let key = position_key(board, color);
+        if halfmove >= 100 || self.path.contains(&key) {
+            return 0;
+        }
+        if ply >= MAX_PLY - 1 {
+            return self.evaluate(board, color, key);
+        }
+
+        let in_check = board.is_in_check(Player::new(color));
+        let depth = if in_check { depth + 1 } else { depth };
+
+        if depth == 0 {
+            return self.quiescence(board, color, ply, alpha, beta);
+        }
+
+        let mut tt_move = None;
+        if let Some(entry) = self.shared.tt.probe(key) {
+            tt_move = entry.best_move.clone();
+            if entry.depth >= depth {
+                let score = score_from_tt(entry.score, ply);
+                let cutoff = match entry.bound {
+                    ScoreBound::Exact => true,
+                    ScoreBound::Lower => score >= beta,
+                    ScoreBound::Upper => score <= alpha,
+                };
+                if cutoff {
+                    return score;
+                }
+            }
+        }
+
+        if let Some(score) = self.probe_tablebase(board, color, ply, halfmove) {
+            self.shared.tt.store(TtEntry {
+                key,
+                depth,
+                score: score_to_tt(score, ply),
+                bound: ScoreBound::Exact,
+                best_move: None,
+            });
+            return score;
+        }
+
+        let mut moves = legal_moves(board, color);
+        if moves.is_empty() {
+            return if in_check { mated_in(ply) } else { 0 };
+        }
+        self.order_moves(board, &mut moves, tt_move.as_ref(), ply);
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+
+        self.path.push(key);
+
+        for m in moves {
+            let next = make_move(board, &m);
+            let next_halfmove = if is_zeroing(board, &m) {
+                0
+            } else {
+                halfmove + 1
+            };
+            let score = -self.negamax(
+                &next,
+                color.opponent(),
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                next_halfmove,
+            );
+
+            if self.stopped {
+                self.path.pop();
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(m.clone());
+            }
+
+            if score > alpha {
+                alpha = score;
+                let child_pv = std::mem::take(&mut self.pv[ply + 1]);
+                self.pv[ply].clear();
+                self.pv[ply].push(m.clone());
+                self.pv[ply].extend(child_pv);
+            }
+
+            if alpha >= beta {
+                if !is_capture(board, &m) && self.killers[ply][0].as_ref() != Some(&m) {
+                    self.killers[ply][1] = self.killers[ply][0].take();
+                    self.killers[ply][0] = Some(m);
+                }
+                break;
+            }
+        }
+
+        self.path.pop();
+
+        let bound = if best_score >= beta {
+            ScoreBound::Lower
+        } else if best_score > original_alpha {
+            ScoreBound::Exact
+        } else {
+            ScoreBound::Upper
+        };
+        self.shared.tt.store(TtEntry {
+            key,
+            depth,
+            score: score_to_tt(best_score, ply),
+            bound,
+            best_move,
+        });
+
+        best_score
+    }
+
+    fn quiescence(
+        &mut self,
+        board: &Board,
+        color: Color,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.pv[ply].clear();
+        self.count_node();
+        self.seldepth = self.seldepth.max(ply);
+
+        if self.should_stop() {
+            return 0;
+        }
+
+        let stand_pat = self.evaluate(board, color, 0);
+        if ply >= MAX_PLY - 1 || stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut moves: Vec<Move> = legal_moves(board, color)
+            .into_iter()
+            .filter(|m| {
+                is_capture(board, m) || m.move_type == MoveType::Promotion(PromotionPiece::Queen)
+            })
+            .collect();
+        self.order_moves(board, &mut moves, None, ply);
+
+        for m in moves {
+            let score = -self.quiescence(
+                &make_move(board, &m),
+                color.opponent(),
+                ply + 1,
+                -beta,
+                -alpha,
+            );
+
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return score;
+            }
+            if score > alpha {
+                alpha = score;
+                let child_pv = std::mem::take(&mut self.pv[ply + 1]);
+                self.pv[ply].clear();
+                self.pv[ply].push(m);
+                self.pv[ply].extend(child_pv);
+            }
+        }
+
+        alpha
+    }
+
+    /// `key` may be 0 when it is not at hand; it is only needed for evaluation noise.
+    fn evaluate(&self, board: &Board, color: Color, key: u64) -> i32 {
+        let score = evaluate(board, color, self.shared.params);
+        if self.shared.eval_noise == 0 {
+            return score;
+        }
+
+        let key = if key == 0 {
+            position_key(board, color)
+        } else {
+            key
+        };
+        let mut z = (key ^ self.shared.noise_seed).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        let span = 2 * self.shared.eval_noise as u64 + 1;
+        score + (z % span) as i32 - self.shared.eval_noise
+    }
+
+    /// Probes the tablebase right after a capture or pawn move, when the material has just
+    /// changed and the fifty-move counter is fresh.
+    fn probe_tablebase(
+        &self,
+        board: &Board,
+        color: Color,
+        ply: usize,
+        halfmove: u32,
+    ) -> Option<i32> {
+        let tablebase = self.shared.tablebase?;
+        if halfmove != 0 || !tablebase.covers(board) {
+            return None;
+        }
+
+        match tablebase.probe_wdl(board, color).ok()? {
+            Wdl::Win => Some(TB_WIN - ply as i32),
+            Wdl::Loss => Some(-TB_WIN + ply as i32),
+            Wdl::CursedWin | Wdl::Draw | Wdl::BlessedLoss => Some(0),
+        }
+    }
+
+    /// Hash move first, then captures by most valuable victim and least valuable attacker,
+    /// promotions, killer moves and the remaining quiet moves.
+    fn order_moves(&self, board: &Board, moves: &mut [Move], tt_move: Option<&Move>, ply: usize) {
+        const VALUES: [i32; 6] = [1, 3, 3, 5, 9, 20];
+        let value =
+            |pos: &Position| board[pos].map_or(1, |piece| VALUES[piece.piece_type.as_index()]);
+
+        moves.sort_by_cached_key(|m| {
+            let score = if tt_move == Some(m) {
+                1_000_000
+            } else if is_capture(board, m) {
+                100_000 + 10 * value(&m.to) - value(&m.from)
+            } else if let MoveType::Promotion(piece) = &m.move_type {
+                90_000 + VALUES[PieceType::from(piece).as_index()]
+            } else if self.killers[ply].iter().any(|k| k.as_ref() == Some(m)) {
+                80_000
+            } else {
+                0
+            };
+            Reverse(score)
+        });
+    }
+}
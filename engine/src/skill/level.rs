@@ -0,0 +1,245 @@
+use domain::{game::GameState, moves::Move};
+use rand::Rng;
+
+use crate::search::{is_mate_score, Clock, PvLine, SearchLimits, Searcher};
+
+/// How strong the engine plays. Weaker levels search less, see a noisier evaluation and
+/// sometimes play a worse MultiPV candidate, chosen by a softmax over the scores.
+///
+/// The presets are ordered from weakest to strongest and carry no rating. How far apart
+/// they are can be measured with `match` and `level=<name>` engine specs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillLevel {
+    pub name: &'static str,
+    pub elo: u32,
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    /// Largest evaluation offset in centipawns.
+    pub eval_noise: i32,
+    /// How many candidate moves to choose from.
+    pub multipv: usize,
+    /// Softmax temperature in centipawns. Zero always plays the best candidate.
+    pub temperature: i32,
+}
+
+const PRESETS: [SkillLevel; 6] = [
+    SkillLevel {
+        name: "Beginner",
+        elo: ELO_BEGINNER,
+        depth: Some(1),
+        nodes: Some(2_000),
+        eval_noise: 120,
+        multipv: 4,
+        temperature: 120,
+    },
+    SkillLevel {
+        name: "Casual",
+        elo: ELO_CASUAL,
+        depth: Some(2),
+        nodes: Some(5_000),
+        eval_noise: 80,
+        multipv: 4,
+        temperature: 80,
+    },
+    SkillLevel {
+        name: "Club",
+        elo: ELO_CLUB,
+        depth: Some(3),
+        nodes: Some(20_000),
+        eval_noise: 50,
+        multipv: 3,
+        temperature: 50,
+    },
+    SkillLevel {
+        name: "Intermediate",
+        elo: ELO_INTERMEDIATE,
+        depth: Some(4),
+        nodes: Some(60_000),
+        eval_noise: 30,
+        multipv: 3,
+        temperature: 25,
+    },
+    SkillLevel {
+        name: "Advanced",
+        elo: ELO_ADVANCED,
+        depth: Some(5),
+        nodes: Some(200_000),
+        eval_noise: 10,
+        multipv: 2,
+        temperature: 10,
+    },
+    SkillLevel {
+        name: "Maximum",
+        elo: ELO_MAXIMUM,
+        depth: Some(6),
+        nodes: Some(400_000),
+        eval_noise: 0,
+        multipv: 1,
+        temperature: 0,
+    },
+];
+
+impl Default for SkillLevel {
+    fn default() -> Self {
+        PRESETS[2].clone()
+    }
+}
+
+impl SkillLevel {
+    pub fn presets() -> &'static [SkillLevel] {
+        &PRESETS
+    }
+
+    /// The preset closest to `elo`.
+    pub fn from_elo(elo: u32) -> Self {
+        PRESETS
+            .iter()
+            .min_by_key(|level| level.elo.abs_diff(elo))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The preset called `name`, in any case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        PRESETS
+            .iter()
+            .find(|level| level.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    pub fn limits(&self) -> SearchLimits {
+        SearchLimits {
+            depth: self.depth,
+            nodes: self.nodes,
+            movetime: None,
+            clock: None,
+            multipv: self.multipv.max(1),
+        }
+    }
+
+    /// Picks one of `lines` with probability proportional to `exp(score / temperature)`.
+    /// Forced mates are never passed over, and a zero temperature picks the best line.
+    pub fn choose<'a, R: Rng>(&self, lines: &'a [PvLine], rng: &mut R) -> Option<&'a PvLine> {
+        let best = lines.iter().max_by_key(|line| line.score)?;
+        if self.temperature <= 0 || is_mate_score(best.score) && best.score > 0 {
+            return Some(best);
+        }
+
+        let temperature = self.temperature as f64;
+        let weights: Vec<f64> = lines
+            .iter()
+            .map(|line| ((line.score - best.score) as f64 / temperature).exp())
+            .collect();
+
+        let mut roll = rng.gen_range(0.0..weights.iter().sum::<f64>());
+        for (line, weight) in lines.iter().zip(weights) {
+            if roll < weight {
+                return Some(line);
+            }
+            roll -= weight;
+        }
+
+        Some(best)
+    }
+
+    /// Searches `game_state` at this level and returns the move to play. Under a `clock`
+    /// the time manager bounds the search as well.
+    ///
+    /// The noise is seeded once per level, so the searcher keeps its transposition table
+    /// from one move to the next.
+    pub fn pick_move<R: Rng>(
+        &self,
+        searcher: &mut Searcher,
+        game_state: &GameState,
+        clock: Option<Clock>,
+        rng: &mut R,
+    ) -> Option<Move> {
+        if searcher.eval_noise() != self.eval_noise {
+            searcher.set_eval_noise(self.eval_noise, rng.gen());
+        }
+        let limits = SearchLimits {
+            clock,
+            ..self.limits()
+        };
+        let result = searcher.search(game_state, &limits);
+
+        self.choose(&result.lines, rng)
+            .map(|line| line.pv[0].clone())
+            .or(result.best_move)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::search::MATE;
+
+    fn line(score: i32) -> PvLine {
+        PvLine { score, pv: vec![] }
+    }
+
+    #[test]
+    fn test_from_elo_picks_nearest_preset() {
+        assert_eq!(SkillLevel::from_elo(0).name, "Beginner");
+        assert_eq!(SkillLevel::from_elo(ELO_CLUB + 10).name, "Club");
+        assert_eq!(SkillLevel::from_elo(4000).name, "Maximum");
+    }
+
+    #[test]
+    fn test_presets_get_stronger() {
+        assert!(PRESETS.windows(2).all(|pair| pair[0].elo < pair[1].elo));
+    }
+
+    #[test]
+    fn test_from_name_ignores_case() {
+        assert_eq!(SkillLevel::from_name("beginner").unwrap().name, "Beginner");
+        assert_eq!(SkillLevel::from_name("MAXIMUM").unwrap().name, "Maximum");
+        assert!(SkillLevel::from_name("Grandmaster").is_none());
+    }
+
+    #[test]
+    fn test_choose_prefers_better_lines() {
+        let level = SkillLevel::from_name("Beginner").unwrap();
+        let lines = [line(50), line(40), line(-400)];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let mut counts = [0; 3];
+        for _ in 0..1000 {
+            let chosen = level.choose(&lines, &mut rng).unwrap();
+            counts[lines.iter().position(|l| l == chosen).unwrap()] += 1;
+        }
+
+        assert!(counts[0] > counts[1]);
+        assert!(counts[1] > 0);
+        assert!(counts[2] < 50);
+    }
+
+    #[test]
+    fn test_choose_never_misses_a_mate() {
+        let level = SkillLevel::from_name("Beginner").unwrap();
+        let lines = [line(300), line(MATE - 3)];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..100 {
+            assert_eq!(level.choose(&lines, &mut rng).unwrap().score, MATE - 3);
+        }
+    }
+
+    #[test]
+    fn test_pick_move_is_legal() {
+        let game_state = GameState::new();
+        let mut searcher = Searcher::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        let m = SkillLevel::from_name("Beginner")
+            .unwrap()
+            .pick_move(&mut searcher, &game_state, None, &mut rng)
+            .unwrap();
+
+        assert!(
+            crate::movegen::legal_moves(&game_state.board, game_state.current_player.color)
+                .contains(&m)
+        );
+    }
+}
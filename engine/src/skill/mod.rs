@@ -0,0 +1,3 @@
+pub mod level;
+
+pub use level::*;
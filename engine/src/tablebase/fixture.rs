@@ -0,0 +1,594 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use super::{
+    index::{file, rank, Square},
+    material::Material,
+    table::{Table, TableKind},
+};
+
+// The published tables cannot be fetched by the tests, so KRvK is solved here by retrograde
+// analysis and written out in the Syzygy format: recursive pairing, canonical Huffman codes,
+// a sparse block index and, for DTZ, a value map.
+
+const WHITE_KING: u8 = 6;
+const WHITE_ROOK: u8 = 4;
+const BLACK_KING: u8 = 14;
+
+/// Placements of the white king, the rook and the black king, for one side to move.
+const PLACEMENTS: usize = 64 * 64 * 64;
+/// Size of the encoding of two kings and a unique piece.
+const TABLE_SIZE: usize = 31332;
+
+const BLOCK_SIZE_LOG2: u8 = 5;
+const SPAN_LOG2: u8 = 6;
+const PAIR_ROUNDS: usize = 4;
+
+const FLAG_MAPPED: u8 = 2;
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+
+/// A KRvK position as (side to move, white king, rook, black king); white moves first.
+type Krvk = (usize, Square, Square, Square);
+
+fn krvk_index((stm, wk, wr, bk): Krvk) -> usize {
+    stm * PLACEMENTS + wk * 4096 + wr * 64 + bk
+}
+
+fn krvk_position(index: usize) -> Krvk {
+    (
+        index / PLACEMENTS,
+        index / 4096 % 64,
+        index / 64 % 64,
+        index % 64,
+    )
+}
+
+fn adjacent(a: Square, b: Square) -> bool {
+    a != b && rank(a).abs_diff(rank(b)) <= 1 && file(a).abs_diff(file(b)) <= 1
+}
+
+/// Whether the rook attacks `target`, with the white king as the only possible blocker.
+fn rook_attacks(rook: Square, target: Square, king: Square) -> bool {
+    let between = |a: usize, b: usize, x: usize| a.min(b) < x && x < a.max(b);
+
+    if rook == target {
+        false
+    } else if rank(rook) == rank(target) {
+        !(rank(king) == rank(rook) && between(file(rook), file(target), file(king)))
+    } else if file(rook) == file(target) {
+        !(file(king) == file(rook) && between(rank(rook), rank(target), rank(king)))
+    } else {
+        false
+    }
+}
+
+fn rook_moves(rook: Square, blockers: [Square; 2]) -> Vec<Square> {
+    let mut moves = vec![];
+    for (dr, df) in [(0, 1), (0, -1), (1, 0), (-1, 0)] {
+        let (mut r, mut f) = (rank(rook) as i32 + dr, file(rook) as i32 + df);
+        while (0..8).contains(&r) && (0..8).contains(&f) {
+            let sq = (8 * r + f) as Square;
+            if blockers.contains(&sq) {
+                break;
+            }
+            moves.push(sq);
+            r += dr;
+            f += df;
+        }
+    }
+    moves
+}
+
+fn is_legal((stm, wk, wr, bk): Krvk) -> bool {
+    wk != wr && wk != bk && wr != bk && !adjacent(wk, bk) && (stm == 1 || !rook_attacks(wr, bk, wk))
+}
+
+/// The positions reachable in one move, and whether black can take the rook.
+fn successors((stm, wk, wr, bk): Krvk) -> (Vec<usize>, bool) {
+    let mut next = vec![];
+    let mut takes_rook = false;
+
+    if stm == 0 {
+        for to in (0..64).filter(|&to| adjacent(wk, to) && to != wr && !adjacent(to, bk)) {
+            next.push(krvk_index((1, to, wr, bk)));
+        }
+        for to in rook_moves(wr, [wk, bk]) {
+            next.push(krvk_index((1, wk, to, bk)));
+        }
+    } else {
+        for to in (0..64).filter(|&to| adjacent(bk, to) && !adjacent(to, wk)) {
+            if to == wr {
+                takes_rook |= !adjacent(wk, wr);
+            } else if !rook_attacks(wr, to, wk) {
+                next.push(krvk_index((0, wk, wr, to)));
+            }
+        }
+    }
+
+    (next, takes_rook)
+}
+
+/// The DTZ of every legal KRvK position, `None` for illegal ones. Mate is the only way to
+/// reset the fifty-move counter while keeping the win, so DTZ is the distance to mate here.
+fn solve_krvk() -> Vec<Option<i32>> {
+    let mut dtz = vec![None; 2 * PLACEMENTS];
+    let mut predecessors = vec![vec![]; 2 * PLACEMENTS];
+    let mut unresolved = vec![0; 2 * PLACEMENTS];
+    let mut queue = VecDeque::new();
+
+    for index in (0..2 * PLACEMENTS).filter(|&i| is_legal(krvk_position(i))) {
+        let position = krvk_position(index);
+        let (next, takes_rook) = successors(position);
+        for &n in &next {
+            predecessors[n].push(index as u32);
+        }
+
+        if position.0 == 1 {
+            let (_, wk, wr, bk) = position;
+            if takes_rook {
+                dtz[index] = Some(0);
+            } else if next.is_empty() {
+                let mated = rook_attacks(wr, bk, wk);
+                dtz[index] = Some(if mated { -1 } else { 0 });
+                if mated {
+                    queue.push_back(index);
+                }
+            }
+        }
+        unresolved[index] = next.len();
+    }
+
+    // Positions come off the queue in order of their distance, so the first loss found for
+    // a white position is the fastest and the last win found for a black one the slowest
+    while let Some(index) = queue.pop_front() {
+        let value = dtz[index].unwrap();
+        for &p in &predecessors[index] {
+            let p = p as usize;
+            if dtz[p].is_some() {
+                continue;
+            }
+
+            if value < 0 {
+                dtz[p] = Some(if value == -1 { 1 } else { 1 - value });
+                queue.push_back(p);
+            } else {
+                unresolved[p] -= 1;
+                if unresolved[p] == 0 {
+                    dtz[p] = Some(-(value + 1));
+                    queue.push_back(p);
+                }
+            }
+        }
+    }
+
+    (0..2 * PLACEMENTS)
+        .map(|i| match dtz[i] {
+            None if is_legal(krvk_position(i)) => Some(0),
+            value => value,
+        })
+        .collect()
+}
+
+/// The solution, computed once for all tests.
+pub(super) fn krvk_solution() -> &'static [Option<i32>] {
+    static SOLUTION: OnceLock<Vec<Option<i32>>> = OnceLock::new();
+    SOLUTION.get_or_init(solve_krvk)
+}
+
+/// The pieces of the position at `index` of the solution as tables take them, and whether
+/// black is to move.
+pub(super) fn krvk_pieces(index: usize) -> (Vec<(Square, u8)>, bool) {
+    let (stm, wk, wr, bk) = krvk_position(index);
+    let mut pieces = vec![(wk, WHITE_KING), (wr, WHITE_ROOK), (bk, BLACK_KING)];
+    pieces.sort_unstable();
+    (pieces, stm == 1)
+}
+
+/// A directory with `KRvK.rtbw` and `KRvK.rtbz`, written once for all tests.
+pub(super) fn krvk_dir() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("syzygy-krvk-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_krvk(&dir, krvk_solution());
+        dir
+    })
+}
+
+fn write_krvk(dir: &Path, dtz: &[Option<i32>]) {
+    let material = Material::parse("KRvK").unwrap();
+    let wdl_path = dir.join("KRvK.rtbw");
+    let dtz_path = dir.join("KRvK.rtbz");
+
+    // The decoder's own indexing places the values, in a table with no data yet
+    fs::write(
+        &wdl_path,
+        table_bytes(TableKind::Wdl, &[SubTable::Single(2), SubTable::Single(2)]),
+    )
+    .unwrap();
+    let placeholder = Table::open(&wdl_path, TableKind::Wdl, material).unwrap();
+
+    let mut wdl_values = vec![vec![None; TABLE_SIZE]; 2];
+    let mut win_plies = vec![None; TABLE_SIZE];
+
+    for (index, value) in dtz.iter().enumerate() {
+        let Some(value) = *value else {
+            continue;
+        };
+        let (pieces, black_to_move) = krvk_pieces(index);
+        let (_, side, idx) = placeholder
+            .index(&pieces, black_to_move, false)
+            .unwrap()
+            .unwrap();
+
+        let wdl = (2 * value.signum() + 2) as u16;
+        let stored = wdl_values[side][idx as usize].get_or_insert(wdl);
+        assert_eq!(
+            *stored, wdl,
+            "Index {} of side {} holds two values",
+            idx, side
+        );
+
+        if side == 0 && value > 0 {
+            let stored = win_plies[idx as usize].get_or_insert(value);
+            assert_eq!(*stored, value, "DTZ index {} holds two values", idx);
+        }
+    }
+
+    let wdl_tables: Vec<SubTable> = wdl_values
+        .iter()
+        .map(|values| {
+            SubTable::compress(&values.iter().map(|v| v.unwrap_or(2)).collect::<Vec<_>>())
+        })
+        .collect();
+    fs::write(&wdl_path, table_bytes(TableKind::Wdl, &wdl_tables)).unwrap();
+
+    // Wins are stored in plies through the map, everything else is never looked up
+    let mut map: Vec<i32> = win_plies.iter().flatten().map(|plies| plies - 1).collect();
+    map.sort_unstable();
+    map.dedup();
+    let stored: Vec<u16> = win_plies
+        .iter()
+        .map(|plies| plies.map_or(0, |plies| map.binary_search(&(plies - 1)).unwrap() as u16))
+        .collect();
+    let dtz_table = SubTable::Mapped {
+        flags: FLAG_MAPPED | FLAG_WIN_PLIES | FLAG_LOSS_PLIES,
+        map: map.iter().map(|&plies| plies as u8).collect(),
+        data: Box::new(SubTable::compress(&stored)),
+    };
+    fs::write(&dtz_path, table_bytes(TableKind::Dtz, &[dtz_table])).unwrap();
+}
+
+/// The values of one side to move, compressed.
+enum SubTable {
+    Single(u8),
+    Compressed(Compressed),
+    /// A DTZ table whose values index a map of wins.
+    Mapped {
+        flags: u8,
+        map: Vec<u8>,
+        data: Box<SubTable>,
+    },
+}
+
+struct Compressed {
+    min_len: u8,
+    max_len: u8,
+    lowest_sym: Vec<u16>,
+    /// Left and right children, or a value and `0xFFF` for a symbol that is not a pair.
+    btree: Vec<(u16, u16)>,
+    sparse_index: Vec<(u32, u16)>,
+    block_lengths: Vec<u16>,
+    data: Vec<u8>,
+}
+
+impl SubTable {
+    fn compress(values: &[u16]) -> Self {
+        if values.iter().all(|&v| v == values[0]) {
+            return Self::Single(values[0] as u8);
+        }
+        Self::Compressed(Compressed::new(values))
+    }
+
+    fn flags(&self) -> u8 {
+        match self {
+            Self::Single(_) => 128,
+            Self::Compressed(_) => 0,
+            Self::Mapped { flags, data, .. } => flags | data.flags(),
+        }
+    }
+
+    fn single(&self) -> Option<u8> {
+        match self {
+            Self::Single(value) => Some(*value),
+            Self::Compressed(_) => None,
+            Self::Mapped { data, .. } => data.single(),
+        }
+    }
+
+    fn compressed(&self) -> Option<&Compressed> {
+        match self {
+            Self::Single(_) => None,
+            Self::Compressed(compressed) => Some(compressed),
+            Self::Mapped { data, .. } => data.compressed(),
+        }
+    }
+}
+
+impl Compressed {
+    fn new(values: &[u16]) -> Self {
+        // Symbols are single values, then pairs of the most frequent neighbouring symbols
+        let mut leaves: Vec<u16> = values.to_vec();
+        leaves.sort_unstable();
+        leaves.dedup();
+        let mut symbols: Vec<(u16, u16)> = leaves.iter().map(|&v| (v, 0xFFF)).collect();
+        let mut expands_to: Vec<usize> = vec![1; symbols.len()];
+        let mut tokens: Vec<usize> = values
+            .iter()
+            .map(|v| leaves.binary_search(v).unwrap())
+            .collect();
+
+        for _ in 0..PAIR_ROUNDS {
+            let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+            for pair in tokens.windows(2) {
+                *counts.entry((pair[0], pair[1])).or_default() += 1;
+            }
+            let Some((&(left, right), _)) = counts
+                .iter()
+                .filter(|(_, &count)| count > 1)
+                .max_by_key(|(&pair, &count)| (count, Reverse(pair)))
+            else {
+                break;
+            };
+
+            let pair = symbols.len();
+            symbols.push((left as u16, right as u16));
+            expands_to.push(expands_to[left] + expands_to[right]);
+
+            let mut paired = Vec::with_capacity(tokens.len());
+            let mut i = 0;
+            while i < tokens.len() {
+                if i + 1 < tokens.len() && (tokens[i], tokens[i + 1]) == (left, right) {
+                    paired.push(pair);
+                    i += 2;
+                } else {
+                    paired.push(tokens[i]);
+                    i += 1;
+                }
+            }
+            tokens = paired;
+        }
+
+        let mut frequencies = vec![0; symbols.len()];
+        for &token in &tokens {
+            frequencies[token] += 1;
+        }
+        let lengths = huffman_lengths(&frequencies);
+
+        // Canonical codes: the longest codes come first and count up from zero, and the
+        // symbols are renumbered in that order, with the ones never coded at the end
+        let mut order: Vec<usize> = (0..symbols.len()).collect();
+        order.sort_by_key(|&sym| (lengths[sym] == 0, Reverse(lengths[sym]), sym));
+        let mut renumbered = vec![0; symbols.len()];
+        for (new, &old) in order.iter().enumerate() {
+            renumbered[old] = new;
+        }
+
+        let coded: Vec<u8> = lengths.iter().copied().filter(|&len| len > 0).collect();
+        let min_len = *coded.iter().min().unwrap();
+        let max_len = *coded.iter().max().unwrap();
+        let count = |len: u8| coded.iter().filter(|&&l| l == len).count() as u64;
+
+        let mut first_code = vec![0u64; max_len as usize + 1];
+        for len in (min_len..max_len).rev() {
+            let next = first_code[len as usize + 1] + count(len + 1);
+            assert_eq!(next % 2, 0, "Huffman code is not complete");
+            first_code[len as usize] = next / 2;
+        }
+        let lowest_sym: Vec<u16> = (min_len..=max_len)
+            .map(|len| coded.iter().filter(|&&l| l > len).count() as u16)
+            .collect();
+
+        let mut codes = vec![(0u64, 0u8); symbols.len()];
+        for &sym in order.iter().filter(|&&sym| lengths[sym] > 0) {
+            let len = lengths[sym];
+            let lowest = lowest_sym[(len - min_len) as usize] as usize;
+            codes[sym] = (
+                first_code[len as usize] + (renumbered[sym] - lowest) as u64,
+                len,
+            );
+        }
+
+        let btree = order
+            .iter()
+            .map(|&sym| match symbols[sym] {
+                (value, 0xFFF) => (value, 0xFFF),
+                (left, right) => (
+                    renumbered[left as usize] as u16,
+                    renumbered[right as usize] as u16,
+                ),
+            })
+            .collect();
+
+        // Fill blocks with whole symbols and remember where each one starts
+        let block_bits = 8usize << BLOCK_SIZE_LOG2;
+        let mut blocks: Vec<Vec<usize>> = vec![vec![]];
+        let mut bits = 0;
+        for &token in &tokens {
+            let len = codes[token].1 as usize;
+            if bits + len > block_bits {
+                blocks.push(vec![]);
+                bits = 0;
+            }
+            blocks.last_mut().unwrap().push(token);
+            bits += len;
+        }
+
+        let block_lengths: Vec<u16> = blocks
+            .iter()
+            .map(|block| block.iter().map(|&t| expands_to[t]).sum::<usize>() as u16 - 1)
+            .collect();
+        let mut block_starts = vec![0];
+        for &len in &block_lengths {
+            block_starts.push(block_starts.last().unwrap() + len as usize + 1);
+        }
+
+        let span = 1usize << SPAN_LOG2;
+        let sparse_index = (0..values.len().div_ceil(span))
+            .map(|k| {
+                let target = k * span + span / 2;
+                let block = (block_starts.partition_point(|&start| start <= target) - 1)
+                    .min(blocks.len() - 1);
+                (block as u32, (target - block_starts[block]) as u16)
+            })
+            .collect();
+
+        let mut data = vec![];
+        for block in &blocks {
+            let mut writer = BitWriter::default();
+            for &token in block {
+                let (code, len) = codes[token];
+                writer.push(code, len);
+            }
+            let mut bytes = writer.bytes;
+            bytes.resize(1 << BLOCK_SIZE_LOG2, 0);
+            data.extend(bytes);
+        }
+
+        Self {
+            min_len,
+            max_len,
+            lowest_sym,
+            btree,
+            sparse_index,
+            block_lengths,
+            data,
+        }
+    }
+}
+
+/// Code lengths of a Huffman code for the symbols with a non-zero frequency.
+fn huffman_lengths(frequencies: &[usize]) -> Vec<u8> {
+    let mut lengths = vec![0u8; frequencies.len()];
+    let mut heap: BinaryHeap<Reverse<(usize, Vec<usize>)>> = frequencies
+        .iter()
+        .enumerate()
+        .filter(|(_, &f)| f > 0)
+        .map(|(sym, &f)| Reverse((f, vec![sym])))
+        .collect();
+
+    if heap.len() == 1 {
+        let Reverse((_, symbols)) = heap.pop().unwrap();
+        lengths[symbols[0]] = 1;
+        return lengths;
+    }
+
+    while heap.len() > 1 {
+        let Reverse((f1, mut s1)) = heap.pop().unwrap();
+        let Reverse((f2, s2)) = heap.pop().unwrap();
+        s1.extend(s2);
+        for &sym in &s1 {
+            lengths[sym] += 1;
+        }
+        heap.push(Reverse((f1 + f2, s1)));
+    }
+    lengths
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bits: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, code: u64, len: u8) {
+        for i in (0..len).rev() {
+            if self.bits.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if code >> i & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> (self.bits % 8);
+            }
+            self.bits += 1;
+        }
+    }
+}
+
+fn table_bytes(kind: TableKind, sides: &[SubTable]) -> Vec<u8> {
+    let mut bytes = match kind {
+        TableKind::Wdl => vec![0x71, 0xE8, 0x23, 0x5D, 1],
+        TableKind::Dtz => vec![0xD7, 0x66, 0x0C, 0xA5, 0],
+    };
+
+    // The leading group comes first, and both sides list the pieces in the same order
+    bytes.push(0);
+    for code in [WHITE_KING, WHITE_ROOK, BLACK_KING] {
+        bytes.push(code | code << 4);
+    }
+    pad_to(&mut bytes, 2);
+
+    for side in sides {
+        bytes.push(side.flags());
+        let Some(c) = side.compressed() else {
+            bytes.extend(side.single());
+            continue;
+        };
+
+        bytes.extend([BLOCK_SIZE_LOG2, SPAN_LOG2, 0]);
+        bytes.extend((c.block_lengths.len() as u32).to_le_bytes());
+        bytes.extend([c.max_len, c.min_len]);
+        c.lowest_sym
+            .iter()
+            .for_each(|sym| bytes.extend(sym.to_le_bytes()));
+        bytes.extend((c.btree.len() as u16).to_le_bytes());
+        for &(left, right) in &c.btree {
+            bytes.extend([
+                left as u8,
+                (left >> 8) as u8 | (right << 4) as u8,
+                (right >> 4) as u8,
+            ]);
+        }
+        pad_to(&mut bytes, 2);
+    }
+
+    for side in sides {
+        if let SubTable::Mapped { map, .. } = side {
+            bytes.push(map.len() as u8);
+            bytes.extend(map);
+            // Losses and the wins and losses the fifty-move rule turns into draws
+            bytes.extend([0, 0, 0]);
+            pad_to(&mut bytes, 2);
+        }
+    }
+
+    for c in sides.iter().filter_map(SubTable::compressed) {
+        for &(block, offset) in &c.sparse_index {
+            bytes.extend(block.to_le_bytes());
+            bytes.extend(offset.to_le_bytes());
+        }
+    }
+    for c in sides.iter().filter_map(SubTable::compressed) {
+        c.block_lengths
+            .iter()
+            .for_each(|len| bytes.extend(len.to_le_bytes()));
+    }
+    for side in sides {
+        pad_to(&mut bytes, 64);
+        if let Some(c) = side.compressed() {
+            bytes.extend(&c.data);
+        }
+    }
+
+    bytes
+}
+
+fn pad_to(bytes: &mut Vec<u8>, alignment: usize) {
+    bytes.resize(bytes.len().next_multiple_of(alignment), 0);
+}
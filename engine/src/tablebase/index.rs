@@ -0,0 +1,179 @@
+use std::sync::OnceLock;
+
+/// Squares follow the Syzygy convention: `a1 = 0`, `h1 = 7`, `h8 = 63`.
+pub(super) type Square = usize;
+
+pub(super) const MAX_PIECES: usize = 7;
+
+pub(super) fn rank(sq: Square) -> usize {
+    sq >> 3
+}
+
+pub(super) fn file(sq: Square) -> usize {
+    sq & 7
+}
+
+pub(super) fn flip_file(sq: Square) -> Square {
+    sq ^ 7
+}
+
+pub(super) fn flip_rank(sq: Square) -> Square {
+    sq ^ 56
+}
+
+/// Distance from the a1-h8 diagonal, negative below it.
+pub(super) fn off_diagonal(sq: Square) -> i32 {
+    rank(sq) as i32 - file(sq) as i32
+}
+
+pub(super) fn flip_diagonal(sq: Square) -> Square {
+    ((sq >> 3) | (sq << 3)) & 63
+}
+
+/// The lookup tables used to turn a placement of pieces into a table index. They only
+/// depend on the board geometry, so they are built once and shared.
+pub(super) struct IndexTables {
+    /// Maps the 28 squares below the a1-h8 diagonal to `0..28`.
+    pub map_b1h1h7: [u64; 64],
+    /// Maps the a1-d1-d4 triangle to `0..10`, diagonal squares last.
+    pub map_a1d1d4: [u64; 64],
+    /// The 462 legal king pairs with the first king in the a1-d1-d4 triangle.
+    pub map_kk: [[u64; 64]; 10],
+    /// `binomial[k][n]` ways of choosing `k` squares out of `n`.
+    pub binomial: [[u64; 64]; 6],
+    /// Encodes a2-h7 to `0..48`; the leading pawn is the one with the highest value.
+    pub map_pawns: [u64; 64],
+    pub lead_pawn_idx: [[u64; 64]; 6],
+    pub lead_pawns_size: [[u64; 4]; 6],
+}
+
+pub(super) fn index_tables() -> &'static IndexTables {
+    static TABLES: OnceLock<IndexTables> = OnceLock::new();
+    TABLES.get_or_init(IndexTables::new)
+}
+
+impl IndexTables {
+    fn new() -> Self {
+        let mut tables = Self {
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+            binomial: [[0; 64]; 6],
+            map_pawns: [0; 64],
+            lead_pawn_idx: [[0; 64]; 6],
+            lead_pawns_size: [[0; 4]; 6],
+        };
+
+        for (code, sq) in (0..64).filter(|&sq| off_diagonal(sq) < 0).enumerate() {
+            tables.map_b1h1h7[sq] = code as u64;
+        }
+
+        let triangle: Vec<Square> = (0..28).filter(|&sq| file(sq) <= 3).collect();
+        let below = triangle.iter().filter(|&&sq| off_diagonal(sq) < 0);
+        let diagonal = triangle.iter().filter(|&&sq| off_diagonal(sq) == 0);
+        for (code, &sq) in below.chain(diagonal).enumerate() {
+            tables.map_a1d1d4[sq] = code as u64;
+        }
+
+        tables.init_map_kk();
+
+        tables.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..6.min(n + 1) {
+                tables.binomial[k][n] = if k > 0 {
+                    tables.binomial[k - 1][n - 1]
+                } else {
+                    0
+                } + if k < n { tables.binomial[k][n - 1] } else { 0 };
+            }
+        }
+
+        tables.init_pawns();
+        tables
+    }
+
+    fn init_map_kk(&mut self) {
+        let is_adjacent = |a: Square, b: Square| {
+            (rank(a) as i32 - rank(b) as i32).abs() <= 1
+                && (file(a) as i32 - file(b) as i32).abs() <= 1
+        };
+
+        let mut code = 0;
+        let mut both_on_diagonal = vec![];
+
+        for idx in 0..10 {
+            // b1 is the only square of the triangle mapped to 0
+            for s1 in
+                (0..28).filter(|&s1| self.map_a1d1d4[s1] == idx as u64 && (idx > 0 || s1 == 1))
+            {
+                for s2 in 0..64 {
+                    let above_diagonal = off_diagonal(s1) == 0 && off_diagonal(s2) > 0;
+                    if is_adjacent(s1, s2) || above_diagonal {
+                        continue;
+                    } else if off_diagonal(s1) == 0 && off_diagonal(s2) == 0 {
+                        both_on_diagonal.push((idx, s2));
+                    } else {
+                        self.map_kk[idx][s2] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+
+        for (idx, s2) in both_on_diagonal {
+            self.map_kk[idx][s2] = code;
+            code += 1;
+        }
+    }
+
+    fn init_pawns(&mut self) {
+        // Squares left for the other pawns once a pawn leads, counting the leader itself
+        let mut available = 48;
+
+        for lead_pawns in 1..=5 {
+            for f in 0..4 {
+                let mut idx = 0;
+                for r in 1..7 {
+                    let sq = 8 * r + f;
+                    if lead_pawns == 1 {
+                        self.map_pawns[sq] = available - 1;
+                        self.map_pawns[flip_file(sq)] = available - 2;
+                        available -= 2;
+                    }
+                    self.lead_pawn_idx[lead_pawns][sq] = idx;
+                    idx += self.binomial[lead_pawns - 1][self.map_pawns[sq] as usize];
+                }
+                self.lead_pawns_size[lead_pawns][f] = idx;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_king_pairs_cover_all_462_placements() {
+        let tables = index_tables();
+        let max = tables.map_kk.iter().flatten().max().copied();
+        assert_eq!(max, Some(461));
+
+        // b1 is the first square below the diagonal, d4 the last one on it
+        assert_eq!(tables.map_a1d1d4[1], 0);
+        assert_eq!(tables.map_a1d1d4[27], 9);
+        assert_eq!(tables.map_b1h1h7[55], 27);
+    }
+
+    #[test]
+    fn test_pawn_tables() {
+        let tables = index_tables();
+        assert_eq!(tables.binomial[2][5], 10);
+        assert_eq!(tables.binomial[5][63], 7_028_847);
+
+        // a2 leaves 47 squares for the other pawns, h2 one less
+        assert_eq!(tables.map_pawns[8], 47);
+        assert_eq!(tables.map_pawns[15], 46);
+        assert_eq!(tables.lead_pawns_size[1].iter().sum::<u64>(), 24);
+    }
+}
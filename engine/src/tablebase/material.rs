@@ -0,0 +1,148 @@
+use domain::{
+    board::Board,
+    game::Color,
+    pieces::{Piece, PieceType},
+};
+
+const PIECE_ORDER: [char; 6] = ['K', 'Q', 'R', 'B', 'N', 'P'];
+
+/// The material of one side written the way Syzygy file names do, e.g. `KRP`.
+pub(super) fn side_material(board: &Board, color: Color) -> String {
+    let mut letters: Vec<char> = board
+        .piece_positions()
+        .into_iter()
+        .filter_map(|pos| board[&pos])
+        .filter(|piece| piece.piece_color == color)
+        .map(|piece| letter(&piece))
+        .collect();
+
+    letters.sort_by_key(|c| PIECE_ORDER.iter().position(|p| p == c));
+    letters.into_iter().collect()
+}
+
+fn letter(piece: &Piece) -> char {
+    match piece.piece_type {
+        PieceType::King(_) => 'K',
+        PieceType::Queen(_) => 'Q',
+        PieceType::Rook(_) => 'R',
+        PieceType::Bishop(_) => 'B',
+        PieceType::Knight(_) => 'N',
+        PieceType::Pawn(_) => 'P',
+    }
+}
+
+/// The piece code used inside table files: 1 to 6 for pawn to king, plus 8 for black.
+pub(super) fn piece_code(piece: &Piece) -> u8 {
+    piece.piece_type.as_index() as u8
+        + 1
+        + match piece.piece_color {
+            Color::White => 0,
+            Color::Black => 8,
+        }
+}
+
+/// A material configuration as named by a table file such as `KRPvKR`. The side written
+/// first is called white by the table, whatever the colours on the board are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Material {
+    pub white: String,
+    pub black: String,
+}
+
+impl Material {
+    pub fn parse(name: &str) -> Option<Self> {
+        let (white, black) = name.split_once('v')?;
+        let is_valid = |side: &str| {
+            side.starts_with('K')
+                && side.matches('K').count() == 1
+                && side.chars().all(|c| PIECE_ORDER.contains(&c))
+        };
+
+        (is_valid(white) && is_valid(black)).then(|| Self {
+            white: white.to_string(),
+            black: black.to_string(),
+        })
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}v{}", self.white, self.black)
+    }
+
+    pub fn piece_count(&self) -> usize {
+        self.white.len() + self.black.len()
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        self.white == self.black
+    }
+
+    pub fn has_pawns(&self) -> bool {
+        self.pawns(&self.white) + self.pawns(&self.black) > 0
+    }
+
+    /// True when some side has exactly one piece of a non-king kind. Such tables encode
+    /// three pieces in their leading group instead of only the two kings.
+    pub fn has_unique_pieces(&self) -> bool {
+        [&self.white, &self.black].into_iter().any(|side| {
+            PIECE_ORDER[1..]
+                .iter()
+                .any(|&p| side.chars().filter(|&c| c == p).count() == 1)
+        })
+    }
+
+    /// Pawn counts of the leading colour and the other one. The side with fewer pawns leads,
+    /// white when both have the same number.
+    pub fn pawn_counts(&self) -> [usize; 2] {
+        let white = self.pawns(&self.white);
+        let black = self.pawns(&self.black);
+
+        if black == 0 || (white > 0 && black >= white) {
+            [white, black]
+        } else {
+            [black, white]
+        }
+    }
+
+    fn pawns(&self, side: &str) -> usize {
+        side.chars().filter(|&c| c == 'P').count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::{
+        board::{BoardBuilder, Position},
+        pieces::PieceKind,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_parse_table_names() {
+        let material = Material::parse("KRPvKR").unwrap();
+        assert_eq!(material.piece_count(), 5);
+        assert!(material.has_pawns());
+        assert!(material.has_unique_pieces());
+        assert_eq!(material.pawn_counts(), [1, 0]);
+        assert_eq!(material.name(), "KRPvKR");
+
+        assert!(!Material::parse("KRRvK").unwrap().has_unique_pieces());
+        assert!(Material::parse("KPPvKP").unwrap().pawn_counts() == [1, 2]);
+        assert!(Material::parse("KvK").unwrap().is_symmetric());
+        assert!(Material::parse("KQvX").is_none());
+        assert!(Material::parse("QvK").is_none());
+    }
+
+    #[test]
+    fn test_side_material_orders_pieces() {
+        let board = BoardBuilder::new()
+            .add_piece(Position::new(7, 4), PieceKind::King, Color::White)
+            .add_piece(Position::new(6, 0), PieceKind::Pawn, Color::White)
+            .add_piece(Position::new(7, 0), PieceKind::Rook, Color::White)
+            .add_piece(Position::new(0, 4), PieceKind::King, Color::Black)
+            .build();
+
+        assert_eq!(side_material(&board, Color::White), "KRP");
+        assert_eq!(side_material(&board, Color::Black), "K");
+    }
+}
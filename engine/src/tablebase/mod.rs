@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod fixture;
+mod index;
+mod material;
+pub mod probe;
+mod table;
+
+pub use probe::*;
@@ -0,0 +1,605 @@
+use std::{
+    collections::HashMap,
+    fs,
+    ops::Neg,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use domain::{board::Board, game::Color, moves::Move};
+
+use crate::{
+    error::EngineError,
+    movegen::{is_capture, is_zeroing, legal_moves, make_move},
+};
+
+use super::{
+    index::Square,
+    material::{piece_code, side_material, Material},
+    table::{Table, TableKind},
+};
+
+/// Win/draw/loss from the point of view of the side to move. Cursed wins and blessed losses
+/// are wins and losses that the fifty-move rule turns into draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+    fn from_value(value: i32) -> Result<Self, EngineError> {
+        match value {
+            -2 => Ok(Self::Loss),
+            -1 => Ok(Self::BlessedLoss),
+            0 => Ok(Self::Draw),
+            1 => Ok(Self::CursedWin),
+            2 => Ok(Self::Win),
+            _ => Err(EngineError::InvalidTablebase(format!(
+                "Invalid WDL value {}",
+                value
+            ))),
+        }
+    }
+
+    fn value(&self) -> i32 {
+        match self {
+            Self::Loss => -2,
+            Self::BlessedLoss => -1,
+            Self::Draw => 0,
+            Self::CursedWin => 1,
+            Self::Win => 2,
+        }
+    }
+
+    /// The DTZ of a position whose best move resets the fifty-move counter.
+    fn dtz_before_zeroing(&self) -> i32 {
+        match self {
+            Self::Win => 1,
+            Self::CursedWin => 101,
+            Self::Draw => 0,
+            Self::BlessedLoss => -101,
+            Self::Loss => -1,
+        }
+    }
+}
+
+impl Neg for Wdl {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Self::Loss => Self::Win,
+            Self::BlessedLoss => Self::CursedWin,
+            Self::Draw => Self::Draw,
+            Self::CursedWin => Self::BlessedLoss,
+            Self::Win => Self::Loss,
+        }
+    }
+}
+
+/// A legal move at the root, with the outcome it leads to under perfect play. `dtz` counts
+/// plies to the next capture or pawn move and is negative when the mover loses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootMove {
+    pub mv: Move,
+    pub wdl: Wdl,
+    pub dtz: i32,
+}
+
+#[derive(Debug)]
+struct TableEntry {
+    material: Material,
+    wdl_path: Option<PathBuf>,
+    dtz_path: Option<PathBuf>,
+    wdl: OnceLock<Result<Table, String>>,
+    dtz: OnceLock<Result<Table, String>>,
+}
+
+impl TableEntry {
+    fn table(&self, kind: TableKind) -> Result<&Table, EngineError> {
+        let (path, cell) = match kind {
+            TableKind::Wdl => (&self.wdl_path, &self.wdl),
+            TableKind::Dtz => (&self.dtz_path, &self.dtz),
+        };
+
+        let path = path.as_ref().ok_or_else(|| {
+            EngineError::MissingTable(format!("{}.{}", self.material.name(), kind.extension()))
+        })?;
+
+        cell.get_or_init(|| {
+            Table::open(path, kind, self.material.clone()).map_err(|err| err.to_string())
+        })
+        .as_ref()
+        .map_err(|err| EngineError::InvalidTablebase(err.clone()))
+    }
+}
+
+/// Syzygy endgame tablebases found in one or more directories. Table files are opened on
+/// first use and read on demand, so a `Tablebase` is cheap to create and can be shared
+/// between threads.
+#[derive(Debug, Default)]
+pub struct Tablebase {
+    tables: HashMap<String, TableEntry>,
+    max_pieces: usize,
+}
+
+impl Tablebase {
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, EngineError> {
+        let mut tablebase = Self::default();
+        tablebase.add_directory(dir)?;
+        Ok(tablebase)
+    }
+
+    /// Registers every `.rtbw` and `.rtbz` file in `dir` and returns how many were found.
+    pub fn add_directory<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize, EngineError> {
+        let mut found = 0;
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let kind = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("rtbw") => TableKind::Wdl,
+                Some("rtbz") => TableKind::Dtz,
+                _ => continue,
+            };
+            let Some(material) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(Material::parse)
+            else {
+                continue;
+            };
+
+            self.max_pieces = self.max_pieces.max(material.piece_count());
+            let table = self
+                .tables
+                .entry(material.name())
+                .or_insert_with(|| TableEntry {
+                    material,
+                    wdl_path: None,
+                    dtz_path: None,
+                    wdl: OnceLock::new(),
+                    dtz: OnceLock::new(),
+                });
+
+            match kind {
+                TableKind::Wdl => table.wdl_path = Some(path),
+                TableKind::Dtz => table.dtz_path = Some(path),
+            }
+            found += 1;
+        }
+
+        Ok(found)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// The largest number of pieces, kings included, covered by the loaded tables.
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    /// Whether `board` is small enough to be looked up. It does not check that the table
+    /// for its exact material is present.
+    pub fn covers(&self, board: &Board) -> bool {
+        let pieces = board.piece_positions().len();
+        pieces <= self.max_pieces.max(2) && !Self::has_castling_rights(board)
+    }
+
+    /// Probes the win/draw/loss outcome for `side_to_move`, taking en passant captures into
+    /// account but not the moves already played towards the fifty-move rule.
+    pub fn probe_wdl(&self, board: &Board, side_to_move: Color) -> Result<Wdl, EngineError> {
+        self.check_covered(board)?;
+        self.search_wdl(board, side_to_move, false)
+            .map(|(wdl, _)| wdl)
+    }
+
+    /// Probes the distance to zeroing in plies: the number of plies to the next capture or
+    /// pawn move with optimal play, positive when the side to move wins and 0 for draws.
+    pub fn probe_dtz(&self, board: &Board, side_to_move: Color) -> Result<i32, EngineError> {
+        self.check_covered(board)?;
+        self.search_dtz(board, side_to_move)
+    }
+
+    /// Ranks the legal moves of `side_to_move`: wins before draws before losses, the fastest
+    /// wins and the slowest losses first.
+    pub fn root_moves(
+        &self,
+        board: &Board,
+        side_to_move: Color,
+    ) -> Result<Vec<RootMove>, EngineError> {
+        self.check_covered(board)?;
+        let opponent = side_to_move.opponent();
+
+        let mut root_moves = legal_moves(board, side_to_move)
+            .into_iter()
+            .map(|mv| {
+                let next = make_move(board, &mv);
+                if Self::is_mate(&next, opponent) {
+                    return Ok(RootMove {
+                        mv,
+                        wdl: Wdl::Win,
+                        dtz: 1,
+                    });
+                }
+
+                let (wdl, _) = self.search_wdl(&next, opponent, false)?;
+                let wdl = -wdl;
+                let dtz = match is_zeroing(board, &mv) {
+                    true => wdl.dtz_before_zeroing(),
+                    false => {
+                        let dtz = -self.search_dtz(&next, opponent)?;
+                        dtz + dtz.signum()
+                    }
+                };
+
+                Ok(RootMove { mv, wdl, dtz })
+            })
+            .collect::<Result<Vec<RootMove>, EngineError>>()?;
+
+        root_moves.sort_by_key(|root_move| (std::cmp::Reverse(root_move.wdl), root_move.dtz));
+        Ok(root_moves)
+    }
+
+    /// The move that keeps the best outcome while reaching it as fast as possible.
+    pub fn best_move(
+        &self,
+        board: &Board,
+        side_to_move: Color,
+    ) -> Result<Option<RootMove>, EngineError> {
+        Ok(self.root_moves(board, side_to_move)?.into_iter().next())
+    }
+}
+
+impl Tablebase {
+    fn has_castling_rights(board: &Board) -> bool {
+        [Color::White, Color::Black].into_iter().any(|color| {
+            board.has_short_castling_rights(color) || board.has_long_castling_rights(color)
+        })
+    }
+
+    fn check_covered(&self, board: &Board) -> Result<(), EngineError> {
+        match self.covers(board) {
+            true => Ok(()),
+            false => Err(EngineError::MissingTable(format!(
+                "No table for a position with {} pieces or castling rights",
+                board.piece_positions().len()
+            ))),
+        }
+    }
+
+    fn is_mate(board: &Board, side_to_move: Color) -> bool {
+        legal_moves(board, side_to_move).is_empty()
+            && board.is_in_check(domain::game::Player::new(side_to_move))
+    }
+
+    /// Looks up `board` in the table for its material. Returns the table together with
+    /// whether black holds the side the table calls white.
+    fn table(&self, board: &Board, kind: TableKind) -> Result<(&Table, bool), EngineError> {
+        let white = side_material(board, Color::White);
+        let black = side_material(board, Color::Black);
+
+        let (entry, black_stronger) = match self.tables.get(&format!("{}v{}", white, black)) {
+            Some(entry) => (entry, false),
+            None => match self.tables.get(&format!("{}v{}", black, white)) {
+                Some(entry) => (entry, true),
+                None => {
+                    return Err(EngineError::MissingTable(format!(
+                        "{}v{}.{}",
+                        white,
+                        black,
+                        kind.extension()
+                    )))
+                }
+            },
+        };
+
+        Ok((entry.table(kind)?, black_stronger))
+    }
+
+    fn pieces(board: &Board) -> Vec<(Square, u8)> {
+        let mut pieces: Vec<(Square, u8)> = board
+            .piece_positions()
+            .into_iter()
+            .filter_map(|pos| {
+                board[&pos].map(|piece| {
+                    let sq = 8 * (7 - pos.row as usize) + pos.column as usize;
+                    (sq, piece_code(&piece))
+                })
+            })
+            .collect();
+
+        pieces.sort_unstable();
+        pieces
+    }
+
+    fn probe_wdl_table(&self, board: &Board, side_to_move: Color) -> Result<Wdl, EngineError> {
+        let pieces = Self::pieces(board);
+        if pieces.len() == 2 {
+            return Ok(Wdl::Draw);
+        }
+
+        let (table, black_stronger) = self.table(board, TableKind::Wdl)?;
+        let value = table.probe_wdl(&pieces, side_to_move == Color::Black, black_stronger)?;
+        Wdl::from_value(value)
+    }
+
+    fn probe_dtz_table(
+        &self,
+        board: &Board,
+        side_to_move: Color,
+        wdl: Wdl,
+    ) -> Result<Option<i32>, EngineError> {
+        let pieces = Self::pieces(board);
+        let (table, black_stronger) = self.table(board, TableKind::Dtz)?;
+        table.probe_dtz(
+            &pieces,
+            side_to_move == Color::Black,
+            black_stronger,
+            wdl.value(),
+        )
+    }
+
+    /// Tables do not know about en passant, and some store "don't care" values for
+    /// positions where a capture is best, so captures are searched before trusting the
+    /// table. The flag tells whether the best move found resets the fifty-move counter.
+    fn search_wdl(
+        &self,
+        board: &Board,
+        side_to_move: Color,
+        check_pawn_moves: bool,
+    ) -> Result<(Wdl, bool), EngineError> {
+        let moves = legal_moves(board, side_to_move);
+        let mut best = Wdl::Loss;
+        let mut searched = 0;
+
+        for m in moves
+            .iter()
+            .filter(|m| is_capture(board, m) || (check_pawn_moves && is_zeroing(board, m)))
+        {
+            searched += 1;
+            let (value, _) =
+                self.search_wdl(&make_move(board, m), side_to_move.opponent(), false)?;
+            let value = -value;
+
+            if value > best {
+                best = value;
+                if value == Wdl::Win {
+                    return Ok((value, true));
+                }
+            }
+        }
+
+        let no_more_moves = searched > 0 && searched == moves.len();
+        let value = match no_more_moves {
+            true => best,
+            false => self.probe_wdl_table(board, side_to_move)?,
+        };
+
+        if best >= value {
+            return Ok((best, best > Wdl::Draw || no_more_moves));
+        }
+
+        Ok((value, false))
+    }
+
+    fn search_dtz(&self, board: &Board, side_to_move: Color) -> Result<i32, EngineError> {
+        let (wdl, zeroing_is_best) = self.search_wdl(board, side_to_move, true)?;
+
+        if wdl == Wdl::Draw {
+            return Ok(0);
+        }
+        if zeroing_is_best {
+            return Ok(wdl.dtz_before_zeroing());
+        }
+
+        if let Some(dtz) = self.probe_dtz_table(board, side_to_move, wdl)? {
+            let cursed = matches!(wdl, Wdl::CursedWin | Wdl::BlessedLoss);
+            return Ok((dtz + if cursed { 100 } else { 0 }) * wdl.value().signum());
+        }
+
+        // The table only stores the other side to move, so search one ply
+        let opponent = side_to_move.opponent();
+        let mut min_dtz = i32::MAX;
+
+        for m in legal_moves(board, side_to_move) {
+            let zeroing = is_zeroing(board, &m);
+            let next = make_move(board, &m);
+
+            let mut dtz = match zeroing {
+                true => -self
+                    .search_wdl(&next, opponent, false)?
+                    .0
+                    .dtz_before_zeroing(),
+                false => -self.search_dtz(&next, opponent)?,
+            };
+
+            if dtz == 1 && Self::is_mate(&next, opponent) {
+                min_dtz = 1;
+            }
+            if !zeroing {
+                dtz += dtz.signum();
+            }
+            if dtz < min_dtz && dtz.signum() == wdl.value().signum() {
+                min_dtz = dtz;
+            }
+        }
+
+        Ok(if min_dtz == i32::MAX { -1 } else { min_dtz })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::{
+        board::{BoardBuilder, Position},
+        pieces::PieceKind,
+    };
+
+    use super::{super::fixture, *};
+
+    fn kings_only() -> Board {
+        BoardBuilder::new()
+            .add_piece(Position::new(7, 3), PieceKind::King, Color::White)
+            .add_piece(Position::new(0, 3), PieceKind::King, Color::Black)
+            .build()
+    }
+
+    fn board(pieces: [(&str, PieceKind, Color); 3]) -> Board {
+        pieces
+            .into_iter()
+            .fold(BoardBuilder::new(), |builder, (square, kind, color)| {
+                builder.add_piece(Position::from_algebraic(square).unwrap(), kind, color)
+            })
+            .build()
+    }
+
+    fn krvk() -> Tablebase {
+        Tablebase::open(fixture::krvk_dir()).unwrap()
+    }
+
+    #[test]
+    fn test_bare_kings_need_no_files() {
+        let tablebase = Tablebase::default();
+        let board = kings_only();
+
+        assert_eq!(
+            tablebase.probe_wdl(&board, Color::White).unwrap(),
+            Wdl::Draw
+        );
+        assert_eq!(tablebase.probe_dtz(&board, Color::Black).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_missing_tables_are_reported() {
+        let tablebase = Tablebase::default();
+        let board = BoardBuilder::new()
+            .add_piece(Position::new(7, 3), PieceKind::King, Color::White)
+            .add_piece(Position::new(6, 3), PieceKind::Queen, Color::White)
+            .add_piece(Position::new(0, 3), PieceKind::King, Color::Black)
+            .build();
+
+        assert!(!tablebase.covers(&board));
+        assert!(matches!(
+            tablebase.probe_wdl(&board, Color::White),
+            Err(EngineError::MissingTable(_))
+        ));
+        assert!(!tablebase.covers(&Board::new()));
+    }
+
+    #[test]
+    fn test_directory_scan_registers_tables() {
+        let dir = std::env::temp_dir().join(format!("syzygy-scan-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "KQvK.rtbw",
+            "KQvK.rtbz",
+            "KRPvKR.rtbw",
+            "README.txt",
+            "notes.rtbw",
+        ] {
+            fs::write(dir.join(name), [0u8; 4]).unwrap();
+        }
+
+        let mut tablebase = Tablebase::default();
+        assert_eq!(tablebase.add_directory(&dir).unwrap(), 3);
+        assert_eq!(tablebase.max_pieces(), 5);
+
+        // The file exists but is not a table
+        let board = BoardBuilder::new()
+            .add_piece(Position::new(7, 3), PieceKind::King, Color::White)
+            .add_piece(Position::new(6, 3), PieceKind::Queen, Color::White)
+            .add_piece(Position::new(0, 3), PieceKind::King, Color::Black)
+            .build();
+        assert!(matches!(
+            tablebase.probe_wdl(&board, Color::White),
+            Err(EngineError::InvalidTablebase(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_krvk_mate_in_one() {
+        let tablebase = krvk();
+        let board = board([
+            ("b6", PieceKind::King, Color::White),
+            ("h1", PieceKind::Rook, Color::White),
+            ("a8", PieceKind::King, Color::Black),
+        ]);
+
+        assert_eq!(tablebase.probe_wdl(&board, Color::White).unwrap(), Wdl::Win);
+        assert_eq!(tablebase.probe_dtz(&board, Color::White).unwrap(), 1);
+
+        let best = tablebase.best_move(&board, Color::White).unwrap().unwrap();
+        assert_eq!(best.mv.to, Position::from_algebraic("h8").unwrap());
+        assert_eq!((best.wdl, best.dtz), (Wdl::Win, 1));
+
+        // Kb8 is forced, then Rh8 mates
+        assert_eq!(
+            tablebase.probe_wdl(&board, Color::Black).unwrap(),
+            Wdl::Loss
+        );
+        assert_eq!(tablebase.probe_dtz(&board, Color::Black).unwrap(), -2);
+    }
+
+    #[test]
+    fn test_krvk_with_colours_swapped() {
+        let tablebase = krvk();
+        let board = board([
+            ("b3", PieceKind::King, Color::Black),
+            ("h8", PieceKind::Rook, Color::Black),
+            ("a1", PieceKind::King, Color::White),
+        ]);
+
+        assert_eq!(tablebase.probe_wdl(&board, Color::Black).unwrap(), Wdl::Win);
+        assert_eq!(tablebase.probe_dtz(&board, Color::Black).unwrap(), 1);
+        assert_eq!(
+            tablebase.probe_wdl(&board, Color::White).unwrap(),
+            Wdl::Loss
+        );
+        assert_eq!(tablebase.probe_dtz(&board, Color::White).unwrap(), -2);
+    }
+
+    #[test]
+    fn test_krvk_undefended_rook_is_a_draw() {
+        let tablebase = krvk();
+        let board = board([
+            ("h1", PieceKind::King, Color::White),
+            ("b7", PieceKind::Rook, Color::White),
+            ("a8", PieceKind::King, Color::Black),
+        ]);
+
+        assert_eq!(
+            tablebase.probe_wdl(&board, Color::Black).unwrap(),
+            Wdl::Draw
+        );
+        assert_eq!(tablebase.probe_dtz(&board, Color::Black).unwrap(), 0);
+        assert_eq!(tablebase.probe_wdl(&board, Color::White).unwrap(), Wdl::Win);
+    }
+
+    #[test]
+    fn test_longest_krvk_win_takes_sixteen_moves() {
+        let tablebase = krvk();
+        let (index, plies) = fixture::krvk_solution()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, dtz)| Some((index, (*dtz)?)))
+            .max_by_key(|&(_, dtz)| dtz)
+            .unwrap();
+        assert_eq!(plies, 31);
+
+        let square = |sq: usize| Position::new(7 - (sq / 8) as i8, (sq % 8) as i8);
+        let board = BoardBuilder::new()
+            .add_piece(square(index / 4096 % 64), PieceKind::King, Color::White)
+            .add_piece(square(index / 64 % 64), PieceKind::Rook, Color::White)
+            .add_piece(square(index % 64), PieceKind::King, Color::Black)
+            .build();
+        assert_eq!(tablebase.probe_dtz(&board, Color::White).unwrap(), 31);
+    }
+}
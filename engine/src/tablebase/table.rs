@@ -0,0 +1,851 @@
+use std::{fs::File, path::Path};
+
+use crate::error::EngineError;
+
+use super::{
+    index::{
+        file, flip_diagonal, flip_file, flip_rank, index_tables, off_diagonal, rank, Square,
+        MAX_PIECES,
+    },
+    material::Material,
+};
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+const FLAG_STM: u8 = 1;
+const FLAG_MAPPED: u8 = 2;
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+const FLAG_WIDE: u8 = 16;
+const FLAG_SINGLE_VALUE: u8 = 128;
+
+const HEADER_HAS_PAWNS: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TableKind {
+    Wdl,
+    Dtz,
+}
+
+impl TableKind {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wdl => "rtbw",
+            Self::Dtz => "rtbz",
+        }
+    }
+
+    fn magic(&self) -> [u8; 4] {
+        match self {
+            Self::Wdl => WDL_MAGIC,
+            Self::Dtz => DTZ_MAGIC,
+        }
+    }
+}
+
+/// Positioned reads from a table file, so a table can be shared between threads without
+/// holding it in memory.
+#[derive(Debug)]
+struct TableFile {
+    file: File,
+    len: u64,
+}
+
+impl TableFile {
+    fn open(path: &Path) -> Result<Self, EngineError> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { file, len })
+    }
+
+    fn read(&self, offset: u64, buf: &mut [u8]) -> Result<(), EngineError> {
+        if offset + buf.len() as u64 > self.len {
+            return Err(EngineError::InvalidTablebase(format!(
+                "Read of {} bytes at {} is past the end of the table",
+                buf.len(),
+                offset
+            )));
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileExt;
+            self.file.read_exact_at(buf, offset)?;
+        }
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::FileExt;
+            let mut done = 0;
+            while done < buf.len() {
+                match self
+                    .file
+                    .seek_read(&mut buf[done..], offset + done as u64)?
+                {
+                    0 => {
+                        return Err(EngineError::InvalidTablebase(
+                            "Unexpected end of table".into(),
+                        ))
+                    }
+                    n => done += n,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn bytes(&self, offset: u64, len: usize) -> Result<Vec<u8>, EngineError> {
+        let mut buf = vec![0; len];
+        self.read(offset, &mut buf)?;
+        Ok(buf)
+    }
+
+    fn u8(&self, offset: u64) -> Result<u8, EngineError> {
+        let mut buf = [0; 1];
+        self.read(offset, &mut buf)?;
+        Ok(buf[0])
+    }
+
+    fn u16(&self, offset: u64) -> Result<u16, EngineError> {
+        let mut buf = [0; 2];
+        self.read(offset, &mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn u32(&self, offset: u64) -> Result<u32, EngineError> {
+        let mut buf = [0; 4];
+        self.read(offset, &mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+}
+
+/// Decoding data of one sub-table: a side to move and, for pawn tables, a file of the
+/// leading pawn. Values are compressed with recursive pairing and canonical Huffman codes.
+#[derive(Debug, Clone, Default)]
+struct PairsData {
+    flags: u8,
+    pieces: [u8; MAX_PIECES],
+    group_len: [usize; MAX_PIECES + 1],
+    group_idx: [u64; MAX_PIECES + 1],
+    block_size: u64,
+    span: u64,
+    num_blocks: u64,
+    min_sym_len: u8,
+    lowest_sym: Vec<u16>,
+    base64: Vec<u64>,
+    symlen: Vec<u8>,
+    btree: Vec<[u8; 3]>,
+    sparse_index: u64,
+    sparse_index_size: u64,
+    block_lengths: u64,
+    block_lengths_size: u64,
+    data: u64,
+    map_idx: [u64; 4],
+}
+
+impl PairsData {
+    fn left(&self, sym: usize) -> usize {
+        let lr = self.btree[sym];
+        ((lr[1] as usize & 0xF) << 8) | lr[0] as usize
+    }
+
+    fn right(&self, sym: usize) -> usize {
+        let lr = self.btree[sym];
+        ((lr[2] as usize) << 4) | (lr[1] as usize >> 4)
+    }
+
+    fn table_size(&self) -> u64 {
+        let groups = self.group_len.iter().position(|&len| len == 0).unwrap_or(0);
+        self.group_idx[groups]
+    }
+}
+
+/// An opened `.rtbw` or `.rtbz` file.
+#[derive(Debug)]
+pub(super) struct Table {
+    kind: TableKind,
+    material: Material,
+    file: TableFile,
+    /// Indexed by the file of the leading pawn (always 0 without pawns), then by side.
+    pairs: Vec<Vec<PairsData>>,
+    dtz_map: u64,
+}
+
+impl Table {
+    pub fn open(path: &Path, kind: TableKind, material: Material) -> Result<Self, EngineError> {
+        let file = TableFile::open(path)?;
+        if file.bytes(0, 4)? != kind.magic() {
+            return Err(EngineError::InvalidTablebase(format!(
+                "{} is not a Syzygy {} table",
+                path.display(),
+                kind.extension()
+            )));
+        }
+
+        let mut table = Self {
+            kind,
+            material,
+            file,
+            pairs: vec![],
+            dtz_map: 0,
+        };
+        table.parse_header()?;
+        Ok(table)
+    }
+
+    /// Looks up a WDL value, from -2 (loss) to 2 (win) for the side to move.
+    pub fn probe_wdl(
+        &self,
+        pieces: &[(Square, u8)],
+        black_to_move: bool,
+        black_stronger: bool,
+    ) -> Result<i32, EngineError> {
+        let (_, value) = self
+            .lookup(pieces, black_to_move, black_stronger)?
+            .ok_or_else(|| EngineError::InvalidTablebase("WDL tables are two-sided".into()))?;
+        Ok(value as i32 - 2)
+    }
+
+    /// Looks up the distance to zeroing in plies, or `None` when the table only stores
+    /// positions with the other side to move.
+    pub fn probe_dtz(
+        &self,
+        pieces: &[(Square, u8)],
+        black_to_move: bool,
+        black_stronger: bool,
+        wdl: i32,
+    ) -> Result<Option<i32>, EngineError> {
+        let Some((tb_file, value)) = self.lookup(pieces, black_to_move, black_stronger)? else {
+            return Ok(None);
+        };
+
+        let d = &self.pairs[tb_file][0];
+        let mut value = value;
+
+        if d.flags & FLAG_MAPPED != 0 {
+            const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+            let idx = d.map_idx[WDL_MAP[(wdl + 2) as usize]] + value;
+            value = match d.flags & FLAG_WIDE != 0 {
+                true => self.file.u16(self.dtz_map + 2 * idx)? as u64,
+                false => self.file.u8(self.dtz_map + idx)? as u64,
+            };
+        }
+
+        let in_moves = (wdl == 2 && d.flags & FLAG_WIN_PLIES == 0)
+            || (wdl == -2 && d.flags & FLAG_LOSS_PLIES == 0)
+            || wdl.abs() == 1;
+
+        let plies = if in_moves { value * 2 } else { value };
+        Ok(Some(plies as i32 + 1))
+    }
+}
+
+impl Table {
+    fn sides(&self) -> usize {
+        match (self.kind, self.material.is_symmetric()) {
+            (TableKind::Wdl, false) => 2,
+            _ => 1,
+        }
+    }
+
+    fn parse_header(&mut self) -> Result<(), EngineError> {
+        let material = self.material.clone();
+        let has_pawns = material.has_pawns();
+        let both_have_pawns = has_pawns && material.pawn_counts()[1] > 0;
+        let sides = self.sides();
+        let files = if has_pawns { 4 } else { 1 };
+
+        let header = self.file.u8(4)?;
+        if (header & HEADER_HAS_PAWNS != 0) != has_pawns {
+            return Err(EngineError::InvalidTablebase(format!(
+                "Header of {} does not match its pawns",
+                material.name()
+            )));
+        }
+
+        let mut offset = 5;
+        self.pairs = vec![vec![PairsData::default(); sides]; files];
+
+        for f in 0..files {
+            let order_bytes = self.file.bytes(offset, 2)?;
+            let order = [
+                [
+                    order_bytes[0] & 0xF,
+                    if both_have_pawns {
+                        order_bytes[1] & 0xF
+                    } else {
+                        0xF
+                    },
+                ],
+                [
+                    order_bytes[0] >> 4,
+                    if both_have_pawns {
+                        order_bytes[1] >> 4
+                    } else {
+                        0xF
+                    },
+                ],
+            ];
+            offset += 1 + both_have_pawns as u64;
+
+            let pieces = self.file.bytes(offset, material.piece_count())?;
+            offset += pieces.len() as u64;
+
+            for (side, d) in self.pairs[f].iter_mut().enumerate() {
+                for (k, byte) in pieces.iter().enumerate() {
+                    d.pieces[k] = if side == 1 { byte >> 4 } else { byte & 0xF };
+                }
+                Self::set_groups(&material, d, order[side], f)?;
+            }
+        }
+
+        offset += offset & 1;
+
+        for f in 0..files {
+            for side in 0..sides {
+                offset = self.set_sizes(f, side, offset)?;
+            }
+        }
+
+        if self.kind == TableKind::Dtz {
+            offset = self.set_dtz_map(offset)?;
+        }
+
+        for d in self.pairs.iter_mut().flatten() {
+            d.sparse_index = offset;
+            offset += d.sparse_index_size * 6;
+        }
+
+        for d in self.pairs.iter_mut().flatten() {
+            d.block_lengths = offset;
+            offset += d.block_lengths_size * 2;
+        }
+
+        for d in self.pairs.iter_mut().flatten() {
+            offset = (offset + 0x3F) & !0x3F;
+            d.data = offset;
+            offset += d.num_blocks * d.block_size;
+        }
+
+        if offset > self.file.len {
+            return Err(EngineError::InvalidTablebase(format!(
+                "Table {} is truncated",
+                material.name()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Splits the pieces into groups that are encoded together and computes the factor of
+    /// each group in the final index. The leading group holds the leading pawns, or the
+    /// kings and possibly a unique piece; the rest are runs of identical pieces.
+    fn set_groups(
+        material: &Material,
+        d: &mut PairsData,
+        order: [u8; 2],
+        f: usize,
+    ) -> Result<(), EngineError> {
+        let tables = index_tables();
+        let has_pawns = material.has_pawns();
+        let both_have_pawns = has_pawns && material.pawn_counts()[1] > 0;
+
+        let mut n = 0;
+        let mut first_len: i32 = match (has_pawns, material.has_unique_pieces()) {
+            (true, _) => 0,
+            (false, true) => 3,
+            (false, false) => 2,
+        };
+
+        d.group_len[0] = 1;
+        for i in 1..material.piece_count() {
+            first_len -= 1;
+            if first_len > 0 || d.pieces[i] == d.pieces[i - 1] {
+                d.group_len[n] += 1;
+            } else {
+                n += 1;
+                d.group_len[n] = 1;
+            }
+        }
+        n += 1;
+        d.group_len[n] = 0;
+
+        if d.group_len.iter().any(|&len| len > 5) {
+            return Err(EngineError::InvalidTablebase(format!(
+                "Table {} has an unsupported piece grouping",
+                material.name()
+            )));
+        }
+
+        let mut next = if both_have_pawns { 2 } else { 1 };
+        let mut free_squares =
+            64 - d.group_len[0] - if both_have_pawns { d.group_len[1] } else { 0 };
+        let mut idx: u64 = 1;
+
+        let mut k = 0;
+        while k < 16 && (next < n || k == order[0] || k == order[1]) {
+            if k == order[0] {
+                d.group_idx[0] = idx;
+                idx *= match (has_pawns, material.has_unique_pieces()) {
+                    (true, _) => tables.lead_pawns_size[d.group_len[0]][f],
+                    (false, true) => 31332,
+                    (false, false) => 462,
+                };
+            } else if k == order[1] {
+                d.group_idx[1] = idx;
+                idx *= tables.binomial[d.group_len[1]][48 - d.group_len[0]];
+            } else {
+                d.group_idx[next] = idx;
+                idx *= tables.binomial[d.group_len[next]][free_squares];
+                free_squares -= d.group_len[next];
+                next += 1;
+            }
+            k += 1;
+        }
+        d.group_idx[n] = idx;
+
+        Ok(())
+    }
+
+    fn set_sizes(&mut self, f: usize, side: usize, mut offset: u64) -> Result<u64, EngineError> {
+        let file = &self.file;
+        let d = &mut self.pairs[f][side];
+
+        d.flags = file.u8(offset)?;
+        offset += 1;
+
+        if d.flags & FLAG_SINGLE_VALUE != 0 {
+            // The single value every position of the table maps to
+            d.min_sym_len = file.u8(offset)?;
+            return Ok(offset + 1);
+        }
+
+        let sizes = file.bytes(offset, 9)?;
+        d.block_size = 1 << sizes[0];
+        d.span = 1 << sizes[1];
+        d.sparse_index_size = d.table_size().div_ceil(d.span);
+        let padding = sizes[2] as u64;
+        d.num_blocks = u32::from_le_bytes([sizes[3], sizes[4], sizes[5], sizes[6]]) as u64;
+        d.block_lengths_size = d.num_blocks + padding;
+        let max_sym_len = sizes[7];
+        d.min_sym_len = sizes[8];
+        offset += 9;
+
+        if max_sym_len < d.min_sym_len || d.min_sym_len == 0 {
+            return Err(EngineError::InvalidTablebase(
+                "Invalid Huffman symbol lengths".into(),
+            ));
+        }
+
+        let lengths = (max_sym_len - d.min_sym_len + 1) as usize;
+        d.lowest_sym = file
+            .bytes(offset, 2 * lengths)?
+            .chunks_exact(2)
+            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect();
+        offset += 2 * lengths as u64;
+
+        // Canonical Huffman codes: longer codes have lower values, so base64[i] holds the
+        // lowest left-aligned code of length i + min_sym_len
+        d.base64 = vec![0; lengths];
+        for i in (0..lengths - 1).rev() {
+            d.base64[i] = d.base64[i + 1]
+                .wrapping_add(d.lowest_sym[i] as u64)
+                .wrapping_sub(d.lowest_sym[i + 1] as u64)
+                / 2;
+        }
+        for (i, base) in d.base64.iter_mut().enumerate() {
+            *base = base
+                .checked_shl(64 - i as u32 - d.min_sym_len as u32)
+                .unwrap_or(0);
+        }
+
+        let symbols = file.u16(offset)? as usize;
+        offset += 2;
+        d.btree = file
+            .bytes(offset, 3 * symbols)?
+            .chunks_exact(3)
+            .map(|chunk| [chunk[0], chunk[1], chunk[2]])
+            .collect();
+        offset += 3 * symbols as u64 + (symbols & 1) as u64;
+
+        if (0..symbols)
+            .any(|sym| d.right(sym) != 0xFFF && (d.left(sym) >= symbols || d.right(sym) >= symbols))
+        {
+            return Err(EngineError::InvalidTablebase("Invalid symbol tree".into()));
+        }
+
+        d.symlen = vec![0; symbols];
+        let mut visited = vec![false; symbols];
+        for sym in 0..symbols {
+            if !visited[sym] {
+                d.symlen[sym] = Self::set_symlen(d, sym, &mut visited);
+            }
+        }
+
+        Ok(offset)
+    }
+
+    /// Each symbol of recursive pairing stands for a pair of symbols; `symlen` is the number
+    /// of values a symbol expands to, minus one.
+    fn set_symlen(d: &mut PairsData, sym: usize, visited: &mut [bool]) -> u8 {
+        visited[sym] = true;
+
+        let right = d.right(sym);
+        if right == 0xFFF {
+            return 0;
+        }
+
+        let left = d.left(sym);
+        if !visited[left] {
+            d.symlen[left] = Self::set_symlen(d, left, visited);
+        }
+        if !visited[right] {
+            d.symlen[right] = Self::set_symlen(d, right, visited);
+        }
+
+        d.symlen[left].wrapping_add(d.symlen[right]).wrapping_add(1)
+    }
+
+    fn set_dtz_map(&mut self, mut offset: u64) -> Result<u64, EngineError> {
+        self.dtz_map = offset;
+
+        for f in 0..self.pairs.len() {
+            let flags = self.pairs[f][0].flags;
+            if flags & FLAG_MAPPED == 0 {
+                continue;
+            }
+
+            if flags & FLAG_WIDE != 0 {
+                offset += offset & 1;
+                for i in 0..4 {
+                    self.pairs[f][0].map_idx[i] = (offset - self.dtz_map) / 2 + 1;
+                    offset += 2 * self.file.u16(offset)? as u64 + 2;
+                }
+            } else {
+                for i in 0..4 {
+                    self.pairs[f][0].map_idx[i] = offset - self.dtz_map + 1;
+                    offset += self.file.u8(offset)? as u64 + 1;
+                }
+            }
+        }
+
+        Ok(offset + (offset & 1))
+    }
+
+    /// Maps a position to its table index and decodes the stored value. `pieces` holds the
+    /// board's squares and piece codes ordered by square.
+    fn lookup(
+        &self,
+        pieces: &[(Square, u8)],
+        black_to_move: bool,
+        black_stronger: bool,
+    ) -> Result<Option<(usize, u64)>, EngineError> {
+        let Some((tb_file, side, idx)) = self.index(pieces, black_to_move, black_stronger)? else {
+            return Ok(None);
+        };
+        Ok(Some((
+            tb_file,
+            self.decompress(&self.pairs[tb_file][side], idx)?,
+        )))
+    }
+
+    /// The file of the leading pawn, the side and the index a position is stored under, or
+    /// `None` when the table only stores positions with the other side to move.
+    pub(super) fn index(
+        &self,
+        pieces: &[(Square, u8)],
+        black_to_move: bool,
+        black_stronger: bool,
+    ) -> Result<Option<(usize, usize, u64)>, EngineError> {
+        let tables = index_tables();
+        let material = &self.material;
+
+        // Tables are stored with the stronger side as white, and symmetric ones only with
+        // white to move, so the position may have to be mirrored and its colours swapped
+        let flip = (material.is_symmetric() && black_to_move) || black_stronger;
+        let flip_color = if flip { 8 } else { 0 };
+        let flip_squares = if flip { 56 } else { 0 };
+        let stm = (flip ^ black_to_move) as usize;
+
+        let mut squares = [0; MAX_PIECES];
+        let mut codes = [0; MAX_PIECES];
+        let mut size = 0;
+        let mut lead_pawns = 0;
+        let mut tb_file = 0;
+
+        if material.has_pawns() {
+            let lead_code = self.pairs[0][0].pieces[0] ^ flip_color;
+            for &(sq, _) in pieces.iter().filter(|(_, code)| *code == lead_code) {
+                squares[size] = sq ^ flip_squares;
+                size += 1;
+            }
+            lead_pawns = size;
+
+            // The leading pawn is the first one with the highest pawn index
+            let lead = (0..lead_pawns).fold(0, |best, i| {
+                match tables.map_pawns[squares[i]] > tables.map_pawns[squares[best]] {
+                    true => i,
+                    false => best,
+                }
+            });
+            squares.swap(0, lead);
+            tb_file = file(squares[0]).min(7 - file(squares[0]));
+        }
+
+        if self.kind == TableKind::Dtz {
+            let flags = self.pairs[tb_file][0].flags;
+            let stores_stm = (flags & FLAG_STM) as usize == stm;
+            let one_sided = !material.is_symmetric() || material.has_pawns();
+            if !stores_stm && one_sided {
+                return Ok(None);
+            }
+        }
+
+        let lead_code = match material.has_pawns() {
+            true => Some(self.pairs[0][0].pieces[0] ^ flip_color),
+            false => None,
+        };
+        for &(sq, code) in pieces.iter().filter(|(_, code)| Some(*code) != lead_code) {
+            squares[size] = sq ^ flip_squares;
+            codes[size] = code ^ flip_color;
+            size += 1;
+        }
+
+        if size != material.piece_count() {
+            return Err(EngineError::InvalidTablebase(format!(
+                "Position does not match table {}",
+                material.name()
+            )));
+        }
+
+        let side = stm % self.sides();
+        let d = &self.pairs[tb_file][side];
+
+        // Reorder the pieces to the sequence the table was generated with
+        for i in lead_pawns..size.saturating_sub(1) {
+            if let Some(j) = (i + 1..size).find(|&j| codes[j] == d.pieces[i]) {
+                codes.swap(i, j);
+                squares.swap(i, j);
+            }
+        }
+
+        if file(squares[0]) > 3 {
+            squares[..size]
+                .iter_mut()
+                .for_each(|sq| *sq = flip_file(*sq));
+        }
+
+        let mut idx = if material.has_pawns() {
+            let mut idx = tables.lead_pawn_idx[lead_pawns][squares[0]];
+            squares[1..lead_pawns].sort_by_key(|&sq| tables.map_pawns[sq]);
+            for (i, &sq) in squares.iter().enumerate().take(lead_pawns).skip(1) {
+                idx += tables.binomial[i][tables.map_pawns[sq] as usize];
+            }
+            idx
+        } else {
+            if rank(squares[0]) > 3 {
+                squares[..size]
+                    .iter_mut()
+                    .for_each(|sq| *sq = flip_rank(*sq));
+            }
+
+            // Mirror so that the first leading piece off the a1-h8 diagonal is below it
+            if let Some(i) = (0..d.group_len[0]).find(|&i| off_diagonal(squares[i]) != 0) {
+                if off_diagonal(squares[i]) > 0 {
+                    squares[i..size]
+                        .iter_mut()
+                        .for_each(|sq| *sq = flip_diagonal(*sq));
+                }
+            }
+
+            match material.has_unique_pieces() {
+                true => Self::encode_unique_pieces(&squares),
+                false => tables.map_kk[tables.map_a1d1d4[squares[0]] as usize][squares[1]],
+            }
+        };
+
+        idx *= d.group_idx[0];
+
+        let mut remaining_pawns = material.has_pawns() && material.pawn_counts()[1] > 0;
+        let mut start = d.group_len[0];
+        let mut next = 1;
+
+        while d.group_len[next] > 0 {
+            let len = d.group_len[next];
+            squares[start..start + len].sort_unstable();
+
+            let mut n = 0;
+            for i in 0..len {
+                let sq = squares[start + i];
+                let adjust = squares[..start].iter().filter(|&&s| sq > s).count();
+                let pawn_offset = if remaining_pawns { 8 } else { 0 };
+                n += tables.binomial[i + 1][sq - adjust - pawn_offset];
+            }
+
+            remaining_pawns = false;
+            idx += n * d.group_idx[next];
+            start += len;
+            next += 1;
+        }
+
+        Ok(Some((tb_file, side, idx)))
+    }
+
+    /// Encodes the two kings and a unique piece together, in one of 31332 ways.
+    fn encode_unique_pieces(squares: &[Square]) -> u64 {
+        let tables = index_tables();
+        let [s0, s1, s2] = [squares[0], squares[1], squares[2]];
+        let adjust1 = (s1 > s0) as u64;
+        let adjust2 = (s2 > s0) as u64 + (s2 > s1) as u64;
+        let [s0, s1, s2] = [s0 as u64, s1 as u64, s2 as u64];
+        let rank = |sq: u64| rank(sq as usize) as u64;
+
+        if off_diagonal(squares[0]) != 0 {
+            (tables.map_a1d1d4[squares[0]] * 63 + (s1 - adjust1)) * 62 + s2 - adjust2
+        } else if off_diagonal(squares[1]) != 0 {
+            (6 * 63 + rank(s0) * 28 + tables.map_b1h1h7[squares[1]]) * 62 + s2 - adjust2
+        } else if off_diagonal(squares[2]) != 0 {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + rank(s0) * 7 * 28
+                + (rank(s1) - adjust1) * 28
+                + tables.map_b1h1h7[squares[2]]
+        } else {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + 4 * 7 * 28
+                + rank(s0) * 7 * 6
+                + (rank(s1) - adjust1) * 6
+                + (rank(s2) - adjust2)
+        }
+    }
+
+    fn decompress(&self, d: &PairsData, idx: u64) -> Result<u64, EngineError> {
+        if d.flags & FLAG_SINGLE_VALUE != 0 {
+            return Ok(d.min_sym_len as u64);
+        }
+
+        let invalid = || EngineError::InvalidTablebase("Corrupt table data".into());
+
+        // The sparse index points into the block list at every span / 2 + k * span values
+        let k = idx / d.span;
+        if k >= d.sparse_index_size {
+            return Err(invalid());
+        }
+        let entry = d.sparse_index + 6 * k;
+        let mut block = self.file.u32(entry)? as u64;
+        let mut offset = self.file.u16(entry + 4)? as i64;
+        offset += (idx % d.span) as i64 - (d.span / 2) as i64;
+
+        let block_length = |block: u64| match block < d.block_lengths_size {
+            true => self
+                .file
+                .u16(d.block_lengths + 2 * block)
+                .map(|len| len as i64),
+            false => Err(invalid()),
+        };
+
+        while offset < 0 {
+            block = block.checked_sub(1).ok_or_else(invalid)?;
+            offset += block_length(block)? + 1;
+        }
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+
+        let start = d.data + block * d.block_size;
+        let len = d.block_size.min(self.file.len.saturating_sub(start)) as usize;
+        let mut bytes = self.file.bytes(start, len)?;
+        bytes.resize((d.block_size as usize).max(8), 0);
+
+        let word = |i: usize| match bytes.get(i..i + 4) {
+            Some(word) => u32::from_be_bytes([word[0], word[1], word[2], word[3]]) as u64,
+            None => 0,
+        };
+
+        let mut buf64 = (word(0) << 32) | word(4);
+        let mut buf_size = 64;
+        let mut ptr = 8;
+        let min_sym_len = d.min_sym_len as usize;
+
+        let mut sym = loop {
+            let mut len = 0;
+            while buf64 < d.base64[len] {
+                len += 1;
+                if len >= d.base64.len() {
+                    return Err(invalid());
+                }
+            }
+
+            let code = (buf64 - d.base64[len]) >> (64 - len - min_sym_len);
+            let sym = (code as u16).wrapping_add(d.lowest_sym[len]) as usize;
+            if sym >= d.symlen.len() {
+                return Err(invalid());
+            }
+
+            if offset < d.symlen[sym] as i64 + 1 {
+                break sym;
+            }
+
+            offset -= d.symlen[sym] as i64 + 1;
+            len += min_sym_len;
+            buf64 <<= len;
+            buf_size -= len;
+
+            if buf_size <= 32 {
+                buf_size += 32;
+                buf64 |= word(ptr) << (64 - buf_size);
+                ptr += 4;
+            }
+        };
+
+        // Expand the pair symbols until reaching the single value at our offset
+        while d.symlen[sym] != 0 {
+            let left = d.left(sym);
+            if offset < d.symlen[left] as i64 + 1 {
+                sym = left;
+            } else {
+                offset -= d.symlen[left] as i64 + 1;
+                sym = d.right(sym);
+            }
+        }
+
+        Ok(d.left(sym) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::fixture, *};
+
+    #[test]
+    fn test_krvk_tables_match_the_solution() {
+        let material = Material::parse("KRvK").unwrap();
+        let dir = fixture::krvk_dir();
+        let wdl = Table::open(&dir.join("KRvK.rtbw"), TableKind::Wdl, material.clone()).unwrap();
+        let dtz = Table::open(&dir.join("KRvK.rtbz"), TableKind::Dtz, material).unwrap();
+
+        for (index, value) in fixture::krvk_solution().iter().enumerate() {
+            let Some(value) = *value else {
+                continue;
+            };
+            let (pieces, black_to_move) = fixture::krvk_pieces(index);
+
+            assert_eq!(
+                wdl.probe_wdl(&pieces, black_to_move, false).unwrap(),
+                2 * value.signum()
+            );
+            // Only white to move is stored
+            let stored = dtz.probe_dtz(&pieces, black_to_move, false, 2).unwrap();
+            match black_to_move {
+                true => assert_eq!(stored, None),
+                false if value > 0 => assert_eq!(stored, Some(value)),
+                false => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use domain::{
+    board::Board,
+    game::{GameState, Pgn},
+};
+
+use crate::{
+    arena::{epd_operation, parse_epd_record},
+    error::EngineError,
+};
+
+/// Plies at the start of every PGN game that are left out of the data set. Opening
+/// positions mostly say something about the players' books, not about the evaluation.
+const SKIPPED_OPENING_PLIES: usize = 8;
+
+/// A position and the result of the game it was played in, 1 for a white win, 0.5 for a
+/// draw and 0 for a black win.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelledPosition {
+    pub board: Board,
+    pub result: f64,
+}
+
+/// Reads labelled positions from EPD, where the result is given by the `c9` operation, or
+/// from PGN, where every position of a finished game is labelled with its result. The
+/// format is told apart like an opening suite. Positions with the side to move in check
+/// are skipped, as a static evaluation says little about them.
+pub fn parse_labelled_positions(text: &str) -> Result<Vec<LabelledPosition>, EngineError> {
+    let first_line = text.lines().map(str::trim).find(|line| !line.is_empty());
+    let positions = match first_line {
+        None => vec![],
+        Some(line) if line.starts_with('[') || line.starts_with("1.") => parse_pgn(text)?,
+        Some(_) => parse_epd(text)?,
+    };
+
+    if positions.is_empty() {
+        return Err(EngineError::InvalidDataset(
+            "The data set has no labelled positions".to_string(),
+        ));
+    }
+    Ok(positions)
+}
+
+fn parse_epd(text: &str) -> Result<Vec<LabelledPosition>, EngineError> {
+    let mut positions = vec![];
+    let lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+
+    for (i, line) in lines {
+        let invalid = |e: String| EngineError::InvalidDataset(format!("Line {}: {}", i + 1, e));
+        let game_state = parse_epd_record(line).map_err(invalid)?;
+        let result = epd_operation(line, "c9")
+            .as_deref()
+            .and_then(parse_result)
+            .ok_or_else(|| invalid("Missing or invalid c9 result".to_string()))?;
+        push_quiet(&mut positions, &game_state, result);
+    }
+
+    Ok(positions)
+}
+
+fn parse_pgn(text: &str) -> Result<Vec<LabelledPosition>, EngineError> {
+    let mut positions = vec![];
+
+    for (i, pgn) in Pgn::parse_all(text).iter().enumerate() {
+        let result = pgn.result.as_deref().or_else(|| pgn.tag("Result"));
+        let Some(result) = result.and_then(parse_result) else {
+            continue;
+        };
+
+        let invalid = |e| EngineError::InvalidDataset(format!("Game {}: {}", i + 1, e));
+        let mut game_state = pgn.start_position().map_err(invalid)?;
+        for (ply, m) in pgn.mainline().map_err(invalid)?.into_iter().enumerate() {
+            if ply >= SKIPPED_OPENING_PLIES {
+                push_quiet(&mut positions, &game_state, result);
+            }
+            game_state.make_move(m);
+        }
+    }
+
+    Ok(positions)
+}
+
+fn push_quiet(positions: &mut Vec<LabelledPosition>, game_state: &GameState, result: f64) {
+    if !game_state.board.is_in_check(game_state.current_player) {
+        positions.push(LabelledPosition {
+            board: game_state.board.clone(),
+            result,
+        });
+    }
+}
+
+fn parse_result(result: &str) -> Option<f64> {
+    match result {
+        "1-0" => Some(1.0),
+        "1/2-1/2" => Some(0.5),
+        "0-1" => Some(0.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epd_with_results() {
+        let text = "\
+4k3/8/8/8/8/8/8/3QK3 w - - c9 \"1-0\";
+4k3/8/8/8/8/8/q7/4K3 w - - hmvc 3; c9 \"0-1\";
+4k3/8/8/8/8/8/8/4K3 b - - c9 \"1/2-1/2\";
+";
+        let positions = parse_labelled_positions(text).unwrap();
+        assert_eq!(
+            positions.iter().map(|p| p.result).collect::<Vec<_>>(),
+            vec![1.0, 0.0, 0.5]
+        );
+
+        assert!(parse_labelled_positions("4k3/8/8/8/8/8/8/4K3 b - - c9 \"*\";").is_err());
+    }
+
+    #[test]
+    fn test_pgn_positions_skip_opening_and_checks() {
+        let text = "\
+[Result \"1-0\"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bc4 Nf6 4. Ng5 d5 5. exd5 Nxd5 6. Nxf7 Kxf7 7. Qf3+ Ke6 1-0
+
+[Result \"*\"]
+
+1. d4 d5 *
+";
+        let positions = parse_labelled_positions(text).unwrap();
+
+        // Plies 9 to 14, without the position after Qf3+
+        assert_eq!(positions.len(), 5);
+        assert!(positions.iter().all(|p| p.result == 1.0));
+    }
+}
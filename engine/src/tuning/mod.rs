@@ -0,0 +1,5 @@
+pub mod dataset;
+pub mod tuner;
+
+pub use dataset::*;
+pub use tuner::*;
@@ -0,0 +1,267 @@
+use domain::{board::Board, game::Color};
+
+use crate::eval::{EvalParams, Phased, MAX_PHASE, PHASE_WEIGHTS};
+
+use super::LabelledPosition;
+
+/// Weights in the flat layout the tuner works on: material, piece-square tables and the
+/// bishop pair, each as a middlegame and an endgame value.
+const MATERIAL: usize = 0;
+const PST: usize = MATERIAL + 6 * 2;
+const BISHOP_PAIR: usize = PST + 6 * 64 * 2;
+const WEIGHT_COUNT: usize = BISHOP_PAIR + 2;
+
+const ADAM_BETA1: f64 = 0.9;
+const ADAM_BETA2: f64 = 0.999;
+const ADAM_EPSILON: f64 = 1e-8;
+
+/// A Texel tuner for `EvalParams`.
+///
+/// The evaluation is linear in its parameters once the game phase is known, so every
+/// position is reduced to the coefficients of the weights it uses. The error is the mean
+/// squared difference between the game results and the sigmoid of the evaluation, and is
+/// minimised by full-batch gradient descent with Adam steps.
+#[derive(Debug, Clone)]
+pub struct Tuner {
+    samples: Vec<Sample>,
+    weights: Vec<f64>,
+    scaling: f64,
+    step: i32,
+    momentum: Vec<f64>,
+    velocity: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct Sample {
+    coefficients: Vec<(usize, f64)>,
+    result: f64,
+}
+
+impl Tuner {
+    pub fn new(positions: &[LabelledPosition], params: &EvalParams) -> Self {
+        Self {
+            samples: positions
+                .iter()
+                .map(|position| Sample {
+                    coefficients: coefficients(&position.board),
+                    result: position.result,
+                })
+                .collect(),
+            weights: to_weights(params),
+            scaling: 1.0,
+            step: 0,
+            momentum: vec![0.0; WEIGHT_COUNT],
+            velocity: vec![0.0; WEIGHT_COUNT],
+        }
+    }
+
+    /// The scaling constant K of the sigmoid.
+    pub fn scaling(&self) -> f64 {
+        self.scaling
+    }
+
+    /// Finds the K that fits the current weights best, so tuning changes the weights and
+    /// not just the scale of the evaluation.
+    pub fn fit_scaling(&mut self) -> f64 {
+        let (mut low, mut high) = (0.01, 5.0);
+        for _ in 0..60 {
+            let a = low + (high - low) / 3.0;
+            let b = high - (high - low) / 3.0;
+            if self.error_with(a) < self.error_with(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+
+        self.scaling = (low + high) / 2.0;
+        self.scaling
+    }
+
+    /// Mean squared error of the current weights.
+    pub fn error(&self) -> f64 {
+        self.error_with(self.scaling)
+    }
+
+    /// One gradient descent step over all positions. `learning_rate` is in centipawns.
+    pub fn epoch(&mut self, learning_rate: f64) {
+        let mut gradient = vec![0.0; WEIGHT_COUNT];
+        let slope = std::f64::consts::LN_10 * self.scaling / 400.0;
+
+        for sample in &self.samples {
+            let predicted = self.predict(sample, self.scaling);
+            let delta = -2.0 * (sample.result - predicted) * predicted * (1.0 - predicted) * slope;
+            for (index, coefficient) in &sample.coefficients {
+                gradient[*index] += delta * coefficient;
+            }
+        }
+
+        self.step += 1;
+        let count = self.samples.len().max(1) as f64;
+        let correction1 = 1.0 - ADAM_BETA1.powi(self.step);
+        let correction2 = 1.0 - ADAM_BETA2.powi(self.step);
+        for (i, gradient) in gradient.iter().enumerate() {
+            let gradient = gradient / count;
+            self.momentum[i] = ADAM_BETA1 * self.momentum[i] + (1.0 - ADAM_BETA1) * gradient;
+            self.velocity[i] =
+                ADAM_BETA2 * self.velocity[i] + (1.0 - ADAM_BETA2) * gradient * gradient;
+
+            let momentum = self.momentum[i] / correction1;
+            let velocity = self.velocity[i] / correction2;
+            self.weights[i] -= learning_rate * momentum / (velocity.sqrt() + ADAM_EPSILON);
+        }
+    }
+
+    /// The tuned weights, rounded to whole centipawns.
+    pub fn params(&self) -> EvalParams {
+        from_weights(&self.weights)
+    }
+}
+
+impl Tuner {
+    fn predict(&self, sample: &Sample, scaling: f64) -> f64 {
+        let eval: f64 = sample
+            .coefficients
+            .iter()
+            .map(|(index, coefficient)| self.weights[*index] * coefficient)
+            .sum();
+        1.0 / (1.0 + 10f64.powf(-scaling * eval / 400.0))
+    }
+
+    fn error_with(&self, scaling: f64) -> f64 {
+        let total: f64 = self
+            .samples
+            .iter()
+            .map(|sample| (sample.result - self.predict(sample, scaling)).powi(2))
+            .sum();
+        total / self.samples.len().max(1) as f64
+    }
+}
+
+/// The coefficient of every weight in the evaluation of `board` from white's point of
+/// view, mirroring `evaluate`.
+fn coefficients(board: &Board) -> Vec<(usize, f64)> {
+    let mut pieces = vec![];
+    let mut phase = 0;
+    let mut bishops = [0; 2];
+
+    for (row, fields) in board.fields.iter().enumerate() {
+        for (column, piece) in fields.iter().enumerate() {
+            let Some(piece) = piece else {
+                continue;
+            };
+
+            let kind = piece.piece_type.as_index();
+            let (sign, square, color) = match piece.piece_color {
+                Color::White => (1.0, 8 * row + column, 0),
+                Color::Black => (-1.0, 8 * (7 - row) + column, 1),
+            };
+            pieces.push((kind, square, sign));
+            phase += PHASE_WEIGHTS[kind];
+            if kind == 2 {
+                bishops[color] += 1;
+            }
+        }
+    }
+
+    let mg = phase.min(MAX_PHASE) as f64 / MAX_PHASE as f64;
+    let eg = 1.0 - mg;
+    let mut coefficients = Vec::with_capacity(pieces.len() * 4 + 2);
+    for (kind, square, sign) in pieces {
+        let pst = PST + (kind * 64 + square) * 2;
+        coefficients.extend([
+            (MATERIAL + kind * 2, sign * mg),
+            (MATERIAL + kind * 2 + 1, sign * eg),
+            (pst, sign * mg),
+            (pst + 1, sign * eg),
+        ]);
+    }
+
+    let pair = f64::from(u8::from(bishops[0] >= 2)) - f64::from(u8::from(bishops[1] >= 2));
+    if pair != 0.0 {
+        coefficients.extend([(BISHOP_PAIR, pair * mg), (BISHOP_PAIR + 1, pair * eg)]);
+    }
+    coefficients
+}
+
+fn to_weights(params: &EvalParams) -> Vec<f64> {
+    let pairs = params
+        .material
+        .iter()
+        .chain(params.pst.iter().flatten())
+        .chain([&params.bishop_pair]);
+    pairs
+        .flat_map(|value| [value.mg as f64, value.eg as f64])
+        .collect()
+}
+
+fn from_weights(weights: &[f64]) -> EvalParams {
+    let pair = |index: usize| {
+        Phased::new(
+            weights[index].round() as i32,
+            weights[index + 1].round() as i32,
+        )
+    };
+
+    let mut params = EvalParams::default();
+    for (kind, value) in params.material.iter_mut().enumerate() {
+        *value = pair(MATERIAL + kind * 2);
+    }
+    for (kind, table) in params.pst.iter_mut().enumerate() {
+        for (square, value) in table.iter_mut().enumerate() {
+            *value = pair(PST + (kind * 64 + square) * 2);
+        }
+    }
+    params.bishop_pair = pair(BISHOP_PAIR);
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::game::GameState;
+
+    use super::*;
+    use crate::eval::evaluate;
+
+    fn position(fen: &str, result: f64) -> LabelledPosition {
+        LabelledPosition {
+            board: GameState::from_fen(fen).unwrap().board,
+            result,
+        }
+    }
+
+    #[test]
+    fn test_weights_reproduce_the_evaluation() {
+        let params = EvalParams::default();
+        let weights = to_weights(&params);
+        assert_eq!(from_weights(&weights), params);
+
+        let fen = "r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+        let board = GameState::from_fen(fen).unwrap().board;
+        let linear: f64 = coefficients(&board)
+            .iter()
+            .map(|(index, coefficient)| weights[*index] * coefficient)
+            .sum();
+        assert!((linear - evaluate(&board, Color::White, &params) as f64).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_descent_lowers_the_error() {
+        // Each side wins with the extra knight, so the knight is worth more than 320
+        let positions = [
+            position("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1", 1.0),
+            position("1n2k3/8/8/8/8/8/8/4K3 w - - 0 1", 0.0),
+            position("4k3/8/8/8/8/8/8/4K3 w - - 0 1", 0.5),
+        ];
+        let mut tuner = Tuner::new(&positions, &EvalParams::default());
+        let scaling = tuner.fit_scaling();
+        assert!(scaling > 0.0);
+
+        let before = tuner.error();
+        for _ in 0..50 {
+            tuner.epoch(5.0);
+        }
+        assert!(tuner.error() < before);
+        assert!(tuner.params().material[1].eg > 320);
+    }
+}
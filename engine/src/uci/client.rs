@@ -0,0 +1,424 @@
+use std::{
+    collections::VecDeque,
+    ffi::OsStr,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use domain::game::GameState;
+
+use crate::error::EngineError;
+
+use super::{position_command, BestMove, EngineMessage, OptionKind, UciOption};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+const QUIT_GRACE_PERIOD: Duration = Duration::from_millis(200);
+
+/// Limits passed to the engine with the `go` command. Unset fields are omitted.
+#[derive(Debug, Clone, Default)]
+pub struct GoParams {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub mate: Option<u32>,
+    pub movetime: Option<Duration>,
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    pub movestogo: Option<u32>,
+    pub infinite: bool,
+}
+
+impl GoParams {
+    pub fn command(&self) -> String {
+        let mut command = String::from("go");
+
+        let numbers = [
+            ("depth", self.depth.map(u64::from)),
+            ("nodes", self.nodes),
+            ("mate", self.mate.map(u64::from)),
+            ("movetime", self.movetime.map(|t| t.as_millis() as u64)),
+            ("wtime", self.wtime.map(|t| t.as_millis() as u64)),
+            ("btime", self.btime.map(|t| t.as_millis() as u64)),
+            ("winc", self.winc.map(|t| t.as_millis() as u64)),
+            ("binc", self.binc.map(|t| t.as_millis() as u64)),
+            ("movestogo", self.movestogo.map(u64::from)),
+        ];
+
+        for (name, value) in numbers {
+            if let Some(value) = value {
+                command.push_str(&format!(" {} {}", name, value));
+            }
+        }
+
+        if self.infinite {
+            command.push_str(" infinite");
+        }
+
+        command
+    }
+}
+
+/// A running UCI engine process.
+///
+/// Engine output is read on a background thread and queued, so callers can poll with
+/// [`UciEngine::try_recv`] from a UI loop without ever blocking on the engine.
+pub struct UciEngine {
+    child: Child,
+    stdin: ChildStdin,
+    receiver: mpsc::Receiver<EngineMessage>,
+    pending: VecDeque<EngineMessage>,
+    name: Option<String>,
+    author: Option<String>,
+    options: Vec<UciOption>,
+}
+
+impl UciEngine {
+    /// Spawns the engine and performs the `uci`/`uciok` handshake.
+    pub fn spawn<P, I, A>(program: P, args: I) -> Result<Self, EngineError>
+    where
+        P: AsRef<OsStr>,
+        I: IntoIterator<Item = A>,
+        A: AsRef<OsStr>,
+    {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| EngineError::Io("Engine stdin is not available".to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| EngineError::Io("Engine stdout is not available".to_string()))?;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if sender.send(EngineMessage::parse(&line)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            child,
+            stdin,
+            receiver,
+            pending: VecDeque::new(),
+            name: None,
+            author: None,
+            options: vec![],
+        };
+
+        engine.handshake()?;
+        Ok(engine)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn options(&self) -> &[UciOption] {
+        &self.options
+    }
+
+    /// Sets an option the engine advertised during the handshake. The value is validated
+    /// against the advertised type and range before it is sent.
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), EngineError> {
+        let option = self
+            .options
+            .iter()
+            .find(|option| option.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| EngineError::UnknownOption(format!("Unknown option: {}", name)))?;
+
+        let command = match option.kind {
+            OptionKind::Button => format!("setoption name {}", option.name),
+            _ => format!(
+                "setoption name {} value {}",
+                option.name,
+                option.validate(value)?
+            ),
+        };
+
+        self.send(&command)
+    }
+
+    pub fn new_game(&mut self) -> Result<(), EngineError> {
+        self.send("ucinewgame")?;
+        self.is_ready()
+    }
+
+    /// Sends `isready` and blocks until the engine answers with `readyok`. Messages received
+    /// in the meantime stay queued for [`UciEngine::try_recv`].
+    pub fn is_ready(&mut self) -> Result<(), EngineError> {
+        self.send("isready")?;
+        let deadline = Instant::now() + READY_TIMEOUT;
+
+        loop {
+            match self.recv_until(deadline)? {
+                EngineMessage::ReadyOk => return Ok(()),
+                message => self.pending.push_back(message),
+            }
+        }
+    }
+
+    pub fn set_position(&mut self, game_state: &GameState) -> Result<(), EngineError> {
+        self.send(&position_command(game_state))
+    }
+
+    /// Starts a search. Progress arrives as [`EngineMessage::Info`] and the search ends
+    /// with an [`EngineMessage::BestMove`].
+    pub fn go(&mut self, params: &GoParams) -> Result<(), EngineError> {
+        self.send(&params.command())
+    }
+
+    pub fn stop(&mut self) -> Result<(), EngineError> {
+        self.send("stop")
+    }
+
+    /// Returns the next queued engine message without blocking.
+    pub fn try_recv(&mut self) -> Result<Option<EngineMessage>, EngineError> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(Some(message));
+        }
+
+        match self.receiver.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => Err(Self::disconnected()),
+        }
+    }
+
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<EngineMessage, EngineError> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(message);
+        }
+
+        self.recv_until(Instant::now() + timeout)
+    }
+
+    /// Blocks until the running search reports its best move, discarding `info` lines.
+    pub fn wait_for_best_move(&mut self, timeout: Duration) -> Result<BestMove, EngineError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let message = match self.pending.pop_front() {
+                Some(message) => message,
+                None => self.recv_until(deadline)?,
+            };
+
+            if let EngineMessage::BestMove(best_move) = message {
+                return Ok(best_move);
+            }
+        }
+    }
+}
+
+impl UciEngine {
+    fn handshake(&mut self) -> Result<(), EngineError> {
+        self.send("uci")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+
+        loop {
+            match self.recv_until(deadline)? {
+                EngineMessage::IdName(name) => self.name = Some(name),
+                EngineMessage::IdAuthor(author) => self.author = Some(author),
+                EngineMessage::Option(option) => self.options.push(option),
+                EngineMessage::UciOk => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), EngineError> {
+        writeln!(self.stdin, "{}", command)?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    fn recv_until(&self, deadline: Instant) -> Result<EngineMessage, EngineError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+
+        self.receiver.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => {
+                EngineError::Timeout("Engine did not respond in time".to_string())
+            }
+            RecvTimeoutError::Disconnected => Self::disconnected(),
+        })
+    }
+
+    fn disconnected() -> EngineError {
+        EngineError::Disconnected("Engine process closed its output".to_string())
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let deadline = Instant::now() + QUIT_GRACE_PERIOD;
+
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::game::GameState;
+
+    use crate::uci::{parse_uci_move, Score};
+
+    use super::*;
+
+    /// A scripted stand-in for a real engine. It echoes `position` and `setoption`
+    /// commands back as `info string` lines so the tests can observe what was sent.
+    const FAKE_ENGINE: &str = r#"
+while read -r cmd args; do
+    case "$cmd" in
+        uci)
+            echo "id name Fake Engine"
+            echo "id author Terminal Chess"
+            echo "option name Hash type spin default 16 min 1 max 1024"
+            echo "option name Ponder type check default false"
+            echo "uciok"
+            ;;
+        isready) echo "readyok" ;;
+        setoption|position) echo "info string $cmd $args" ;;
+        go)
+            echo "info depth 1 score cp 25 nodes 20 nps 2000 pv e2e4"
+            echo "info depth 2 score cp 18 nodes 90 nps 9000 pv e2e4 e7e5"
+            echo "bestmove e2e4 ponder e7e5"
+            ;;
+        quit) exit 0 ;;
+    esac
+done
+"#;
+
+    fn spawn_fake_engine() -> UciEngine {
+        UciEngine::spawn("sh", ["-c", FAKE_ENGINE]).expect("Failed to spawn fake engine")
+    }
+
+    fn next_info_string(engine: &mut UciEngine) -> String {
+        loop {
+            match engine.recv_timeout(Duration::from_secs(5)).unwrap() {
+                EngineMessage::Info(info) if info.string.is_some() => return info.string.unwrap(),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_handshake_reads_id_and_options() {
+        let engine = spawn_fake_engine();
+
+        assert_eq!(engine.name(), Some("Fake Engine"));
+        assert_eq!(engine.author(), Some("Terminal Chess"));
+        assert_eq!(engine.options().len(), 2);
+    }
+
+    #[test]
+    fn test_set_option_is_validated() {
+        let mut engine = spawn_fake_engine();
+
+        assert!(matches!(
+            engine.set_option("Threads", "4"),
+            Err(EngineError::UnknownOption(_))
+        ));
+        assert!(matches!(
+            engine.set_option("Hash", "4096"),
+            Err(EngineError::InvalidOptionValue(_))
+        ));
+
+        engine.set_option("hash", "64").unwrap();
+        assert_eq!(
+            next_info_string(&mut engine),
+            "setoption name Hash value 64"
+        );
+    }
+
+    #[test]
+    fn test_position_is_built_from_move_history() {
+        let mut engine = spawn_fake_engine();
+        let mut game_state = GameState::new();
+
+        for uci in ["e2e4", "e7e5", "g1f3"] {
+            let m = parse_uci_move(&game_state, uci).unwrap();
+            game_state.make_move(m);
+        }
+
+        engine.new_game().unwrap();
+        engine.set_position(&game_state).unwrap();
+
+        assert_eq!(
+            next_info_string(&mut engine),
+            "position startpos moves e2e4 e7e5 g1f3"
+        );
+    }
+
+    #[test]
+    fn test_search_streams_info_then_best_move() {
+        let mut engine = spawn_fake_engine();
+        engine
+            .go(&GoParams {
+                depth: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let mut infos = vec![];
+        let best_move = loop {
+            match engine.recv_timeout(Duration::from_secs(5)).unwrap() {
+                EngineMessage::Info(info) => infos.push(info),
+                EngineMessage::BestMove(best_move) => break best_move,
+                _ => {}
+            }
+        };
+
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[1].depth, Some(2));
+        assert_eq!(infos[1].score, Some(Score::Centipawns(18)));
+        assert_eq!(best_move.best, "e2e4");
+        assert_eq!(best_move.ponder.as_deref(), Some("e7e5"));
+    }
+
+    #[test]
+    fn test_go_params_command() {
+        let params = GoParams {
+            wtime: Some(Duration::from_secs(60)),
+            btime: Some(Duration::from_secs(55)),
+            winc: Some(Duration::from_secs(1)),
+            binc: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.command(),
+            "go wtime 60000 btime 55000 winc 1000 binc 1000"
+        );
+    }
+}
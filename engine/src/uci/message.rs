@@ -0,0 +1,206 @@
+use super::UciOption;
+
+/// A line of output received from a UCI engine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineMessage {
+    IdName(String),
+    IdAuthor(String),
+    Option(UciOption),
+    UciOk,
+    ReadyOk,
+    Info(InfoLine),
+    BestMove(BestMove),
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BestMove {
+    pub best: String,
+    pub ponder: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+    Mate(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreBound {
+    #[default]
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InfoLine {
+    pub depth: Option<u32>,
+    pub seldepth: Option<u32>,
+    pub multipv: Option<u32>,
+    pub score: Option<Score>,
+    pub bound: ScoreBound,
+    pub nodes: Option<u64>,
+    pub nps: Option<u64>,
+    pub time_ms: Option<u64>,
+    pub hashfull: Option<u32>,
+    pub tbhits: Option<u64>,
+    pub currmove: Option<String>,
+    pub pv: Vec<String>,
+    pub string: Option<String>,
+}
+
+impl EngineMessage {
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim();
+        let (command, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args = args.trim();
+
+        match command {
+            "id" => match args.split_once(char::is_whitespace) {
+                Some(("name", name)) => Self::IdName(name.trim().to_string()),
+                Some(("author", author)) => Self::IdAuthor(author.trim().to_string()),
+                _ => Self::Unknown(line.to_string()),
+            },
+            "option" => UciOption::parse(args)
+                .map(Self::Option)
+                .unwrap_or_else(|| Self::Unknown(line.to_string())),
+            "uciok" => Self::UciOk,
+            "readyok" => Self::ReadyOk,
+            "info" => Self::Info(InfoLine::parse(args)),
+            "bestmove" => {
+                let mut tokens = args.split_whitespace();
+                match tokens.next() {
+                    Some(best) => Self::BestMove(BestMove {
+                        best: best.to_string(),
+                        ponder: match (tokens.next(), tokens.next()) {
+                            (Some("ponder"), Some(ponder)) => Some(ponder.to_string()),
+                            _ => None,
+                        },
+                    }),
+                    None => Self::Unknown(line.to_string()),
+                }
+            }
+            _ => Self::Unknown(line.to_string()),
+        }
+    }
+}
+
+impl InfoLine {
+    /// Parses the arguments of an `info` line, i.e. everything after the `info` token.
+    /// Unknown tokens are skipped so that engine-specific extensions don't break parsing.
+    pub fn parse(args: &str) -> Self {
+        let mut info = Self::default();
+        let tokens: Vec<&str> = args.split_whitespace().collect();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let value = tokens.get(i + 1).copied().unwrap_or_default();
+            match tokens[i] {
+                "depth" => info.depth = value.parse().ok(),
+                "seldepth" => info.seldepth = value.parse().ok(),
+                "multipv" => info.multipv = value.parse().ok(),
+                "nodes" => info.nodes = value.parse().ok(),
+                "nps" => info.nps = value.parse().ok(),
+                "time" => info.time_ms = value.parse().ok(),
+                "hashfull" => info.hashfull = value.parse().ok(),
+                "tbhits" => info.tbhits = value.parse().ok(),
+                "currmove" => info.currmove = Some(value.to_string()),
+                "score" => {
+                    let amount = tokens.get(i + 2).and_then(|amount| amount.parse().ok());
+                    info.score = match (value, amount) {
+                        ("cp", Some(cp)) => Some(Score::Centipawns(cp)),
+                        ("mate", Some(moves)) => Some(Score::Mate(moves)),
+                        _ => None,
+                    };
+                    i += 1;
+                }
+                "lowerbound" => {
+                    info.bound = ScoreBound::Lower;
+                    i += 1;
+                    continue;
+                }
+                "upperbound" => {
+                    info.bound = ScoreBound::Upper;
+                    i += 1;
+                    continue;
+                }
+                "pv" => {
+                    info.pv = tokens[i + 1..].iter().map(|m| m.to_string()).collect();
+                    break;
+                }
+                "string" => {
+                    info.string = Some(tokens[i + 1..].join(" "));
+                    break;
+                }
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            }
+            i += 2;
+        }
+
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_info_line() {
+        let message = EngineMessage::parse(
+            "info depth 12 seldepth 18 multipv 2 score cp -35 upperbound nodes 123456 nps 800000 \
+             hashfull 12 tbhits 0 time 154 pv e7e5 g1f3 b8c6",
+        );
+
+        let EngineMessage::Info(info) = message else {
+            panic!("Expected an info message, got {:?}", message);
+        };
+
+        assert_eq!(info.depth, Some(12));
+        assert_eq!(info.seldepth, Some(18));
+        assert_eq!(info.multipv, Some(2));
+        assert_eq!(info.score, Some(Score::Centipawns(-35)));
+        assert_eq!(info.bound, ScoreBound::Upper);
+        assert_eq!(info.nodes, Some(123456));
+        assert_eq!(info.nps, Some(800000));
+        assert_eq!(info.time_ms, Some(154));
+        assert_eq!(info.pv, vec!["e7e5", "g1f3", "b8c6"]);
+    }
+
+    #[test]
+    fn test_parse_mate_score_and_string() {
+        let EngineMessage::Info(info) = EngineMessage::parse("info depth 3 score mate -2 pv h7h8")
+        else {
+            panic!("Expected an info message");
+        };
+        assert_eq!(info.score, Some(Score::Mate(-2)));
+
+        let EngineMessage::Info(info) = EngineMessage::parse("info string NNUE evaluation enabled")
+        else {
+            panic!("Expected an info message");
+        };
+        assert_eq!(info.string.as_deref(), Some("NNUE evaluation enabled"));
+    }
+
+    #[test]
+    fn test_parse_bestmove() {
+        assert_eq!(
+            EngineMessage::parse("bestmove e2e4 ponder e7e5"),
+            EngineMessage::BestMove(BestMove {
+                best: "e2e4".to_string(),
+                ponder: Some("e7e5".to_string()),
+            })
+        );
+        assert_eq!(
+            EngineMessage::parse("bestmove a7a8q"),
+            EngineMessage::BestMove(BestMove {
+                best: "a7a8q".to_string(),
+                ponder: None,
+            })
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod client;
+pub mod message;
+pub mod notation;
+pub mod options;
+
+pub use client::*;
+pub use message::*;
+pub use notation::*;
+pub use options::*;
@@ -0,0 +1,110 @@
+use domain::{
+    board::Position,
+    game::GameState,
+    moves::{Move, MoveType},
+    pieces::PromotionPiece,
+};
+
+use crate::error::EngineError;
+
+/// Formats a move in UCI long algebraic notation, e.g. `e2e4` or `e7e8q`.
+pub fn move_to_uci(m: &Move) -> String {
+    match &m.move_type {
+        MoveType::Promotion(piece) => {
+            format!("{}{}{}", m.from, m.to, piece.to_string().to_lowercase())
+        }
+        _ => format!("{}{}", m.from, m.to),
+    }
+}
+
+/// Resolves a move in UCI notation against the legal moves of `game_state`.
+pub fn parse_uci_move(game_state: &GameState, uci: &str) -> Result<Move, EngineError> {
+    let invalid = || EngineError::InvalidMove(format!("Invalid UCI move: {}", uci));
+
+    if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+        return Err(invalid());
+    }
+
+    let from = Position::from_algebraic(&uci[0..2]).ok_or_else(invalid)?;
+    let to = Position::from_algebraic(&uci[2..4]).ok_or_else(invalid)?;
+    let promotion = match &uci[4..] {
+        "" => None,
+        "q" => Some(PromotionPiece::Queen),
+        "r" => Some(PromotionPiece::Rook),
+        "b" => Some(PromotionPiece::Bishop),
+        "n" => Some(PromotionPiece::Knight),
+        _ => return Err(invalid()),
+    };
+
+    let mut m = game_state
+        .legal_moves()
+        .into_iter()
+        .find(|m| m.from == from && m.to == to)
+        .ok_or_else(invalid)?;
+
+    match (&m.move_type, promotion) {
+        (MoveType::Promotion(_), Some(piece)) => m.move_type = MoveType::Promotion(piece),
+        (MoveType::Promotion(_), None) => {}
+        (_, Some(_)) => return Err(invalid()),
+        (_, None) => {}
+    }
+
+    Ok(m)
+}
+
+/// Builds the `position` command describing `game_state` from the starting position.
+pub fn position_command(game_state: &GameState) -> String {
+    let position = match &game_state.setup {
+        Some(fen) => format!("position fen {}", fen),
+        None => "position startpos".to_string(),
+    };
+    if game_state.move_history.is_empty() {
+        return position;
+    }
+
+    let moves: Vec<String> = game_state
+        .move_history
+        .iter()
+        .map(|record| move_to_uci(&record.mv))
+        .collect();
+
+    format!("{} moves {}", position, moves.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_uci_moves() {
+        let mut game_state = GameState::new();
+
+        let e4 = parse_uci_move(&game_state, "e2e4").unwrap();
+        assert_eq!(e4.move_type, MoveType::DoublePawn);
+        assert_eq!(move_to_uci(&e4), "e2e4");
+        game_state.make_move(e4);
+
+        let nf6 = parse_uci_move(&game_state, "g8f6").unwrap();
+        game_state.make_move(nf6);
+
+        assert!(parse_uci_move(&game_state, "e4e6").is_err());
+        assert!(parse_uci_move(&game_state, "e4e5q").is_err());
+        assert!(parse_uci_move(&game_state, "z9e5").is_err());
+
+        assert_eq!(
+            position_command(&game_state),
+            "position startpos moves e2e4 g8f6"
+        );
+    }
+
+    #[test]
+    fn test_promotion_suffix() {
+        let m = Move::new(
+            MoveType::Promotion(PromotionPiece::Knight),
+            Position::from((1, 0)),
+            Position::from((0, 0)),
+        );
+
+        assert_eq!(move_to_uci(&m), "a7a8n");
+    }
+}
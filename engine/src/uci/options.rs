@@ -0,0 +1,166 @@
+use crate::error::EngineError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UciOption {
+    pub name: String,
+    pub kind: OptionKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionKind {
+    Check { default: bool },
+    Spin { default: i64, min: i64, max: i64 },
+    Combo { default: String, vars: Vec<String> },
+    Button,
+    String { default: String },
+}
+
+impl UciOption {
+    /// Parses the arguments of an `option` line, i.e. everything after the `option` token.
+    pub fn parse(args: &str) -> Option<Self> {
+        let tokens: Vec<&str> = args.split_whitespace().collect();
+        let name = Self::field(&tokens, "name")?;
+        let kind = match Self::field(&tokens, "type")?.as_str() {
+            "check" => OptionKind::Check {
+                default: Self::field(&tokens, "default")? == "true",
+            },
+            "spin" => OptionKind::Spin {
+                default: Self::field(&tokens, "default")?.parse().ok()?,
+                min: Self::field(&tokens, "min")?.parse().ok()?,
+                max: Self::field(&tokens, "max")?.parse().ok()?,
+            },
+            "combo" => OptionKind::Combo {
+                default: Self::field(&tokens, "default").unwrap_or_default(),
+                vars: Self::vars(&tokens),
+            },
+            "button" => OptionKind::Button,
+            "string" => OptionKind::String {
+                default: Self::field(&tokens, "default").unwrap_or_default(),
+            },
+            _ => return None,
+        };
+
+        Some(Self { name, kind })
+    }
+
+    /// Checks that `value` is acceptable for this option and returns it in the form
+    /// the engine expects on the wire.
+    pub fn validate(&self, value: &str) -> Result<String, EngineError> {
+        let invalid = || {
+            EngineError::InvalidOptionValue(format!(
+                "Invalid value '{}' for option '{}'",
+                value, self.name
+            ))
+        };
+
+        match &self.kind {
+            OptionKind::Check { .. } => match value {
+                "true" | "false" => Ok(value.to_string()),
+                _ => Err(invalid()),
+            },
+            OptionKind::Spin { min, max, .. } => {
+                let parsed: i64 = value.parse().map_err(|_| invalid())?;
+                if parsed < *min || parsed > *max {
+                    return Err(invalid());
+                }
+                Ok(parsed.to_string())
+            }
+            OptionKind::Combo { vars, .. } => vars
+                .iter()
+                .find(|var| var.eq_ignore_ascii_case(value))
+                .cloned()
+                .ok_or_else(invalid),
+            OptionKind::Button => Ok(String::new()),
+            OptionKind::String { .. } => Ok(value.to_string()),
+        }
+    }
+}
+
+impl UciOption {
+    const KEYWORDS: [&'static str; 6] = ["name", "type", "default", "min", "max", "var"];
+
+    fn field(tokens: &[&str], keyword: &str) -> Option<String> {
+        let start = tokens.iter().position(|token| *token == keyword)? + 1;
+        let value: Vec<&str> = tokens[start..]
+            .iter()
+            .take_while(|token| !Self::KEYWORDS.contains(token))
+            .copied()
+            .collect();
+
+        match value.as_slice() {
+            ["<empty>"] => Some(String::new()),
+            _ => Some(value.join(" ")),
+        }
+    }
+
+    fn vars(tokens: &[&str]) -> Vec<String> {
+        tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| **token == "var")
+            .map(|(i, _)| {
+                tokens[i + 1..]
+                    .iter()
+                    .take_while(|token| !Self::KEYWORDS.contains(token))
+                    .copied()
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_option_kinds() {
+        let hash = UciOption::parse("name Hash type spin default 16 min 1 max 33554432").unwrap();
+        assert_eq!(hash.name, "Hash");
+        assert_eq!(
+            hash.kind,
+            OptionKind::Spin {
+                default: 16,
+                min: 1,
+                max: 33554432
+            }
+        );
+
+        let ponder = UciOption::parse("name Ponder type check default false").unwrap();
+        assert_eq!(ponder.kind, OptionKind::Check { default: false });
+
+        let style =
+            UciOption::parse("name Play Style type combo default Normal var Solid var Normal")
+                .unwrap();
+        assert_eq!(style.name, "Play Style");
+        assert_eq!(
+            style.kind,
+            OptionKind::Combo {
+                default: "Normal".to_string(),
+                vars: vec!["Solid".to_string(), "Normal".to_string()],
+            }
+        );
+
+        let path = UciOption::parse("name SyzygyPath type string default <empty>").unwrap();
+        assert_eq!(
+            path.kind,
+            OptionKind::String {
+                default: String::new()
+            }
+        );
+
+        let clear = UciOption::parse("name Clear Hash type button").unwrap();
+        assert_eq!(clear.kind, OptionKind::Button);
+    }
+
+    #[test]
+    fn test_validate_spin_range() {
+        let threads = UciOption::parse("name Threads type spin default 1 min 1 max 8").unwrap();
+
+        assert_eq!(threads.validate("4").unwrap(), "4");
+        assert!(threads.validate("0").is_err());
+        assert!(threads.validate("9").is_err());
+        assert!(threads.validate("many").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use domain::{
+    board::{Board, Position},
+    game::{Color, GameState},
+    pieces::PieceKind,
+};
+
+use super::RANDOM64;
+
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+/// Computes the Polyglot Zobrist key of the position in `game_state`.
+pub fn polyglot_key(game_state: &GameState) -> u64 {
+    position_key(&game_state.board, game_state.current_player.color)
+}
+
+/// Computes the Polyglot Zobrist key of `board` with `side_to_move` to play.
+pub fn position_key(board: &Board, side_to_move: Color) -> u64 {
+    let mut key = board
+        .piece_positions()
+        .into_iter()
+        .filter_map(|pos| board[&pos].map(|piece| (pos, piece)))
+        .fold(0, |key, (pos, piece)| {
+            let kind = 2 * piece.piece_type.as_index()
+                + match piece.piece_color {
+                    Color::Black => 0,
+                    Color::White => 1,
+                };
+            let rank = 7 - pos.row as usize;
+            key ^ RANDOM64[64 * kind + 8 * rank + pos.column as usize]
+        });
+
+    let castling_rights = [
+        board.has_short_castling_rights(Color::White),
+        board.has_long_castling_rights(Color::White),
+        board.has_short_castling_rights(Color::Black),
+        board.has_long_castling_rights(Color::Black),
+    ];
+
+    for (i, _) in castling_rights
+        .iter()
+        .enumerate()
+        .filter(|(_, right)| **right)
+    {
+        key ^= RANDOM64[CASTLING_OFFSET + i];
+    }
+
+    if let Some(column) = en_passant_file(board, side_to_move) {
+        key ^= RANDOM64[EN_PASSANT_OFFSET + column as usize];
+    }
+
+    if side_to_move == Color::White {
+        key ^= RANDOM64[TURN_OFFSET];
+    }
+
+    key
+}
+
+/// Polyglot only hashes the en passant file when a pawn of the side to move stands next to
+/// the pawn that just advanced two squares, regardless of whether the capture is legal.
+fn en_passant_file(board: &Board, side_to_move: Color) -> Option<i8> {
+    let target = board.get_en_passant_square(&side_to_move.opponent())?;
+    let row = match side_to_move {
+        Color::White => 3,
+        Color::Black => 4,
+    };
+
+    [target.column - 1, target.column + 1]
+        .into_iter()
+        .map(|column| Position::new(row, column))
+        .filter(|pos| board.is_inside(pos))
+        .any(|pos| {
+            board[&pos].is_some_and(|piece| {
+                piece.piece_color == side_to_move
+                    && PieceKind::from(&piece.piece_type) == PieceKind::Pawn
+            })
+        })
+        .then_some(target.column)
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::game::San;
+
+    use super::*;
+
+    fn key_after(moves: &[&str]) -> u64 {
+        let mut game_state = GameState::new();
+        for san in moves {
+            let m = San::parse(&game_state, san).unwrap();
+            game_state.make_move(m);
+        }
+        polyglot_key(&game_state)
+    }
+
+    #[test]
+    fn test_polyglot_reference_keys() {
+        assert_eq!(key_after(&[]), 0x463b96181691fc9c);
+        assert_eq!(key_after(&["e4"]), 0x823c9b50fd114196);
+        assert_eq!(key_after(&["e4", "d5"]), 0x0756b94461c50fb0);
+        assert_eq!(key_after(&["e4", "d5", "e5"]), 0x662fafb965db29d4);
+        assert_eq!(key_after(&["e4", "d5", "e5", "f5"]), 0x22a48b5a8e47ff78);
+        assert_eq!(
+            key_after(&["e4", "d5", "e5", "f5", "Ke2"]),
+            0x652a607ca3f242c1
+        );
+        assert_eq!(
+            key_after(&["e4", "d5", "e5", "f5", "Ke2", "Kf7"]),
+            0x00fdd303c946bdd9
+        );
+        assert_eq!(
+            key_after(&["a4", "b5", "h4", "b4", "c4"]),
+            0x3c8123ea7b067637
+        );
+        assert_eq!(
+            key_after(&["a4", "b5", "h4", "b4", "c4", "bxc3", "Ra3"]),
+            0x5c3f9b829b279560
+        );
+    }
+}
@@ -0,0 +1,5 @@
+pub mod key;
+mod random;
+
+pub use key::*;
+use random::*;
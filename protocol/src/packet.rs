@@ -110,6 +110,12 @@ pub struct PacketFramer {
     buffer: Vec<u8>,
 }
 
+impl Default for PacketFramer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PacketFramer {
     pub fn new() -> Self {
         Self {
@@ -2,12 +2,14 @@ use domain::game::GameState;
 
 use crate::ids::PlayerId;
 
+#[allow(dead_code)]
 pub struct GameSession {
     pub player_1: PlayerId,
     pub player_2: PlayerId,
     pub game_state: GameState,
 }
 
+#[allow(dead_code)]
 impl GameSession {
     pub fn new(player_1: PlayerId, player_2: PlayerId) -> Self {
         Self {
@@ -2,7 +2,6 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use protocol::packet::Packet;
-use uuid::Uuid;
 
 use crate::{global_state::GlobalState, ids::PlayerId, matchmaker::GameRequest};
 
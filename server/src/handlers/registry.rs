@@ -17,11 +17,8 @@ impl HandlerRegistry {
             HashMap::new();
 
         for packet_type in PacketType::iter() {
-            match packet_type {
-                PacketType::GameRequest => {
-                    handlers.insert(packet_type, Box::new(GameRequestHandler));
-                }
-                _ => {}
+            if packet_type == PacketType::GameRequest {
+                handlers.insert(packet_type, Box::new(GameRequestHandler));
             }
         }
 
@@ -0,0 +1,68 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use domain::{game::GameState, moves::Move};
+use engine::{
+    search::{SearchLimits, Searcher},
+    tablebase::Tablebase,
+};
+
+use crate::constants::ANALYSIS_DEPTH;
+
+/// What a search of the game position is asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisKind {
+    /// The best move for the side to move.
+    Hint,
+    /// The move the opponent would play if the side to move passed.
+    Threat,
+}
+
+/// A search running off the UI thread, polled for its move on every tick.
+#[derive(Debug)]
+pub struct Analysis {
+    pub kind: AnalysisKind,
+    receiver: Receiver<Option<Move>>,
+}
+
+impl Analysis {
+    /// Starts searching `game_state`, `None` if there is nothing to search, e.g. a threat
+    /// while in check.
+    pub fn start(
+        kind: AnalysisKind,
+        game_state: &GameState,
+        tablebase: Option<Arc<Tablebase>>,
+    ) -> Option<Self> {
+        let position = match kind {
+            AnalysisKind::Hint => game_state.clone(),
+            AnalysisKind::Threat => game_state.null_move()?,
+        };
+        if position.is_game_over() {
+            return None;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut searcher = Searcher::default();
+            searcher.set_tablebase(tablebase);
+            let result = searcher.search(&position, &SearchLimits::depth(ANALYSIS_DEPTH));
+            let _ = sender.send(result.best_move);
+        });
+
+        Some(Self { kind, receiver })
+    }
+
+    /// The move found, once the search has finished.
+    pub fn poll(&self) -> Option<Option<Move>> {
+        match self.receiver.try_recv() {
+            Ok(best_move) => Some(best_move),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
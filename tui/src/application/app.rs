@@ -1,7 +1,17 @@
-use domain::game::GameState;
+use std::{collections::HashMap, sync::Arc};
+
+use domain::game::{GameState, Pgn};
+use engine::{book::Book, tablebase::Tablebase};
 use ratatui::{layout::Rect, Frame};
 
-use super::{Game, GameType, Menu};
+use crate::{
+    constants::{BOOK_PATH_ENV, MIN_HEIGHT, MIN_WIDTH, SYZYGY_PATH_ENV},
+    key_bindings::ContextBindings,
+    theme::Theme,
+    widgets::{KeyHelp, TooSmall},
+};
+
+use super::{EngineSetup, Game, GameType, Help, Menu, Review};
 
 pub type AppResult<T> = Result<T, Box<dyn std::error::Error>>;
 
@@ -10,7 +20,18 @@ pub struct App {
     pub current_screen: CurrentScreen,
     pub event_context: EventContext,
     pub menu: Menu,
+    pub engine_setup: EngineSetup,
     pub game: Option<Game>,
+    pub review: Option<Review>,
+    pub help: Help,
+    pub tablebase: Option<Arc<Tablebase>>,
+    pub book: Option<Arc<Book>>,
+    pub theme: Theme,
+    /// The keys of every context, for the help overlay.
+    pub key_help: HashMap<EventContext, ContextBindings>,
+    pub show_key_help: bool,
+    /// Problems with the key bindings and theme files, shown in the key help.
+    pub config_problems: Vec<String>,
 }
 
 impl Default for App {
@@ -20,7 +41,16 @@ impl Default for App {
             current_screen: CurrentScreen::MainMenu,
             event_context: EventContext::MainMenu,
             menu: Menu::default(),
+            engine_setup: EngineSetup::default(),
             game: None,
+            review: None,
+            help: Help::default(),
+            tablebase: Self::load_tablebase(),
+            book: Self::load_book(),
+            theme: Theme::default(),
+            key_help: HashMap::new(),
+            show_key_help: false,
+            config_problems: vec![],
         }
     }
 }
@@ -28,39 +58,205 @@ impl Default for App {
 impl App {
     pub fn start_game(&mut self, game_type: GameType) {
         let new_game = GameState::default();
-        self.game = Some(Game::new(new_game, game_type));
+        self.game = Some(Game::new(
+            new_game,
+            game_type,
+            self.tablebase.clone(),
+            self.book.clone(),
+        ));
+        self.current_screen = CurrentScreen::Game;
+        self.event_context = EventContext::Game;
+    }
+
+    /// Starts a hot-seat game from a pasted FEN, or replays a pasted PGN game in one.
+    /// Any other text is ignored, and a game still being played is not replaced.
+    pub fn load_pasted(&mut self, text: &str) {
+        let text = text.trim();
+        let (start, moves) = if let Ok(game_state) = GameState::from_fen(text) {
+            (game_state, vec![])
+        } else {
+            let Some(pgn) = Pgn::parse_all(text).into_iter().next() else {
+                return;
+            };
+            let (Ok(start), Ok(moves)) = (pgn.start_position(), pgn.mainline()) else {
+                return;
+            };
+            if moves.is_empty() && pgn.tag("FEN").is_none() {
+                return;
+            }
+            (start, moves)
+        };
+
+        if let (CurrentScreen::Game, Some(game)) = (&self.current_screen, self.game.as_mut()) {
+            if game.game_state.result.is_none() {
+                game.status = Some("Finish the game before loading another".to_string());
+                return;
+            }
+        }
+
+        let mut game = Game::new(
+            start,
+            GameType::Normal,
+            self.tablebase.clone(),
+            self.book.clone(),
+        );
+        for m in moves {
+            game.make_move(m);
+        }
+        game.status = Some(match game.san_history.len() {
+            0 => "Position loaded".to_string(),
+            moves => format!("Game loaded, {} moves", moves),
+        });
+
+        self.game = Some(game);
         self.current_screen = CurrentScreen::Game;
         self.event_context = EventContext::Game;
     }
 
+    /// Passes focus changes of the terminal on to the game, which pauses its clock.
+    pub fn set_focused(&mut self, focused: bool) {
+        if let Some(game) = self.game.as_mut() {
+            game.set_focused(focused);
+        }
+    }
+
+    pub fn open_engine_setup(&mut self) {
+        self.current_screen = CurrentScreen::EngineSetup;
+        self.event_context = EventContext::EngineSetup;
+    }
+
+    pub fn open_help(&mut self) {
+        self.help = Help::default();
+        self.current_screen = CurrentScreen::Help;
+        self.event_context = EventContext::Help;
+    }
+
+    /// Reviews the current game, which should be over.
+    pub fn start_review(&mut self) {
+        if let Some(game) = &self.game {
+            self.review = Some(Review::start(game.game_state.clone()));
+            self.current_screen = CurrentScreen::Review;
+            self.event_context = EventContext::Review;
+        }
+    }
+
     pub fn quit(&mut self) {
         self.is_running = false;
     }
 
-    pub fn tick(&self) {}
+    pub fn tick(&mut self) {
+        if let (CurrentScreen::Game, Some(game)) = (&self.current_screen, self.game.as_mut()) {
+            game.tick();
+            let playing = matches!(
+                self.event_context,
+                EventContext::Game | EventContext::PromotionMenu
+            );
+            if playing && game.game_state.is_game_over() {
+                game.promotion_menu = None;
+                self.event_context = EventContext::GameOver;
+            }
+        }
+        if let Some(review) = self.review.as_mut() {
+            review.poll();
+        }
+    }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+            frame.render_widget(TooSmall::new(&self.theme), area);
+            return;
+        }
+
         match self.current_screen {
-            CurrentScreen::MainMenu => self.menu.render_self(frame, area),
+            CurrentScreen::MainMenu => self.menu.render_self(frame, area, &self.theme),
+            CurrentScreen::EngineSetup => self.engine_setup.render_self(frame, area, &self.theme),
             CurrentScreen::Game => {
                 if let Some(game) = &self.game {
-                    game.render_self(frame, area);
+                    game.render_self(frame, area, &self.theme);
+                }
+            }
+            CurrentScreen::Review => {
+                if let Some(review) = &self.review {
+                    review.render_self(frame, area, &self.theme);
                 }
             }
+            CurrentScreen::Help => self
+                .help
+                .render_self(frame, area, &self.key_help, &self.theme),
+        }
+
+        if self.show_key_help {
+            let bindings = self
+                .key_help
+                .get(&self.event_context)
+                .map_or(&[][..], Vec::as_slice);
+            frame.render_widget(
+                KeyHelp::new(bindings, &self.config_problems, &self.theme),
+                area,
+            );
         }
     }
 }
 
+impl App {
+    fn load_tablebase() -> Option<Arc<Tablebase>> {
+        let dir = std::env::var(SYZYGY_PATH_ENV).ok()?;
+        Tablebase::open(dir)
+            .ok()
+            .filter(|tablebase| !tablebase.is_empty())
+            .map(Arc::new)
+    }
+
+    fn load_book() -> Option<Arc<Book>> {
+        let path = std::env::var(BOOK_PATH_ENV).ok()?;
+        Book::open(path)
+            .ok()
+            .filter(|book| !book.is_empty())
+            .map(Arc::new)
+    }
+}
+
 #[derive(Debug)]
 pub enum CurrentScreen {
     MainMenu,
+    EngineSetup,
     Game,
+    Review,
+    Help,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EventContext {
     MainMenu,
+    EngineSetup,
     Game,
     PromotionMenu,
     GameOver,
+    Review,
+    Help,
+}
+
+impl EventContext {
+    pub const ALL: [EventContext; 7] = [
+        EventContext::MainMenu,
+        EventContext::EngineSetup,
+        EventContext::Game,
+        EventContext::PromotionMenu,
+        EventContext::GameOver,
+        EventContext::Review,
+        EventContext::Help,
+    ];
+
+    /// The name of the context's table in the key bindings file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventContext::MainMenu => "main_menu",
+            EventContext::EngineSetup => "engine_setup",
+            EventContext::Game => "game",
+            EventContext::PromotionMenu => "promotion_menu",
+            EventContext::GameOver => "game_over",
+            EventContext::Review => "review",
+            EventContext::Help => "help",
+        }
+    }
 }
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use domain::game::Color;
+use engine::{arena::TimeControl, search::Clock};
+
+/// A chess clock for both sides. The side to move is passed in, as the clock does not
+/// follow the game itself.
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    remaining: [Duration; 2],
+    increment: Duration,
+    turn_started: Instant,
+    /// When the clock was stopped, if it is.
+    paused_at: Option<Instant>,
+}
+
+impl GameClock {
+    pub fn new(time_control: &TimeControl) -> Self {
+        let base = time_control.base.unwrap_or_default();
+        Self {
+            remaining: [base, base],
+            increment: time_control.increment,
+            turn_started: Instant::now(),
+            paused_at: None,
+        }
+    }
+
+    /// Time left to `color`, counting the running turn if it is `to_move`.
+    pub fn remaining(&self, color: Color, to_move: Color) -> Duration {
+        let remaining = self.remaining[Self::index(color)];
+        match color == to_move {
+            true => remaining.saturating_sub(self.turn_elapsed()),
+            false => remaining,
+        }
+    }
+
+    pub fn is_flagged(&self, to_move: Color) -> bool {
+        self.remaining(to_move, to_move).is_zero()
+    }
+
+    /// Stops the clock of `mover` after its move, adds the increment and starts the
+    /// opponent's.
+    pub fn press(&mut self, mover: Color) {
+        let remaining = self.remaining(mover, mover);
+        self.remaining[Self::index(mover)] = remaining + self.increment;
+        self.turn_started = Instant::now();
+        self.paused_at = None;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Stops the running turn until `resume`.
+    pub fn pause(&mut self) {
+        self.paused_at.get_or_insert_with(Instant::now);
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.turn_started += paused_at.elapsed();
+        }
+    }
+
+    /// The clock of `to_move` as the search sees it.
+    pub fn search_clock(&self, to_move: Color) -> Clock {
+        Clock {
+            remaining: self.remaining(to_move, to_move),
+            increment: self.increment,
+            movestogo: None,
+        }
+    }
+
+    /// `m:ss`, or `s.t` under ten seconds.
+    pub fn format(duration: Duration) -> String {
+        let seconds = duration.as_secs();
+        match seconds {
+            0..10 => format!("{}.{}", seconds, duration.subsec_millis() / 100),
+            _ => format!("{}:{:02}", seconds / 60, seconds % 60),
+        }
+    }
+}
+
+impl GameClock {
+    fn turn_elapsed(&self) -> Duration {
+        self.paused_at
+            .unwrap_or_else(Instant::now)
+            .saturating_duration_since(self.turn_started)
+    }
+
+    fn index(color: Color) -> usize {
+        match color {
+            Color::White => 0,
+            Color::Black => 1,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use domain::{
+    game::{Color, GameState},
+    moves::Move,
+};
+use engine::{
+    book::{Book, BookPolicy},
+    search::{Clock, Searcher},
+    skill::SkillLevel,
+    tablebase::Tablebase,
+};
+
+/// The engine side of a game against the engine. Moves are searched on a background
+/// thread, which takes the searcher along and hands it back with the move, so the hash
+/// table carries over between moves. The skill level's evaluation noise is seeded on
+/// the first move and kept, as reseeding it would clear the table.
+pub struct EngineOpponent {
+    pub level: SkillLevel,
+    pub color: Color,
+    book: Option<Arc<Book>>,
+    tablebase: Option<Arc<Tablebase>>,
+    searcher: Option<Searcher>,
+    thinking: Option<Receiver<(Searcher, Option<Move>)>>,
+}
+
+impl EngineOpponent {
+    pub fn new(
+        level: SkillLevel,
+        color: Color,
+        book: Option<Arc<Book>>,
+        tablebase: Option<Arc<Tablebase>>,
+    ) -> Self {
+        let searcher = Self::searcher(&tablebase);
+        Self {
+            level,
+            color,
+            book,
+            tablebase,
+            searcher: Some(searcher),
+            thinking: None,
+        }
+    }
+
+    /// A searcher set up the way this opponent plays, without a hash table yet.
+    fn searcher(tablebase: &Option<Arc<Tablebase>>) -> Searcher {
+        let mut searcher = Searcher::default();
+        searcher.set_tablebase(tablebase.clone());
+        searcher
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.thinking.is_some()
+    }
+
+    /// Starts looking for a move in `game_state`, from the book if it has one. Does
+    /// nothing while the engine is already thinking.
+    pub fn think(&mut self, game_state: &GameState, clock: Option<Clock>) {
+        let Some(mut searcher) = self.searcher.take() else {
+            return;
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let level = self.level.clone();
+        let book = self.book.clone();
+        let position = game_state.clone();
+        thread::spawn(move || {
+            let mut rng = rand::thread_rng();
+            let m = book
+                .and_then(|book| book.pick(&position, BookPolicy::Weighted, &mut rng))
+                .or_else(|| level.pick_move(&mut searcher, &position, clock, &mut rng));
+            let _ = sender.send((searcher, m));
+        });
+
+        self.thinking = Some(receiver);
+    }
+
+    /// The move found, once the engine has finished thinking. If the search thread died
+    /// with the searcher, a new one is set up the same way and the hash table is lost.
+    pub fn poll(&mut self) -> Option<Move> {
+        let result = match self.thinking.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => (Self::searcher(&self.tablebase), None),
+        };
+
+        let (searcher, m) = result;
+        self.searcher = Some(searcher);
+        self.thinking = None;
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn wait_for_move(opponent: &mut EngineOpponent) -> Option<Move> {
+        while opponent.is_thinking() {
+            if let Some(m) = opponent.poll() {
+                return Some(m);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        None
+    }
+
+    #[test]
+    fn test_thinks_again_after_losing_the_search_thread() {
+        let level = SkillLevel::from_name("Beginner").unwrap();
+        let mut opponent = EngineOpponent::new(level, Color::White, None, None);
+        let game_state = GameState::new();
+
+        // A search thread that ended without sending anything back
+        opponent.searcher = None;
+        let (_, receiver) = mpsc::channel();
+        opponent.thinking = Some(receiver);
+
+        assert_eq!(opponent.poll(), None);
+        assert!(!opponent.is_thinking());
+
+        opponent.think(&game_state, None);
+        let m = wait_for_move(&mut opponent).unwrap();
+        assert!(game_state.legal_moves().contains(&m));
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use domain::game::Color as PieceColor;
+use engine::{arena::TimeControl, skill::SkillLevel};
+use rand::Rng;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::Text,
+    widgets::{Block, Borders, List, ListItem, Padding},
+    Frame,
+};
+
+use crate::{theme::Theme, widgets::AppTitle};
+
+pub const COLOR_CHOICES: [&str; 3] = ["White", "Black", "Random"];
+
+pub const TIME_CONTROLS: [(&str, Option<TimeControl>); 5] = [
+    ("Untimed", None),
+    ("3+2", Some(minutes(3, 2))),
+    ("5+0", Some(minutes(5, 0))),
+    ("10+5", Some(minutes(10, 5))),
+    ("15+10", Some(minutes(15, 10))),
+];
+
+const fn minutes(base: u64, increment: u64) -> TimeControl {
+    TimeControl {
+        base: Some(Duration::from_secs(base * 60)),
+        increment: Duration::from_secs(increment),
+        movetime: None,
+    }
+}
+
+/// How a game against the engine is played, as chosen on the setup screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineSettings {
+    pub level: SkillLevel,
+    /// The colour the engine plays.
+    pub engine_color: PieceColor,
+    pub time_control: Option<TimeControl>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupRow {
+    Color,
+    Strength,
+    TimeControl,
+}
+
+/// Lets the player pick their colour, the engine strength and the time control before a
+/// game against the engine.
+#[derive(Debug, Clone)]
+pub struct EngineSetup {
+    pub levels: &'static [SkillLevel],
+    pub row: SetupRow,
+    pub color: usize,
+    pub level: usize,
+    pub time_control: usize,
+}
+
+impl Default for EngineSetup {
+    fn default() -> Self {
+        let levels = SkillLevel::presets();
+        let default = SkillLevel::default();
+
+        Self {
+            levels,
+            row: SetupRow::Color,
+            color: 0,
+            level: levels.iter().position(|l| *l == default).unwrap_or(0),
+            time_control: 0,
+        }
+    }
+}
+
+impl EngineSetup {
+    pub fn next_row(&mut self) {
+        self.row = match self.row {
+            SetupRow::Color => SetupRow::Strength,
+            SetupRow::Strength => SetupRow::TimeControl,
+            SetupRow::TimeControl => SetupRow::Color,
+        };
+    }
+
+    pub fn previous_row(&mut self) {
+        self.row = match self.row {
+            SetupRow::Color => SetupRow::TimeControl,
+            SetupRow::Strength => SetupRow::Color,
+            SetupRow::TimeControl => SetupRow::Strength,
+        };
+    }
+
+    pub fn next_value(&mut self) {
+        let (value, count) = self.selected_value();
+        *value = (*value + 1) % count;
+    }
+
+    pub fn previous_value(&mut self) {
+        let (value, count) = self.selected_value();
+        *value = (*value + count - 1) % count;
+    }
+
+    /// The settings chosen, with a random colour drawn now.
+    pub fn settings(&self) -> EngineSettings {
+        let player_color = match self.color {
+            0 => PieceColor::White,
+            1 => PieceColor::Black,
+            _ if rand::thread_rng().gen_bool(0.5) => PieceColor::White,
+            _ => PieceColor::Black,
+        };
+
+        EngineSettings {
+            level: self.levels[self.level].clone(),
+            engine_color: player_color.opponent(),
+            time_control: TIME_CONTROLS[self.time_control].1,
+        }
+    }
+
+    pub fn render_self(&self, frame: &mut Frame, main_area: Rect, theme: &Theme) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+            .split(main_area);
+
+        let menu_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50)])
+            .flex(Flex::Center)
+            .split(layout[1]);
+
+        let level = &self.levels[self.level];
+        let rows = [
+            (
+                SetupRow::Color,
+                format!("You play: {}", COLOR_CHOICES[self.color]),
+            ),
+            (
+                SetupRow::Strength,
+                format!("Strength: {} (~{} Elo)", level.name, level.elo),
+            ),
+            (
+                SetupRow::TimeControl,
+                format!("Time control: {}", TIME_CONTROLS[self.time_control].0),
+            ),
+        ];
+
+        let list_items: Vec<ListItem> = rows
+            .into_iter()
+            .map(|(row, item)| {
+                if self.row == row {
+                    ListItem::new(Text::from(format!("< {} >", item)).alignment(Alignment::Center))
+                        .style(
+                            Style::default()
+                                .fg(theme.accent)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                } else {
+                    ListItem::new(Text::from(item).alignment(Alignment::Center))
+                        .style(Style::default())
+                }
+            })
+            .collect();
+
+        let list = List::new(list_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .padding(Padding::top(2))
+                    .title("Play the Engine")
+                    .title_bottom("Enter: start  Esc: back")
+                    .title_alignment(Alignment::Center),
+            )
+            .style(Style::default().fg(theme.text));
+
+        frame.render_widget(AppTitle, layout[0]);
+        frame.render_widget(list, menu_layout[0]);
+    }
+}
+
+impl EngineSetup {
+    fn selected_value(&mut self) -> (&mut usize, usize) {
+        match self.row {
+            SetupRow::Color => (&mut self.color, COLOR_CHOICES.len()),
+            SetupRow::Strength => (&mut self.level, self.levels.len()),
+            SetupRow::TimeControl => (&mut self.time_control, TIME_CONTROLS.len()),
+        }
+    }
+}
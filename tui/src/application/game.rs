@@ -1,35 +1,317 @@
+use std::{borrow::Cow, cell::Cell, sync::Arc};
+
 use domain::{
-    board::{Direction, Position},
-    game::{Color, GameState},
-    moves::{Move, MoveType},
-    pieces::PromotionPiece,
+    board::{Board as DomainBoard, Direction, Position},
+    game::{Color, GameState, San},
+    moves::Move,
 };
+use engine::{book::Book, tablebase::Tablebase};
 use ratatui::{
-    layout::{Constraint, Direction as LayoutDirection, Layout, Rect},
+    layout::{
+        Alignment, Constraint, Direction as LayoutDirection, Layout, Position as ScreenPosition,
+        Rect,
+    },
+    widgets::Paragraph,
     Frame,
 };
 
-use crate::widgets::{promotion_menu::PromotionMenu, Board};
+use crate::{
+    constants::COMPACT_WIDTH,
+    theme::Theme,
+    widgets::{
+        board_layout::BoardLayout, game_over::GameOver, promotion_menu::PromotionMenu,
+        tablebase::TablebaseVerdict, Board, MoveHistory, TablebaseIndicator,
+    },
+};
+
+use super::{Analysis, AnalysisKind, EngineOpponent, EngineSettings, GameClock, TablebaseProbe};
 
-#[derive(Debug, Clone)]
 pub struct Game {
     pub game_state: GameState,
+    /// The moves played so far in SAN, kept alongside the history for display.
+    pub san_history: Vec<String>,
     pub view_state: ViewState,
     pub game_type: GameType,
     pub promotion_menu: Option<PromotionMenu>,
+    /// The result dialog, once the game is over.
+    pub game_over: Option<GameOver>,
+    pub tablebase_verdict: Option<TablebaseVerdict>,
+    /// The lookup of the verdict for the current position, while it runs.
+    pub tablebase_probe: Option<TablebaseProbe>,
+    pub analysis: Option<Analysis>,
+    pub status: Option<String>,
+    pub engine: Option<EngineOpponent>,
+    pub clock: Option<GameClock>,
+    /// How many moves into the game the board shows while browsing the history, `None`
+    /// for the live position.
+    pub viewing: Option<usize>,
+    /// The square a piece was picked up from with the mouse, while the button is held.
+    pub drag_start: Option<Position>,
+    /// Whether the terminal has focus. Without it the player's clock stands still.
+    focused: bool,
+    tablebase: Option<Arc<Tablebase>>,
+    /// Where the game was last drawn, to find the action under a click on the result
+    /// dialog.
+    area: Cell<Rect>,
+    /// Where the board was last drawn, to find the square under a click.
+    board_area: Cell<Rect>,
+    /// Where the move list was last drawn, to find the move under a click.
+    history_area: Cell<Rect>,
 }
 
 impl Game {
-    pub fn new(game_state: GameState, game_type: GameType) -> Self {
-        Self {
+    pub fn new(
+        game_state: GameState,
+        game_type: GameType,
+        tablebase: Option<Arc<Tablebase>>,
+        book: Option<Arc<Book>>,
+    ) -> Self {
+        let (engine, clock) = match &game_type {
+            GameType::AgainstBot(settings) => (
+                Some(EngineOpponent::new(
+                    settings.level.clone(),
+                    settings.engine_color,
+                    book,
+                    tablebase.clone(),
+                )),
+                settings.time_control.as_ref().map(GameClock::new),
+            ),
+            _ => (None, None),
+        };
+
+        // Black sits at the bottom when the engine has white
+        let mut view_state = ViewState::default();
+        if engine
+            .as_ref()
+            .is_some_and(|engine| engine.color == Color::White)
+        {
+            view_state.flip();
+        }
+
+        let mut game = Self {
             game_state,
-            view_state: ViewState::default(),
+            san_history: vec![],
+            view_state,
             game_type,
             promotion_menu: None,
+            game_over: None,
+            tablebase_verdict: None,
+            tablebase_probe: None,
+            analysis: None,
+            status: None,
+            engine,
+            clock,
+            viewing: None,
+            drag_start: None,
+            focused: true,
+            tablebase,
+            area: Cell::default(),
+            board_area: Cell::default(),
+            history_area: Cell::default(),
+        };
+        game.probe_tablebase();
+        game.update_game_over();
+        game
+    }
+
+    pub fn make_move(&mut self, m: Move) {
+        if let Some(clock) = self.clock.as_mut() {
+            clock.press(self.game_state.current_player.color);
+        }
+        self.san_history
+            .push(San::derive(&self.game_state, &m).to_string());
+        self.game_state.make_move(m);
+        self.probe_tablebase();
+        self.update_game_over();
+        self.analysis = None;
+        self.status = None;
+        self.view_state.hint = None;
+        self.view_state.threat = None;
+
+        // In hot-seat games the board turns to whoever is to move
+        let to_move = self.game_state.current_player.color;
+        if matches!(self.game_type, GameType::Normal)
+            && self.view_state.flipped != (to_move == Color::Black)
+        {
+            self.view_state.flip();
+        }
+    }
+
+    pub fn view_first(&mut self) {
+        self.view(0);
+    }
+
+    pub fn view_previous(&mut self) {
+        let shown = self.viewing.unwrap_or(self.san_history.len());
+        self.view(shown.saturating_sub(1));
+    }
+
+    pub fn view_next(&mut self) {
+        if let Some(shown) = self.viewing {
+            self.view(shown + 1);
+        }
+    }
+
+    pub fn view_last(&mut self) {
+        self.viewing = None;
+    }
+
+    /// Shows the position after `moves` moves, the live one once it is reached.
+    pub fn view(&mut self, moves: usize) {
+        self.viewing = Some(moves).filter(|moves| *moves < self.san_history.len());
+    }
+
+    /// Shows the position after the move clicked in the move list. Returns whether
+    /// there was a move under the click.
+    pub fn click_history(&mut self, position: ScreenPosition) -> bool {
+        let history = MoveHistory::new(&self.game_state, &self.san_history, None);
+        let ply = history.ply_at(self.history_area.get(), position);
+        if let Some(ply) = ply {
+            self.view(ply + 1);
+        }
+        ply.is_some()
+    }
+
+    /// The square drawn at the terminal cell `position`, as the board was last drawn.
+    pub fn square_at(&self, position: ScreenPosition) -> Option<Position> {
+        let (row, column) = BoardLayout::fit(self.board_area.get())?.square_under(position)?;
+        Some(self.view_state.square_at(row, column))
+    }
+
+    /// The piece of the open promotion menu drawn at the terminal cell `position`.
+    pub fn promotion_piece_at(&self, position: ScreenPosition) -> Option<usize> {
+        self.promotion_menu
+            .as_ref()?
+            .piece_at(self.board_area.get(), position)
+    }
+
+    /// The action of the result dialog drawn at the terminal cell `position`.
+    pub fn game_over_action_at(&self, position: ScreenPosition) -> Option<usize> {
+        self.game_over
+            .as_ref()
+            .filter(|game_over| self.shows_game_over(game_over))?
+            .action_at(self.area.get(), position)
+    }
+
+    /// The board on display: a past position while browsing the history, otherwise the
+    /// live one. The game itself is left untouched.
+    pub fn shown_board(&self) -> Cow<'_, DomainBoard> {
+        let Some(moves) = self.viewing else {
+            return Cow::Borrowed(&self.game_state.board);
+        };
+
+        let mut board = match &self.game_state.setup {
+            Some(fen) => GameState::from_fen(fen).unwrap_or_default().board,
+            None => DomainBoard::new(),
+        };
+        for record in self.game_state.move_history.iter().take(moves) {
+            record.mv.execute(&mut board);
+        }
+        Cow::Owned(board)
+    }
+
+    /// Whether the side to move is played from the keyboard.
+    pub fn is_human_turn(&self) -> bool {
+        let to_move = self.game_state.current_player.color;
+        self.engine
+            .as_ref()
+            .is_none_or(|engine| engine.color != to_move)
+    }
+
+    /// Advances everything that runs in the background: analysis, the tablebase lookup,
+    /// the engine's move and the clock.
+    pub fn tick(&mut self) {
+        self.poll_analysis();
+        self.poll_tablebase();
+        if self.game_state.is_game_over() {
+            return;
+        }
+        self.update_clock_pause();
+
+        let to_move = self.game_state.current_player.color;
+        if self
+            .clock
+            .as_ref()
+            .is_some_and(|clock| clock.is_flagged(to_move))
+        {
+            self.game_state.lose_on_time();
+            self.update_game_over();
+            return;
+        }
+
+        let Some(engine) = self
+            .engine
+            .as_mut()
+            .filter(|engine| engine.color == to_move)
+        else {
+            return;
+        };
+        let engine_move = engine.poll();
+        if engine_move.is_none() && !engine.is_thinking() {
+            let clock = self.clock.as_ref().map(|clock| clock.search_clock(to_move));
+            engine.think(&self.game_state, clock);
+        }
+        if let Some(m) = engine_move {
+            self.make_move(m);
+            self.view_state.selected_position = None;
+            self.view_state.currently_legal_moves.clear();
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.update_clock_pause();
+    }
+
+    /// Searches for a hint or a threat in the background; the move is shown on the board
+    /// once `poll_analysis` picks it up.
+    pub fn start_analysis(&mut self, kind: AnalysisKind) {
+        self.analysis = Analysis::start(kind, &self.game_state, self.tablebase.clone());
+        self.status = match (&self.analysis, kind) {
+            (Some(_), AnalysisKind::Hint) => Some("Looking for a hint...".to_string()),
+            (Some(_), AnalysisKind::Threat) => Some("Looking for a threat...".to_string()),
+            (None, AnalysisKind::Hint) => None,
+            (None, AnalysisKind::Threat) => Some("No threat while in check".to_string()),
+        };
+    }
+
+    pub fn poll_analysis(&mut self) {
+        let Some(analysis) = &self.analysis else {
+            return;
+        };
+        let Some(best_move) = analysis.poll() else {
+            return;
+        };
+
+        self.status = best_move.is_none().then(|| "No move found".to_string());
+        match analysis.kind {
+            AnalysisKind::Hint => self.view_state.hint = best_move,
+            AnalysisKind::Threat => self.view_state.threat = best_move,
         }
+        self.analysis = None;
+    }
+
+    /// Looks up the new position in the background; the verdict of the last one is
+    /// dropped right away so it is never shown for the wrong position.
+    fn probe_tablebase(&mut self) {
+        self.tablebase_verdict = None;
+        self.tablebase_probe = TablebaseProbe::start(&self.game_state, self.tablebase.clone());
     }
 
+    fn poll_tablebase(&mut self) {
+        let Some(verdict) = self.tablebase_probe.as_ref().and_then(TablebaseProbe::poll) else {
+            return;
+        };
+        self.tablebase_verdict = verdict;
+        self.tablebase_probe = None;
+    }
+
+    /// Moves the cursor as seen on screen, whichever way the board is turned.
     pub fn move_cursor(&mut self, direction: Direction) {
+        let direction = match self.view_state.flipped {
+            true => direction.opposite(),
+            false => direction,
+        };
         self.view_state.cursor_position += direction;
 
         if self.view_state.cursor_position.row > 7 {
@@ -50,9 +332,9 @@ impl Game {
     }
 
     pub fn select_piece(&mut self) {
-        if let Some(_) = self.game_state.board[&self.view_state.cursor_position] {
+        if self.game_state.board[&self.view_state.cursor_position].is_some() {
             let position = self.view_state.cursor_position;
-            self.view_state.selected_position = Some(position.clone());
+            self.view_state.selected_position = Some(position);
             self.view_state.currently_legal_moves.clear();
             self.view_state.currently_legal_moves.extend(
                 self.game_state
@@ -62,30 +344,30 @@ impl Game {
         }
     }
 
-    pub fn render_self(&self, frame: &mut Frame, area: Rect) {
+    pub fn render_self(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        // The header and status lines keep a row each in short terminals
+        let bar_height = (area.height / 18).max(1);
         let main_layout = Layout::default()
             .direction(LayoutDirection::Vertical)
             .constraints(
                 [
-                    Constraint::Ratio(1, 18),
-                    Constraint::Ratio(16, 18),
-                    Constraint::Ratio(1, 18),
+                    Constraint::Length(bar_height),
+                    Constraint::Min(0),
+                    Constraint::Length(bar_height),
                 ]
                 .as_ref(),
             )
             .split(area);
 
+        // Narrow terminals only get the board
+        let compact = area.width < COMPACT_WIDTH;
+        let columns = match compact {
+            true => [(0, 1), (1, 1), (0, 1), (0, 1)],
+            false => [(2, 17), (9, 17), (1, 17), (5, 17)],
+        };
         let layout_vertical = Layout::default()
             .direction(LayoutDirection::Horizontal)
-            .constraints(
-                [
-                    Constraint::Ratio(2, 17),
-                    Constraint::Ratio(9, 17),
-                    Constraint::Ratio(1, 17),
-                    Constraint::Ratio(5, 17),
-                ]
-                .as_ref(),
-            )
+            .constraints(columns.map(|(part, whole)| Constraint::Ratio(part, whole)))
             .split(main_layout[1]);
 
         let history_area = Layout::default()
@@ -100,12 +382,138 @@ impl Game {
             )
             .split(layout_vertical[3]);
 
-        let board = Board::new(&self.game_state.board, &self.view_state);
+        let shown_board = self.shown_board();
+        let history_view_state = ViewState {
+            cursor_position: self.view_state.cursor_position,
+            ..ViewState::default()
+        };
+        let view_state = match self.viewing {
+            Some(_) => &history_view_state,
+            None => &self.view_state,
+        };
+        let shown_moves = self.viewing.unwrap_or(self.game_state.move_history.len());
+        let last_move = shown_moves
+            .checked_sub(1)
+            .and_then(|ply| self.game_state.move_history.get(ply))
+            .map(|record| &record.mv);
+        let board = Board::new(&shown_board, view_state, theme).last_move(last_move);
 
+        if let Some(header) = self.header() {
+            frame.render_widget(
+                Paragraph::new(header).alignment(Alignment::Center),
+                main_layout[0],
+            );
+        }
         frame.render_widget(board, layout_vertical[1]);
+        self.area.set(area);
+        self.board_area.set(layout_vertical[1]);
+        self.history_area.set(history_area[1]);
+        if !compact {
+            frame.render_widget(
+                MoveHistory::new(
+                    &self.game_state,
+                    &self.san_history,
+                    self.viewing
+                        .unwrap_or(self.san_history.len())
+                        .checked_sub(1),
+                )
+                .theme(theme),
+                history_area[1],
+            );
+            frame.render_widget(
+                Paragraph::new(format!("Cursor: {}", self.view_state.cursor_position))
+                    .alignment(Alignment::Center),
+                history_area[2],
+            );
+        }
+        if let Some(moves) = self.viewing {
+            let text = format!(
+                "Viewing move {} of {}  (End: back to the game)",
+                moves,
+                self.san_history.len()
+            );
+            frame.render_widget(
+                Paragraph::new(text).alignment(Alignment::Center),
+                main_layout[2],
+            );
+        } else if self.game_state.is_game_over() {
+            frame.render_widget(
+                Paragraph::new("Game over. h: show or hide the result")
+                    .alignment(Alignment::Center),
+                main_layout[2],
+            );
+        } else if let Some(status) = &self.status {
+            frame.render_widget(
+                Paragraph::new(status.as_str()).alignment(Alignment::Center),
+                main_layout[2],
+            );
+        } else if let Some(verdict) = self.tablebase_verdict {
+            frame.render_widget(TablebaseIndicator::new(verdict), main_layout[2]);
+        }
         if let Some(promotion_menu) = &self.promotion_menu {
             frame.render_widget(promotion_menu.clone(), layout_vertical[1]);
         }
+        if let Some(game_over) = self
+            .game_over
+            .as_ref()
+            .filter(|game_over| self.shows_game_over(game_over))
+        {
+            game_over.render_self(frame, area, theme);
+        }
+    }
+}
+
+impl Game {
+    /// Stops the player's clock while the terminal is out of focus. The engine's keeps
+    /// running, it thinks either way.
+    fn update_clock_pause(&mut self) {
+        let pause = !self.focused && self.is_human_turn() && !self.game_state.is_game_over();
+        if let Some(clock) = self.clock.as_mut() {
+            match pause {
+                true => clock.pause(),
+                false => clock.resume(),
+            }
+        }
+    }
+
+    fn update_game_over(&mut self) {
+        if self.game_over.is_none() {
+            self.game_over = self.game_state.result.clone().map(GameOver::from);
+        }
+    }
+
+    /// The result dialog steps aside while the history is browsed.
+    fn shows_game_over(&self, game_over: &GameOver) -> bool {
+        !game_over.hidden && self.viewing.is_none()
+    }
+
+    /// The engine and the clocks, when the game has them.
+    fn header(&self) -> Option<String> {
+        let engine = self.engine.as_ref().map(|engine| {
+            let thinking = if engine.is_thinking() {
+                " thinking..."
+            } else {
+                ""
+            };
+            format!(
+                "Engine: {} (~{} Elo){}",
+                engine.level.name, engine.level.elo, thinking
+            )
+        });
+        let Some(clock) = &self.clock else {
+            return engine;
+        };
+
+        let to_move = self.game_state.current_player.color;
+        let time = |color| GameClock::format(clock.remaining(color, to_move));
+        let paused = if clock.is_paused() { "   (paused)" } else { "" };
+        Some(format!(
+            "White {}   {}   Black {}{}",
+            time(Color::White),
+            engine.unwrap_or_default(),
+            time(Color::Black),
+            paused
+        ))
     }
 }
 
@@ -114,6 +522,10 @@ pub struct ViewState {
     pub cursor_position: Position,
     pub selected_position: Option<Position>,
     pub currently_legal_moves: Vec<Move>,
+    pub hint: Option<Move>,
+    pub threat: Option<Move>,
+    /// Whether black's side of the board is drawn at the bottom.
+    pub flipped: bool,
 }
 
 impl Default for ViewState {
@@ -122,14 +534,94 @@ impl Default for ViewState {
             cursor_position: Position::from((6, 3)),
             selected_position: None,
             currently_legal_moves: Vec::with_capacity(17), // 17 is the maximum potential number of moves for a queen
+            hint: None,
+            threat: None,
+            flipped: false,
+        }
+    }
+}
+
+impl ViewState {
+    /// The square drawn in screen row `row` and column `column`.
+    pub fn square_at(&self, row: usize, column: usize) -> Position {
+        match self.flipped {
+            true => Position::from((7 - row, 7 - column)),
+            false => Position::from((row, column)),
         }
     }
+
+    /// Turns the board around. The cursor keeps its place on screen.
+    pub fn flip(&mut self) {
+        self.flipped = !self.flipped;
+        self.cursor_position = Position::from((
+            7 - self.cursor_position.row,
+            7 - self.cursor_position.column,
+        ));
+    }
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Default)]
 pub enum GameType {
     #[default]
     Normal,
     Online,
-    AgainstBot,
+    AgainstBot(EngineSettings),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_state_square_at() {
+        let mut view_state = ViewState::default();
+        assert_eq!(view_state.square_at(0, 0), Position::from((0, 0)));
+        assert_eq!(view_state.square_at(6, 2), Position::from((6, 2)));
+
+        view_state.flipped = true;
+        assert_eq!(view_state.square_at(0, 0), Position::from((7, 7)));
+        assert_eq!(view_state.square_at(6, 2), Position::from((1, 5)));
+    }
+
+    #[test]
+    fn test_square_at_a_click_on_the_board() {
+        let mut game = Game::new(GameState::new(), GameType::Normal, None, None);
+        // Squares of 5 by 3 cells, the top left one at (2, 1)
+        game.board_area.set(Rect::new(0, 0, 44, 26));
+        assert_eq!(
+            game.square_at(ScreenPosition::new(2, 1)),
+            Some(Position::from((0, 0)))
+        );
+        assert_eq!(
+            game.square_at(ScreenPosition::new(41, 24)),
+            Some(Position::from((7, 7)))
+        );
+        assert_eq!(
+            game.square_at(ScreenPosition::new(8, 22)),
+            Some(Position::from((7, 1)))
+        );
+
+        game.view_state.flip();
+        assert_eq!(
+            game.square_at(ScreenPosition::new(2, 1)),
+            Some(Position::from((7, 7)))
+        );
+        assert_eq!(
+            game.square_at(ScreenPosition::new(8, 22)),
+            Some(Position::from((0, 6)))
+        );
+    }
+
+    #[test]
+    fn test_square_at_a_click_off_the_board() {
+        let game = Game::new(GameState::new(), GameType::Normal, None, None);
+        // Nothing drawn yet
+        assert_eq!(game.square_at(ScreenPosition::new(5, 5)), None);
+
+        game.board_area.set(Rect::new(0, 0, 44, 26));
+        assert_eq!(game.square_at(ScreenPosition::new(0, 10)), None);
+        assert_eq!(game.square_at(ScreenPosition::new(20, 25)), None);
+        assert_eq!(game.square_at(ScreenPosition::new(43, 10)), None);
+    }
 }
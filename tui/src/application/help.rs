@@ -0,0 +1,148 @@
+use std::{cell::Cell, collections::HashMap};
+
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::{key_bindings::ContextBindings, theme::Theme};
+
+use super::EventContext;
+
+const RULES: &[&str] = &[
+    "White moves first, then the players take turns. The goal is to checkmate the",
+    "opposing king: to attack it so that no move gets it out of the attack.",
+    "",
+    "King      one square in any direction",
+    "Queen     any number of squares along a rank, file or diagonal",
+    "Rook      any number of squares along a rank or file",
+    "Bishop    any number of squares along a diagonal",
+    "Knight    an L: two squares one way and one square to the side, jumping",
+    "Pawn      one square forward, two from its starting square, captures diagonally",
+    "",
+    "Castling  the king moves two squares towards a rook, which jumps over it. Neither",
+    "          may have moved, the squares between them must be empty and the king",
+    "          may not be in, pass through or land in check.",
+    "En passant  a pawn that moved two squares can be captured by an enemy pawn beside",
+    "          it, as if it had moved one, but only on the very next move.",
+    "Promotion a pawn reaching the last rank becomes a queen, rook, bishop or knight.",
+    "",
+    "A move may never leave your own king in check.",
+];
+
+const DRAWS: &[&str] = &[
+    "Stalemate             the player to move is not in check but has no legal move.",
+    "Fifty-move rule       fifty moves by each player without a capture or a pawn move.",
+    "Threefold repetition  the same position occurs for the third time, with the same",
+    "                      player to move and the same castling and en passant rights.",
+    "Insufficient material neither side can mate: king against king, or king and a",
+    "                      single bishop or knight against king.",
+    "",
+    "These draws are applied as soon as they happen, without a claim.",
+];
+
+/// Width of the command name column in the key tables.
+const NAME_WIDTH: usize = 16;
+
+/// The rules, the draw rules and the keys of every screen, scrolled line by line.
+#[derive(Debug, Default)]
+pub struct Help {
+    pub scroll: u16,
+    /// The lines on screen and the largest scroll that still fills it, as of the last
+    /// draw.
+    height: Cell<u16>,
+    max_scroll: Cell<u16>,
+}
+
+impl Help {
+    pub fn scroll_by(&mut self, lines: i32) {
+        let scroll = i32::from(self.scroll) + lines;
+        self.scroll = scroll.clamp(0, i32::from(self.max_scroll.get())) as u16;
+    }
+
+    pub fn scroll_pages(&mut self, pages: i32) {
+        let page = i32::from(self.height.get().saturating_sub(1).max(1));
+        self.scroll_by(pages * page);
+    }
+
+    pub fn scroll_to_end(&mut self) {
+        self.scroll = self.max_scroll.get();
+    }
+
+    pub fn render_self(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        key_help: &HashMap<EventContext, ContextBindings>,
+        theme: &Theme,
+    ) {
+        let heading = |text: &'static str| {
+            Line::styled(
+                text,
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+
+        let mut lines = vec![heading("Rules")];
+        lines.extend(RULES.iter().map(|line| Line::from(*line)));
+        lines.push(Line::from(""));
+        lines.push(heading("Draws"));
+        lines.extend(DRAWS.iter().map(|line| Line::from(*line)));
+        lines.push(Line::from(""));
+        lines.push(heading("Keys"));
+
+        for context in EventContext::ALL {
+            let Some(bindings) = key_help.get(&context) else {
+                continue;
+            };
+            lines.push(Line::from(""));
+            lines.push(Line::styled(
+                context_title(context),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            for (name, keys) in bindings {
+                let keys = match keys.is_empty() {
+                    true => "-".to_string(),
+                    false => keys.join(", "),
+                };
+                lines.push(Line::from(vec![
+                    Span::raw(format!("  {:<width$}", name, width = NAME_WIDTH)),
+                    Span::raw(keys),
+                ]));
+            }
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Help")
+            .title_alignment(Alignment::Center);
+        let height = block.inner(area).height;
+        let max_scroll = (lines.len() as u16).saturating_sub(height);
+        self.height.set(height);
+        self.max_scroll.set(max_scroll);
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .scroll((self.scroll.min(max_scroll), 0)),
+            area,
+        );
+    }
+}
+
+fn context_title(context: EventContext) -> &'static str {
+    match context {
+        EventContext::MainMenu => "Main menu",
+        EventContext::EngineSetup => "Engine setup",
+        EventContext::Game => "Game",
+        EventContext::PromotionMenu => "Promotion",
+        EventContext::GameOver => "Game over",
+        EventContext::Review => "Review",
+        EventContext::Help => "Help",
+    }
+}
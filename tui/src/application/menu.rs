@@ -1,19 +1,38 @@
+use std::cell::Cell;
+
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Position, Rect},
+    style::{Modifier, Style},
     text::Text,
     widgets::{Block, Borders, List, ListItem, Padding},
     Frame,
 };
 
-use crate::widgets::AppTitle;
+use crate::{theme::Theme, widgets::AppTitle};
+
+pub const MENU_ITEMS: [(&str, MenuItem); 4] = [
+    ("Human vs. Human", MenuItem::HumanVsHuman),
+    ("Human vs. Engine", MenuItem::HumanVsEngine),
+    ("Online Game", MenuItem::OnlineGame),
+    ("Help", MenuItem::Help),
+];
 
-pub const MENU_ITEMS: [&str; 4] = ["Human vs. Human", "Human vs. Engine", "Online Game", "Help"];
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    HumanVsHuman,
+    HumanVsEngine,
+    OnlineGame,
+    Help,
+}
 
 #[derive(Debug, Clone)]
 pub struct Menu {
-    pub items: [&'static str; 4],
+    pub items: [(&'static str, MenuItem); 4],
     pub selected: usize,
+    /// Shown below the options, e.g. why one could not be opened.
+    pub status: Option<String>,
+    /// Where the options were last drawn, to find the one under a click.
+    list_area: Cell<Rect>,
 }
 
 impl Default for Menu {
@@ -21,6 +40,8 @@ impl Default for Menu {
         Self {
             items: MENU_ITEMS,
             selected: 0,
+            status: None,
+            list_area: Cell::default(),
         }
     }
 }
@@ -28,9 +49,11 @@ impl Default for Menu {
 impl Menu {
     pub fn next(&mut self) {
         self.selected = (self.selected + 1) % self.items.len();
+        self.status = None;
     }
 
     pub fn previous(&mut self) {
+        self.status = None;
         if self.selected > 0 {
             self.selected -= 1;
         } else {
@@ -38,7 +61,20 @@ impl Menu {
         }
     }
 
-    pub fn render_self(&self, frame: &mut Frame, main_area: Rect) {
+    pub fn selected_item(&self) -> MenuItem {
+        self.items[self.selected].1
+    }
+
+    /// The option drawn at the terminal cell `position`, as the menu was last drawn.
+    pub fn item_at(&self, position: Position) -> Option<usize> {
+        let inner = Self::block().inner(self.list_area.get());
+        if !inner.contains(position) {
+            return None;
+        }
+        Some(usize::from(position.y - inner.y)).filter(|item| *item < self.items.len())
+    }
+
+    pub fn render_self(&self, frame: &mut Frame, main_area: Rect, theme: &Theme) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
@@ -54,12 +90,12 @@ impl Menu {
             .items
             .iter()
             .enumerate()
-            .map(|(i, &item)| {
+            .map(|(i, &(item, _))| {
                 if self.selected == i {
                     ListItem::new(Text::from(format!("> {}", item)).alignment(Alignment::Center))
                         .style(
                             Style::default()
-                                .fg(Color::Yellow)
+                                .fg(theme.accent)
                                 .add_modifier(Modifier::BOLD),
                         )
                 } else {
@@ -70,16 +106,52 @@ impl Menu {
             .collect();
 
         let list = List::new(list_items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .padding(Padding::top(2))
-                    .title("Options")
-                    .title_alignment(Alignment::Center),
-            )
-            .style(Style::default().fg(Color::White));
+            .block(Self::block().title_bottom(self.status.clone().unwrap_or_default()))
+            .style(Style::default().fg(theme.text));
 
         frame.render_widget(AppTitle, layout[0]);
         frame.render_widget(list, menu_layout[0]);
+        self.list_area.set(menu_layout[0]);
+    }
+}
+
+impl Menu {
+    fn block() -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::top(2))
+            .title("Options")
+            .title_alignment(Alignment::Center)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_at_each_option() {
+        let menu = Menu::default();
+        menu.list_area.set(Rect::new(10, 5, 20, 10));
+        // Inside the border and the padding, the options start on row 8
+        for item in 0..MENU_ITEMS.len() {
+            assert_eq!(menu.item_at(Position::new(15, 8 + item as u16)), Some(item));
+        }
+    }
+
+    #[test]
+    fn test_item_at_padding_border_or_outside_is_none() {
+        let menu = Menu::default();
+        menu.list_area.set(Rect::new(10, 5, 20, 10));
+        assert_eq!(menu.item_at(Position::new(15, 7)), None);
+        assert_eq!(menu.item_at(Position::new(15, 12)), None);
+        assert_eq!(menu.item_at(Position::new(10, 8)), None);
+        assert_eq!(menu.item_at(Position::new(29, 8)), None);
+        assert_eq!(menu.item_at(Position::new(40, 8)), None);
+    }
+
+    #[test]
+    fn test_item_at_before_the_menu_is_drawn_is_none() {
+        assert_eq!(Menu::default().item_at(Position::new(0, 0)), None);
     }
 }
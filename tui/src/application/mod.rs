@@ -1,7 +1,21 @@
+pub mod analysis;
 pub mod app;
+pub mod clock;
+pub mod engine_opponent;
+pub mod engine_setup;
 pub mod game;
+pub mod help;
 pub mod menu;
+pub mod review;
+pub mod tablebase;
 
+pub use analysis::*;
 pub use app::*;
+pub use clock::*;
+pub use engine_opponent::*;
+pub use engine_setup::*;
 pub use game::*;
+pub use help::*;
 pub use menu::*;
+pub use review::*;
+pub use tablebase::*;
@@ -0,0 +1,277 @@
+use std::{
+    fs,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use domain::{
+    board::Position,
+    game::{Color as PieceColor, GameResult, GameState, Pgn},
+};
+use engine::{
+    review::{eval_text, review_game, GameReview, MoveClass},
+    search::Searcher,
+};
+use ratatui::{
+    layout::{Constraint, Direction as LayoutDirection, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::{
+    constants::{REVIEW_DEPTH, REVIEW_PGN_PATH},
+    theme::Theme,
+    widgets::Board,
+};
+
+use super::ViewState;
+
+enum ReviewUpdate {
+    Progress(usize, usize),
+    Done(GameReview),
+}
+
+/// A finished game being reviewed. The analysis runs on a background thread and is
+/// picked up on every tick.
+pub struct Review {
+    pub game_state: GameState,
+    pub review: Option<GameReview>,
+    pub progress: (usize, usize),
+    /// Index of the selected move in the move history.
+    pub selected: usize,
+    pub status: Option<String>,
+    receiver: Receiver<ReviewUpdate>,
+}
+
+impl Review {
+    pub fn start(game_state: GameState) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let reviewed = game_state.clone();
+        thread::spawn(move || {
+            let mut searcher = Searcher::default();
+            let progress = sender.clone();
+            let review = review_game(&mut searcher, &reviewed, REVIEW_DEPTH, |done, total| {
+                let _ = progress.send(ReviewUpdate::Progress(done, total));
+            });
+            let _ = sender.send(ReviewUpdate::Done(review));
+        });
+
+        Self {
+            progress: (0, game_state.move_history.len() + 1),
+            game_state,
+            review: None,
+            selected: 0,
+            status: None,
+            receiver,
+        }
+    }
+
+    /// Takes in the updates the analysis sent since the last call.
+    pub fn poll(&mut self) {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(ReviewUpdate::Progress(done, total)) => self.progress = (done, total),
+                Ok(ReviewUpdate::Done(review)) => self.review = Some(review),
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+            }
+        }
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.game_state.move_history.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Writes the game with the review as NAGs and comments to `REVIEW_PGN_PATH`.
+    pub fn save_pgn(&mut self) {
+        let Some(review) = &self.review else {
+            self.status = Some("The review is not finished yet".to_string());
+            return;
+        };
+
+        let result = self.game_state.result.as_ref().map(GameResult::score);
+        let mut pgn = Pgn::from_game(&self.game_state, vec![], result);
+        review.annotate(&mut pgn);
+        self.status = Some(match fs::write(REVIEW_PGN_PATH, pgn.to_string()) {
+            Ok(()) => format!("Saved to {}", REVIEW_PGN_PATH),
+            Err(e) => format!("Could not save: {}", e),
+        });
+    }
+
+    pub fn render_self(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let layout = Layout::default()
+            .direction(LayoutDirection::Horizontal)
+            .constraints([Constraint::Ratio(11, 17), Constraint::Ratio(6, 17)].as_ref())
+            .split(area);
+
+        let panel = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(4),
+                    Constraint::Min(3),
+                    Constraint::Length(5),
+                ]
+                .as_ref(),
+            )
+            .split(layout[1]);
+
+        let position = self.selected_position();
+        // The board is only looked at, so no cursor is drawn
+        let view_state = ViewState {
+            cursor_position: Position::from((-1i8, -1i8)),
+            ..ViewState::default()
+        };
+        let selected_move = self
+            .game_state
+            .move_history
+            .get(self.selected)
+            .map(|record| &record.mv);
+        frame.render_widget(
+            Board::new(&position.board, &view_state, theme).last_move(selected_move),
+            layout[0],
+        );
+        frame.render_widget(self.summary(), panel[0]);
+
+        let mut list_state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(self.move_list(theme), panel[1], &mut list_state);
+        frame.render_widget(self.details(theme), panel[2]);
+    }
+}
+
+impl Review {
+    /// The position after the selected move.
+    fn selected_position(&self) -> GameState {
+        let mut position = self.start_position();
+        for record in self.game_state.move_history.iter().take(self.selected + 1) {
+            position.make_move(record.mv.clone());
+        }
+        position
+    }
+
+    fn start_position(&self) -> GameState {
+        match &self.game_state.setup {
+            Some(fen) => GameState::from_fen(fen).unwrap_or_default(),
+            None => GameState::new(),
+        }
+    }
+
+    fn summary(&self) -> Paragraph<'_> {
+        let block = Block::default().borders(Borders::ALL).title("Review");
+        let Some(review) = &self.review else {
+            let (done, total) = self.progress;
+            return Paragraph::new(format!(
+                "Analysing at depth {}... {}/{}",
+                REVIEW_DEPTH, done, total
+            ))
+            .block(block);
+        };
+
+        let line = |color: PieceColor, name: &str| {
+            let accuracy = review
+                .accuracy(color)
+                .map_or("-".to_string(), |accuracy| format!("{:.1}%", accuracy));
+            Line::from(format!(
+                "{}: {}  {}?! {}? {}??",
+                name,
+                accuracy,
+                review.count(color, MoveClass::Inaccuracy),
+                review.count(color, MoveClass::Mistake),
+                review.count(color, MoveClass::Blunder),
+            ))
+        };
+        Paragraph::new(vec![
+            line(PieceColor::White, "White"),
+            line(PieceColor::Black, "Black"),
+        ])
+        .block(block)
+    }
+
+    fn move_list(&self, theme: &Theme) -> List<'_> {
+        let start = self.start_position();
+        let black_starts = start.current_player.color == PieceColor::Black;
+        let reviews = self.review.as_ref().map(|review| &review.moves);
+        let items: Vec<ListItem> = match reviews {
+            Some(moves) => moves
+                .iter()
+                .map(|review| {
+                    let number =
+                        start.fullmove_number() + (review.ply + usize::from(black_starts)) / 2;
+                    let number = match review.color {
+                        PieceColor::White => format!("{}.", number),
+                        PieceColor::Black => format!("{}...", number),
+                    };
+                    let mut spans = vec![
+                        Span::raw(format!("{:<6}", number)),
+                        Span::styled(
+                            format!("{}{}", review.san, review.class.symbol()),
+                            Style::default().fg(class_color(review.class, theme)),
+                        ),
+                        Span::raw(format!("  {}", eval_text(review.eval_after))),
+                    ];
+                    if review.critical {
+                        spans.push(Span::styled(
+                            "  critical",
+                            Style::default()
+                                .fg(theme.critical_move)
+                                .add_modifier(Modifier::BOLD),
+                        ));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Moves"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+    }
+
+    fn details(&self, theme: &Theme) -> Paragraph<'_> {
+        let selected = self
+            .review
+            .as_ref()
+            .and_then(|review| review.moves.get(self.selected));
+
+        let mut lines = match selected {
+            Some(review) => vec![
+                Line::styled(
+                    format!("{} {}", review.san, review.class.name()),
+                    Style::default().fg(class_color(review.class, theme)),
+                ),
+                Line::from(format!(
+                    "{} -> {}, best {}",
+                    eval_text(review.eval_before),
+                    eval_text(review.eval_after),
+                    review.best_san.as_deref().unwrap_or("-"),
+                )),
+            ],
+            None => vec![],
+        };
+        lines.push(Line::from(
+            self.status
+                .clone()
+                .unwrap_or("j/k: move  s: save PGN  Esc: menu".to_string()),
+        ));
+
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL))
+    }
+}
+
+fn class_color(class: MoveClass, theme: &Theme) -> Color {
+    match class {
+        MoveClass::Best => theme.best_move,
+        MoveClass::Good => theme.good_move,
+        MoveClass::Inaccuracy => theme.inaccuracy,
+        MoveClass::Mistake => theme.mistake,
+        MoveClass::Blunder => theme.blunder,
+    }
+}
@@ -0,0 +1,48 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use domain::game::GameState;
+use engine::tablebase::Tablebase;
+
+use crate::widgets::tablebase::TablebaseVerdict;
+
+/// A tablebase lookup running off the UI thread, as probing reads the table files. It is
+/// polled for the verdict on every tick.
+#[derive(Debug)]
+pub struct TablebaseProbe {
+    receiver: Receiver<Option<TablebaseVerdict>>,
+}
+
+impl TablebaseProbe {
+    /// Starts probing `game_state`, `None` if the tables do not cover it.
+    pub fn start(game_state: &GameState, tablebase: Option<Arc<Tablebase>>) -> Option<Self> {
+        let tablebase = tablebase.filter(|tablebase| tablebase.covers(&game_state.board))?;
+        let board = game_state.board.clone();
+        let side_to_move = game_state.current_player.color;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let verdict = tablebase.probe_wdl(&board, side_to_move).and_then(|wdl| {
+                let dtz = tablebase.probe_dtz(&board, side_to_move)?;
+                Ok(TablebaseVerdict::new(side_to_move, wdl, dtz))
+            });
+            let _ = sender.send(verdict.ok());
+        });
+
+        Some(Self { receiver })
+    }
+
+    /// The verdict, once the lookup has finished; `Some(None)` if it failed.
+    pub fn poll(&self) -> Option<Option<TablebaseVerdict>> {
+        match self.receiver.try_recv() {
+            Ok(verdict) => Some(verdict),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
@@ -9,6 +9,35 @@ pub const TITLE: &str = r#"
  ╚═════╝╚═╝  ╚═╝╚══════╝╚══════╝╚══════╝
 "#;
 
+/// Directory holding Syzygy `.rtbw`/`.rtbz` files, probed once a game reaches the endgame.
+pub const SYZYGY_PATH_ENV: &str = "SYZYGY_PATH";
+
+/// Polyglot opening book the engine plays from in games against it.
+pub const BOOK_PATH_ENV: &str = "CHESS_BOOK";
+
+/// Search depth of the hint and threat analysis during a game.
+pub const ANALYSIS_DEPTH: u32 = 6;
+/// Search depth of the post-game review.
+pub const REVIEW_DEPTH: u32 = 6;
+/// File the reviewed game is saved to as annotated PGN.
+pub const REVIEW_PGN_PATH: &str = "game_review.pgn";
+/// File a finished game is exported to as PGN. If it exists the export is numbered.
+pub const GAME_PGN_PATH: &str = "game.pgn";
+
+/// Smallest terminal the screens are drawn in, below it only a warning is shown.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 12;
+/// Below this width the game screen leaves out the move list and gives the board all
+/// the room.
+pub const COMPACT_WIDTH: u16 = 70;
+
+/// Directory under the user's config directory holding the settings files.
+pub const CONFIG_DIR: &str = "chess-tui";
+/// Colour theme and piece set, see `Theme::parse` for the format.
+pub const THEME_FILE: &str = "theme.toml";
+/// Key bindings, see `KeyBindings::parse` for the format.
+pub const KEYS_FILE: &str = "keys.toml";
+
 pub const WHITE: Color = Color::Rgb(194, 178, 128);
 pub const BLACK: Color = Color::Rgb(101, 67, 33);
 
@@ -59,3 +88,33 @@ pub const ROOK: &str = "\
     ▐███▌\n\
    ▗█████▖\n\
 ";
+
+pub const MEDIUM_BISHOP: &str = "\
+⭘
+█✝█
+▟███▙";
+
+pub const MEDIUM_KING: &str = "\
+✚
+▐█▌
+▟███▙";
+
+pub const MEDIUM_KNIGHT: &str = "\
+▟▛█▙
+ ▟██
+▟███▙";
+
+pub const MEDIUM_PAWN: &str = "\
+▟▙
+▜▛
+▟██▙";
+
+pub const MEDIUM_QUEEN: &str = "\
+◀▟█▙▶
+◥█◈█◤
+▟███▙";
+
+pub const MEDIUM_ROOK: &str = "\
+▙█▟
+▐█▌
+▟███▙";
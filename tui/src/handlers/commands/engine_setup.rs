@@ -0,0 +1,35 @@
+use derive_new::new;
+use domain::board::Direction;
+
+use crate::application::{App, AppResult, GameType};
+
+use super::Command;
+
+#[derive(Debug, Clone, new)]
+pub(super) struct EngineSetupNavigationCommand {
+    direction: Direction,
+}
+
+impl Command for EngineSetupNavigationCommand {
+    fn execute(&self, state: &mut App) -> AppResult<()> {
+        match self.direction {
+            Direction::North => state.engine_setup.previous_row(),
+            Direction::South => state.engine_setup.next_row(),
+            Direction::East => state.engine_setup.next_value(),
+            Direction::West => state.engine_setup.previous_value(),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct EngineSetupEnterCommand;
+
+impl Command for EngineSetupEnterCommand {
+    fn execute(&self, state: &mut App) -> AppResult<()> {
+        let settings = state.engine_setup.settings();
+        state.start_game(GameType::AgainstBot(settings));
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
+use derive_new::new;
 use domain::{
     board::Direction,
     moves::{Move, MoveType},
 };
 
 use crate::{
-    application::{App, AppResult, EventContext, Game},
+    application::{AnalysisKind, App, AppResult, EventContext, Game, GameType},
     widgets::promotion_menu::PromotionMenu,
 };
 
@@ -21,7 +22,72 @@ impl BoardNavigationCommand {
 
 impl Command for BoardNavigationCommand {
     fn execute(&self, app: &mut App) -> AppResult<()> {
-        app.game.as_mut().map(|game| game.move_cursor(self.0));
+        if let Some(game) = app.game.as_mut() {
+            game.move_cursor(self.0);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct AnalysisCommand(AnalysisKind);
+
+impl AnalysisCommand {
+    pub(super) fn new(kind: AnalysisKind) -> Self {
+        Self(kind)
+    }
+}
+
+impl Command for AnalysisCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        if let Some(game) = app.game.as_mut() {
+            game.start_analysis(self.0);
+        }
+        Ok(())
+    }
+}
+
+/// Turns the board around. Against the engine the board keeps facing the human's
+/// side, so the key does nothing there.
+#[derive(Debug, Clone)]
+pub(super) struct FlipBoardCommand;
+
+impl Command for FlipBoardCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        if let Some(game) = app
+            .game
+            .as_mut()
+            .filter(|game| !matches!(game.game_type, GameType::AgainstBot(_)))
+        {
+            game.view_state.flip();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum ReplayStep {
+    First,
+    Previous,
+    Next,
+    Last,
+}
+
+#[derive(Debug, Clone, new)]
+pub(super) struct ReplayCommand {
+    step: ReplayStep,
+}
+
+impl Command for ReplayCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        if let Some(game) = app.game.as_mut() {
+            match self.step {
+                ReplayStep::First => game.view_first(),
+                ReplayStep::Previous => game.view_previous(),
+                ReplayStep::Next => game.view_next(),
+                ReplayStep::Last => game.view_last(),
+            }
+        }
         Ok(())
     }
 }
@@ -30,7 +96,13 @@ pub(super) struct BoardEnterCommand;
 
 impl Command for BoardEnterCommand {
     fn execute(&self, app: &mut App) -> AppResult<()> {
-        if let Some(game) = app.game.as_mut() {
+        // Moving while browsing the history returns to the live position instead
+        if let Some(game) = app.game.as_mut().filter(|game| game.viewing.is_some()) {
+            game.view_last();
+            return Ok(());
+        }
+
+        if let Some(game) = app.game.as_mut().filter(|game| game.is_human_turn()) {
             match game.view_state.selected_position {
                 None => game.select_piece(),
                 Some(_) => self.handle_piece_or_move(app)?,
@@ -58,7 +130,7 @@ impl BoardEnterCommand {
                     self.open_promotion_menu(app, m)?;
                     app.event_context = EventContext::PromotionMenu;
                 } else {
-                    game.game_state.make_move(m);
+                    game.make_move(m);
                     game.view_state.currently_legal_moves.clear();
                     if game.game_state.is_game_over() {
                         app.event_context = EventContext::GameOver;
@@ -80,12 +152,58 @@ impl BoardEnterCommand {
     }
 
     fn open_promotion_menu(&self, app: &mut App, promotion_move: Move) -> AppResult<()> {
-        app.game.as_mut().map(|game| {
+        if let Some(game) = app.game.as_mut() {
             game.promotion_menu = Some(PromotionMenu::new(
                 game.game_state.current_player.color,
                 promotion_move,
-            ))
-        });
+                &app.theme,
+            ));
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use domain::game::{Color, GameState};
+    use engine::skill::SkillLevel;
+
+    use super::*;
+    use crate::application::EngineSettings;
+
+    fn app_with(game_type: GameType) -> App {
+        App {
+            game: Some(Game::new(GameState::new(), game_type, None, None)),
+            ..App::default()
+        }
+    }
+
+    fn flipped(app: &App) -> bool {
+        app.game.as_ref().unwrap().view_state.flipped
+    }
+
+    #[test]
+    fn test_flip_turns_a_hot_seat_board() {
+        let mut app = app_with(GameType::Normal);
+
+        FlipBoardCommand.execute(&mut app).unwrap();
+        assert!(flipped(&app));
+        FlipBoardCommand.execute(&mut app).unwrap();
+        assert!(!flipped(&app));
+    }
+
+    #[test]
+    fn test_flip_is_ignored_against_the_engine() {
+        // The human's side starts at the bottom and stays there
+        for (engine_color, was_flipped) in [(Color::Black, false), (Color::White, true)] {
+            let mut app = app_with(GameType::AgainstBot(EngineSettings {
+                level: SkillLevel::default(),
+                engine_color,
+                time_control: None,
+            }));
+
+            FlipBoardCommand.execute(&mut app).unwrap();
+            assert_eq!(flipped(&app), was_flipped);
+        }
+    }
+}
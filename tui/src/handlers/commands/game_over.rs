@@ -0,0 +1,169 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, ErrorKind, Write},
+    path::{self, Path, PathBuf},
+};
+
+use derive_new::new;
+use domain::{
+    board::Direction,
+    game::{GameResult, Pgn},
+};
+
+use crate::{
+    application::{App, AppResult, GameType},
+    clipboard,
+    constants::GAME_PGN_PATH,
+    widgets::game_over::{GameOver, GameOverAction},
+};
+
+use super::{BackToMainMenuCommand, Command};
+
+fn game_over(app: &mut App) -> Option<&mut GameOver> {
+    app.game.as_mut()?.game_over.as_mut()
+}
+
+#[derive(Debug, Clone, new)]
+pub(super) struct GameOverNavigationCommand {
+    direction: Direction,
+}
+
+impl Command for GameOverNavigationCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        if let Some(game_over) = game_over(app) {
+            match self.direction {
+                Direction::North => game_over.previous(),
+                Direction::South => game_over.next(),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct GameOverEnterCommand;
+
+impl Command for GameOverEnterCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        match game_over(app).map(|game_over| game_over.selected_action()) {
+            Some(action) => GameOverActionCommand::new(action).execute(app),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ToggleGameOverCommand;
+
+impl Command for ToggleGameOverCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        if let Some(game_over) = game_over(app) {
+            game_over.hidden = !game_over.hidden;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, new)]
+pub(super) struct GameOverActionCommand {
+    action: GameOverAction,
+}
+
+impl Command for GameOverActionCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        let Some(game) = app.game.as_mut() else {
+            return Ok(());
+        };
+        let mut game_type = game.game_type.clone();
+
+        let status = match self.action {
+            GameOverAction::Rematch => {
+                app.start_game(game_type);
+                return Ok(());
+            }
+            // In hot-seat games the players just change seats
+            GameOverAction::SwapColours => {
+                if let GameType::AgainstBot(settings) = &mut game_type {
+                    settings.engine_color = settings.engine_color.opponent();
+                }
+                app.start_game(game_type);
+                return Ok(());
+            }
+            GameOverAction::Review => {
+                app.start_review();
+                return Ok(());
+            }
+            GameOverAction::MainMenu => return BackToMainMenuCommand.execute(app),
+            GameOverAction::ExportPgn => {
+                let result = game.game_state.result.as_ref().map(GameResult::score);
+                let pgn = Pgn::from_game(&game.game_state, vec![], result);
+                match save_new(Path::new(GAME_PGN_PATH), &pgn.to_string()) {
+                    Ok(path) => format!("Saved to {}", path.display()),
+                    Err(e) => format!("Could not save: {}", e),
+                }
+            }
+            GameOverAction::CopyFen => match clipboard::copy(&game.game_state.fen()) {
+                Ok(()) => "FEN copied to the clipboard".to_string(),
+                Err(e) => format!("Could not copy: {}", e),
+            },
+        };
+
+        if let Some(game_over) = game.game_over.as_mut() {
+            game_over.status = Some(status);
+        }
+        Ok(())
+    }
+}
+
+/// Writes `text` to `path`, or to `name-2.ext`, `name-3.ext` and so on next to it if the
+/// file already exists, so an earlier export is never overwritten. Returns the absolute
+/// path written.
+fn save_new(path: &Path, text: &str) -> io::Result<PathBuf> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    for n in 1.. {
+        let candidate = match n {
+            1 => path.to_path_buf(),
+            n => path.with_file_name(format!("{}-{}.{}", stem, n, extension)),
+        };
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return path::absolute(candidate);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("ran out of file numbers")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn test_save_new_keeps_earlier_files() {
+        let dir = env::temp_dir().join(format!("chess-tui-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.pgn");
+
+        let first = save_new(&path, "first").unwrap();
+        let second = save_new(&path, "second").unwrap();
+        let third = save_new(&path, "third").unwrap();
+
+        assert_eq!(first, path);
+        assert_eq!(second, dir.join("game-2.pgn"));
+        assert_eq!(third, dir.join("game-3.pgn"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "second");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,30 @@
+use derive_new::new;
+
+use crate::application::{App, AppResult};
+
+use super::Command;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum HelpScroll {
+    Lines(i32),
+    Pages(i32),
+    Top,
+    Bottom,
+}
+
+#[derive(Debug, Clone, new)]
+pub(super) struct HelpScrollCommand {
+    scroll: HelpScroll,
+}
+
+impl Command for HelpScrollCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        match self.scroll {
+            HelpScroll::Lines(lines) => app.help.scroll_by(lines),
+            HelpScroll::Pages(pages) => app.help.scroll_pages(pages),
+            HelpScroll::Top => app.help.scroll = 0,
+            HelpScroll::Bottom => app.help.scroll_to_end(),
+        }
+        Ok(())
+    }
+}
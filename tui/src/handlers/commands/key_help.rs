@@ -0,0 +1,13 @@
+use crate::application::{App, AppResult};
+
+use super::Command;
+
+/// Shows the keys of the current screen over it until the next key press.
+pub(super) struct KeyHelpCommand;
+
+impl Command for KeyHelpCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        app.show_key_help = true;
+        Ok(())
+    }
+}
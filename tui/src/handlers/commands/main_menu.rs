@@ -1,7 +1,7 @@
 use derive_new::new;
 use domain::board::Direction;
 
-use crate::application::{App, AppResult, GameType};
+use crate::application::{App, AppResult, GameType, MenuItem};
 
 use super::Command;
 
@@ -13,8 +13,8 @@ pub(super) struct MainMenuNavigationCommand {
 impl Command for MainMenuNavigationCommand {
     fn execute(&self, state: &mut App) -> AppResult<()> {
         match self.direction {
-            Direction::North => state.menu.next(),
-            Direction::South => state.menu.previous(),
+            Direction::North => state.menu.previous(),
+            Direction::South => state.menu.next(),
             _ => {}
         }
         Ok(())
@@ -26,7 +26,14 @@ pub(super) struct MainMenuEnterCommand;
 
 impl Command for MainMenuEnterCommand {
     fn execute(&self, state: &mut App) -> AppResult<()> {
-        state.start_game(GameType::default());
+        match state.menu.selected_item() {
+            MenuItem::HumanVsHuman => state.start_game(GameType::Normal),
+            MenuItem::HumanVsEngine => state.open_engine_setup(),
+            MenuItem::OnlineGame => {
+                state.menu.status = Some("Online play is not available yet".to_string())
+            }
+            MenuItem::Help => state.open_help(),
+        }
         Ok(())
     }
 }
@@ -1,15 +1,29 @@
 pub(super) mod back_to_main_menu;
 pub(super) mod command;
+pub(super) mod engine_setup;
 pub(super) mod game;
+pub(super) mod game_over;
+pub(super) mod help;
+pub(super) mod key_help;
 pub(super) mod main_menu;
+pub(super) mod mouse;
+pub(super) mod piece_set;
 pub(super) mod promotion_menu;
 pub(super) mod quit;
 pub mod registry;
+pub(super) mod review;
 
 use back_to_main_menu::*;
-use command::*;
+pub use command::*;
+use engine_setup::*;
 use game::*;
+use game_over::*;
+use help::*;
+use key_help::*;
 use main_menu::*;
+pub use mouse::*;
+use piece_set::*;
 use promotion_menu::*;
 use quit::*;
 pub use registry::*;
+use review::*;
@@ -0,0 +1,119 @@
+use derive_new::new;
+use ratatui::{
+    crossterm::event::{MouseButton, MouseEvent, MouseEventKind},
+    layout::Position as ScreenPosition,
+};
+
+use crate::application::{App, AppResult, CurrentScreen, EventContext};
+
+use super::{
+    BoardEnterCommand, Command, GameOverEnterCommand, MainMenuEnterCommand,
+    PromotionMenuEnterCommand,
+};
+
+/// Clicks act like moving the cursor there and pressing Enter. A piece is picked up
+/// when the button goes down and, when dragged, dropped where it is released.
+#[derive(Debug, Clone, new)]
+pub struct MouseCommand {
+    event: MouseEvent,
+}
+
+impl Command for MouseCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        let position = ScreenPosition::new(self.event.column, self.event.row);
+        match (self.event.kind, &app.current_screen) {
+            (MouseEventKind::Down(MouseButton::Left), CurrentScreen::MainMenu) => {
+                if let Some(item) = app.menu.item_at(position) {
+                    app.menu.selected = item;
+                    MainMenuEnterCommand.execute(app)?;
+                }
+            }
+            (MouseEventKind::Down(MouseButton::Left), CurrentScreen::Game) => {
+                self.press(app, position)?
+            }
+            (MouseEventKind::Drag(MouseButton::Left), CurrentScreen::Game) => {
+                if let Some(game) = app.game.as_mut().filter(|game| game.drag_start.is_some()) {
+                    if let Some(square) = game.square_at(position) {
+                        game.view_state.cursor_position = square;
+                    }
+                }
+            }
+            (MouseEventKind::Up(MouseButton::Left), CurrentScreen::Game) => {
+                self.release(app, position)?
+            }
+            (MouseEventKind::ScrollDown, CurrentScreen::Help) => app.help.scroll_by(3),
+            (MouseEventKind::ScrollUp, CurrentScreen::Help) => app.help.scroll_by(-3),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl MouseCommand {
+    fn press(&self, app: &mut App, position: ScreenPosition) -> AppResult<()> {
+        let Some(game) = app.game.as_mut() else {
+            return Ok(());
+        };
+
+        if app.event_context == EventContext::PromotionMenu {
+            if let Some(piece) = game.promotion_piece_at(position) {
+                if let Some(promotion_menu) = game.promotion_menu.as_mut() {
+                    promotion_menu.selected = piece;
+                }
+                PromotionMenuEnterCommand.execute(app)?;
+            }
+            return Ok(());
+        }
+        if app.event_context == EventContext::GameOver {
+            if let Some(action) = game.game_over_action_at(position) {
+                if let Some(game_over) = game.game_over.as_mut() {
+                    game_over.selected = action;
+                }
+                return GameOverEnterCommand.execute(app);
+            }
+        }
+        if game.click_history(position) {
+            return Ok(());
+        }
+
+        let Some(square) = game.square_at(position) else {
+            return Ok(());
+        };
+        match app.event_context {
+            EventContext::Game => {
+                game.view_state.cursor_position = square;
+                BoardEnterCommand.execute(app)?;
+            }
+            _ => game.view_state.cursor_position = square,
+        }
+
+        if let Some(game) = app.game.as_mut() {
+            game.drag_start = game
+                .view_state
+                .selected_position
+                .filter(|selected| *selected == square);
+        }
+        Ok(())
+    }
+
+    /// Dropping a dragged piece on another square tries to move it there.
+    fn release(&self, app: &mut App, position: ScreenPosition) -> AppResult<()> {
+        let Some(game) = app.game.as_mut() else {
+            return Ok(());
+        };
+        let Some(start) = game.drag_start.take() else {
+            return Ok(());
+        };
+
+        match game.square_at(position) {
+            Some(square) if square != start && app.event_context == EventContext::Game => {
+                game.view_state.cursor_position = square;
+                BoardEnterCommand.execute(app)
+            }
+            _ => {
+                game.view_state.cursor_position = start;
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::application::{App, AppResult};
+
+use super::Command;
+
+/// Switches to the next piece set, e.g. to figurines when the board gets small.
+pub(super) struct NextPieceSetCommand;
+
+impl Command for NextPieceSetCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        app.theme.piece_set = app.theme.piece_set.next();
+        Ok(())
+    }
+}
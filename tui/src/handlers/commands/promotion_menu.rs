@@ -12,15 +12,17 @@ pub(super) struct PromotionMenuNavigationCommand {
 
 impl Command for PromotionMenuNavigationCommand {
     fn execute(&self, app: &mut App) -> AppResult<()> {
-        app.game.as_mut().map(|game| {
-            game.promotion_menu.as_mut().map(|pm| {
-                match self.direction {
-                    Direction::East => pm.next(),
-                    Direction::West => pm.previous(),
-                    _ => {}
-                };
-            })
-        });
+        if let Some(pm) = app
+            .game
+            .as_mut()
+            .and_then(|game| game.promotion_menu.as_mut())
+        {
+            match self.direction {
+                Direction::East => pm.next(),
+                Direction::West => pm.previous(),
+                _ => {}
+            };
+        }
         Ok(())
     }
 }
@@ -30,20 +32,20 @@ pub(super) struct PromotionMenuEnterCommand;
 
 impl Command for PromotionMenuEnterCommand {
     fn execute(&self, app: &mut App) -> AppResult<()> {
-        app.game.as_mut().map(|game| {
-            game.promotion_menu.as_mut().map(|pm| {
+        if let Some(game) = app.game.as_mut() {
+            if let Some(pm) = game.promotion_menu.as_mut() {
                 let selected_piece_type = pm.pieces[pm.selected].clone().inner().piece_type;
                 let mut promotion_move = pm.m.clone();
                 promotion_move.move_type = MoveType::Promotion(selected_piece_type.into());
-                game.game_state.make_move(promotion_move);
-            });
+                game.make_move(promotion_move);
+            }
             game.promotion_menu = None;
             game.view_state.currently_legal_moves.clear();
             match game.game_state.is_game_over() {
                 true => app.event_context = EventContext::GameOver,
                 false => app.event_context = EventContext::Game,
             }
-        });
+        }
 
         Ok(())
     }
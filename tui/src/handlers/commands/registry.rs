@@ -3,15 +3,33 @@ use std::collections::HashMap;
 use domain::board::Direction;
 use ratatui::crossterm::event::{KeyCode, KeyEvent};
 
+use crate::{
+    application::AnalysisKind,
+    key_bindings::{chord_name, ContextBindings, UNBOUND},
+    widgets::game_over::GameOverAction,
+};
+
 use super::{
-    BackToMainMenuCommand, BoardEnterCommand, BoardNavigationCommand, Command, CommandBox,
-    MainMenuEnterCommand, MainMenuNavigationCommand, PromotionMenuEnterCommand,
-    PromotionMenuNavigationCommand, QuitCommand,
+    AnalysisCommand, BackToMainMenuCommand, BoardEnterCommand, BoardNavigationCommand, Command,
+    CommandBox, EngineSetupEnterCommand, EngineSetupNavigationCommand, FlipBoardCommand,
+    GameOverActionCommand, GameOverEnterCommand, GameOverNavigationCommand, HelpScroll,
+    HelpScrollCommand, KeyHelpCommand, MainMenuEnterCommand, MainMenuNavigationCommand,
+    NextPieceSetCommand, PromotionMenuEnterCommand, PromotionMenuNavigationCommand, QuitCommand,
+    ReplayCommand, ReplayStep, ReviewNavigationCommand, ReviewSaveCommand, StartReviewCommand,
+    ToggleGameOverCommand,
 };
 
+#[derive(Clone)]
+struct Binding {
+    name: &'static str,
+    command: CommandBox,
+}
+
 #[derive(Clone)]
 pub struct CommandRegistry {
-    commands: HashMap<KeyEvent, CommandBox>,
+    commands: HashMap<KeyEvent, Binding>,
+    /// Command names in the order they were registered, for listing them.
+    names: Vec<&'static str>,
 }
 
 impl CommandRegistry {
@@ -19,28 +37,79 @@ impl CommandRegistry {
         let mut registry = Self::new();
 
         registry.register(
+            "down",
             KeyEvent::from(KeyCode::Down),
             MainMenuNavigationCommand::new(Direction::South),
         );
 
         registry.register(
+            "down",
             KeyEvent::from(KeyCode::Char('j')),
             MainMenuNavigationCommand::new(Direction::South),
         );
 
         registry.register(
+            "up",
             KeyEvent::from(KeyCode::Up),
             MainMenuNavigationCommand::new(Direction::North),
         );
 
         registry.register(
+            "up",
             KeyEvent::from(KeyCode::Char('k')),
             MainMenuNavigationCommand::new(Direction::North),
         );
 
-        registry.register(KeyEvent::from(KeyCode::Enter), MainMenuEnterCommand);
+        registry.register(
+            "select",
+            KeyEvent::from(KeyCode::Enter),
+            MainMenuEnterCommand,
+        );
 
-        registry.register(KeyEvent::from(KeyCode::Char('q')), QuitCommand);
+        registry.register("quit", KeyEvent::from(KeyCode::Char('q')), QuitCommand);
+
+        registry.register_help();
+
+        registry
+    }
+
+    pub fn init_engine_setup_registry() -> Self {
+        let mut registry = Self::new();
+
+        for (name, keys, direction) in [
+            (
+                "down",
+                [KeyCode::Down, KeyCode::Char('j')],
+                Direction::South,
+            ),
+            ("up", [KeyCode::Up, KeyCode::Char('k')], Direction::North),
+            (
+                "right",
+                [KeyCode::Right, KeyCode::Char('l')],
+                Direction::East,
+            ),
+            ("left", [KeyCode::Left, KeyCode::Char('h')], Direction::West),
+        ] {
+            for key in keys {
+                registry.register(
+                    name,
+                    KeyEvent::from(key),
+                    EngineSetupNavigationCommand::new(direction),
+                );
+            }
+        }
+
+        registry.register(
+            "start",
+            KeyEvent::from(KeyCode::Enter),
+            EngineSetupEnterCommand,
+        );
+
+        registry.register("back", KeyEvent::from(KeyCode::Esc), BackToMainMenuCommand);
+
+        registry.register("quit", KeyEvent::from(KeyCode::Char('q')), QuitCommand);
+
+        registry.register_help();
 
         registry
     }
@@ -49,15 +118,23 @@ impl CommandRegistry {
         let mut registry = Self::new();
 
         registry.register(
+            "left",
             KeyEvent::from(KeyCode::Left),
             PromotionMenuNavigationCommand::new(Direction::West),
         );
         registry.register(
+            "right",
             KeyEvent::from(KeyCode::Right),
             PromotionMenuNavigationCommand::new(Direction::East),
         );
 
-        registry.register(KeyEvent::from(KeyCode::Enter), PromotionMenuEnterCommand);
+        registry.register(
+            "promote",
+            KeyEvent::from(KeyCode::Enter),
+            PromotionMenuEnterCommand,
+        );
+
+        registry.register_help();
 
         registry
     }
@@ -65,49 +142,288 @@ impl CommandRegistry {
     pub fn init_game_over_registry() -> Self {
         let mut registry = Self::new();
 
-        registry.register(KeyEvent::from(KeyCode::Enter), BackToMainMenuCommand);
+        registry.register(
+            "previous-action",
+            KeyEvent::from(KeyCode::Up),
+            GameOverNavigationCommand::new(Direction::North),
+        );
+
+        registry.register(
+            "previous-action",
+            KeyEvent::from(KeyCode::Char('k')),
+            GameOverNavigationCommand::new(Direction::North),
+        );
+
+        registry.register(
+            "next-action",
+            KeyEvent::from(KeyCode::Down),
+            GameOverNavigationCommand::new(Direction::South),
+        );
+
+        registry.register(
+            "next-action",
+            KeyEvent::from(KeyCode::Char('j')),
+            GameOverNavigationCommand::new(Direction::South),
+        );
+
+        registry.register(
+            "select",
+            KeyEvent::from(KeyCode::Enter),
+            GameOverEnterCommand,
+        );
+
+        registry.register(
+            "rematch",
+            KeyEvent::from(KeyCode::Char('n')),
+            GameOverActionCommand::new(GameOverAction::Rematch),
+        );
+
+        registry.register(
+            "swap-colours",
+            KeyEvent::from(KeyCode::Char('s')),
+            GameOverActionCommand::new(GameOverAction::SwapColours),
+        );
+
+        registry.register(
+            "review",
+            KeyEvent::from(KeyCode::Char('r')),
+            StartReviewCommand,
+        );
+
+        registry.register(
+            "export-pgn",
+            KeyEvent::from(KeyCode::Char('e')),
+            GameOverActionCommand::new(GameOverAction::ExportPgn),
+        );
+
+        registry.register(
+            "copy-fen",
+            KeyEvent::from(KeyCode::Char('c')),
+            GameOverActionCommand::new(GameOverAction::CopyFen),
+        );
+
+        registry.register(
+            "main-menu",
+            KeyEvent::from(KeyCode::Esc),
+            BackToMainMenuCommand,
+        );
+
+        registry.register(
+            "toggle-result",
+            KeyEvent::from(KeyCode::Char('h')),
+            ToggleGameOverCommand,
+        );
+
+        registry.register("flip", KeyEvent::from(KeyCode::Char('f')), FlipBoardCommand);
+
+        registry.register(
+            "piece-set",
+            KeyEvent::from(KeyCode::Char('p')),
+            NextPieceSetCommand,
+        );
+
+        registry.register_replay();
+
+        registry.register_help();
 
         registry
     }
 
-    pub fn init_game_registry() -> Self {
+    pub fn init_review_registry() -> Self {
         let mut registry = Self::new();
 
         registry.register(
+            "next-move",
+            KeyEvent::from(KeyCode::Down),
+            ReviewNavigationCommand::new(Direction::South),
+        );
+
+        registry.register(
+            "next-move",
+            KeyEvent::from(KeyCode::Char('j')),
+            ReviewNavigationCommand::new(Direction::South),
+        );
+
+        registry.register(
+            "previous-move",
             KeyEvent::from(KeyCode::Up),
-            BoardNavigationCommand::new(Direction::North),
+            ReviewNavigationCommand::new(Direction::North),
         );
+
         registry.register(
+            "previous-move",
             KeyEvent::from(KeyCode::Char('k')),
-            BoardNavigationCommand::new(Direction::North),
+            ReviewNavigationCommand::new(Direction::North),
         );
+
         registry.register(
+            "save-pgn",
+            KeyEvent::from(KeyCode::Char('s')),
+            ReviewSaveCommand,
+        );
+
+        registry.register(
+            "piece-set",
+            KeyEvent::from(KeyCode::Char('p')),
+            NextPieceSetCommand,
+        );
+
+        registry.register(
+            "main-menu",
+            KeyEvent::from(KeyCode::Esc),
+            BackToMainMenuCommand,
+        );
+
+        registry.register("quit", KeyEvent::from(KeyCode::Char('q')), QuitCommand);
+
+        registry.register_help();
+
+        registry
+    }
+
+    pub fn init_help_registry() -> Self {
+        let mut registry = Self::new();
+
+        registry.register(
+            "scroll-down",
             KeyEvent::from(KeyCode::Down),
-            BoardNavigationCommand::new(Direction::South),
+            HelpScrollCommand::new(HelpScroll::Lines(1)),
         );
+
         registry.register(
+            "scroll-down",
             KeyEvent::from(KeyCode::Char('j')),
-            BoardNavigationCommand::new(Direction::South),
+            HelpScrollCommand::new(HelpScroll::Lines(1)),
         );
+
         registry.register(
-            KeyEvent::from(KeyCode::Left),
-            BoardNavigationCommand::new(Direction::West),
+            "scroll-up",
+            KeyEvent::from(KeyCode::Up),
+            HelpScrollCommand::new(HelpScroll::Lines(-1)),
         );
+
         registry.register(
-            KeyEvent::from(KeyCode::Char('h')),
-            BoardNavigationCommand::new(Direction::West),
+            "scroll-up",
+            KeyEvent::from(KeyCode::Char('k')),
+            HelpScrollCommand::new(HelpScroll::Lines(-1)),
         );
+
         registry.register(
-            KeyEvent::from(KeyCode::Right),
-            BoardNavigationCommand::new(Direction::East),
+            "page-down",
+            KeyEvent::from(KeyCode::PageDown),
+            HelpScrollCommand::new(HelpScroll::Pages(1)),
+        );
+
+        registry.register(
+            "page-down",
+            KeyEvent::from(KeyCode::Char(' ')),
+            HelpScrollCommand::new(HelpScroll::Pages(1)),
+        );
+
+        registry.register(
+            "page-up",
+            KeyEvent::from(KeyCode::PageUp),
+            HelpScrollCommand::new(HelpScroll::Pages(-1)),
+        );
+
+        registry.register(
+            "page-up",
+            KeyEvent::from(KeyCode::Char('b')),
+            HelpScrollCommand::new(HelpScroll::Pages(-1)),
+        );
+
+        registry.register(
+            "top",
+            KeyEvent::from(KeyCode::Home),
+            HelpScrollCommand::new(HelpScroll::Top),
+        );
+
+        registry.register(
+            "top",
+            KeyEvent::from(KeyCode::Char('g')),
+            HelpScrollCommand::new(HelpScroll::Top),
+        );
+
+        registry.register(
+            "bottom",
+            KeyEvent::from(KeyCode::End),
+            HelpScrollCommand::new(HelpScroll::Bottom),
+        );
+
+        registry.register(
+            "bottom",
+            KeyEvent::from(KeyCode::Char('G')),
+            HelpScrollCommand::new(HelpScroll::Bottom),
         );
+
+        registry.register("back", KeyEvent::from(KeyCode::Esc), BackToMainMenuCommand);
+
+        registry.register("quit", KeyEvent::from(KeyCode::Char('q')), QuitCommand);
+
+        registry.register_help();
+
+        registry
+    }
+
+    pub fn init_game_registry() -> Self {
+        let mut registry = Self::new();
+
+        for (name, keys, direction) in [
+            (
+                "cursor-up",
+                [KeyCode::Up, KeyCode::Char('k')],
+                Direction::North,
+            ),
+            (
+                "cursor-down",
+                [KeyCode::Down, KeyCode::Char('j')],
+                Direction::South,
+            ),
+            (
+                "cursor-left",
+                [KeyCode::Left, KeyCode::Char('h')],
+                Direction::West,
+            ),
+            (
+                "cursor-right",
+                [KeyCode::Right, KeyCode::Char('l')],
+                Direction::East,
+            ),
+        ] {
+            for key in keys {
+                registry.register(
+                    name,
+                    KeyEvent::from(key),
+                    BoardNavigationCommand::new(direction),
+                );
+            }
+        }
+        registry.register("select", KeyEvent::from(KeyCode::Enter), BoardEnterCommand);
+
+        registry.register(
+            "hint",
+            KeyEvent::from(KeyCode::Char('i')),
+            AnalysisCommand::new(AnalysisKind::Hint),
+        );
+        registry.register(
+            "threat",
+            KeyEvent::from(KeyCode::Char('t')),
+            AnalysisCommand::new(AnalysisKind::Threat),
+        );
+
+        registry.register_replay();
+
+        registry.register("flip", KeyEvent::from(KeyCode::Char('f')), FlipBoardCommand);
+
         registry.register(
-            KeyEvent::from(KeyCode::Char('l')),
-            BoardNavigationCommand::new(Direction::East),
+            "piece-set",
+            KeyEvent::from(KeyCode::Char('p')),
+            NextPieceSetCommand,
         );
-        registry.register(KeyEvent::from(KeyCode::Enter), BoardEnterCommand);
 
-        registry.register(KeyEvent::from(KeyCode::Char('q')), QuitCommand);
+        registry.register("quit", KeyEvent::from(KeyCode::Char('q')), QuitCommand);
+
+        registry.register_help();
 
         registry
     }
@@ -117,14 +433,232 @@ impl CommandRegistry {
     fn new() -> Self {
         Self {
             commands: HashMap::new(),
+            names: vec![],
         }
     }
 
     pub fn get(&self, key_event: &KeyEvent) -> Option<&CommandBox> {
-        self.commands.get(key_event)
+        self.commands.get(key_event).map(|binding| &binding.command)
+    }
+
+    /// Binds the keys of a config file to commands of the registry by name, or unbinds
+    /// them. Returns the bindings that could not be applied and the commands left without
+    /// a key.
+    pub fn rebind(&mut self, bindings: &[(KeyEvent, String)]) -> Vec<String> {
+        let mut problems = vec![];
+        let before = self.commands.clone();
+
+        for (key, name) in bindings {
+            if name == UNBOUND {
+                self.commands.remove(key);
+                continue;
+            }
+            let binding = before
+                .values()
+                .find(|binding| binding.name == name)
+                .cloned();
+            match binding {
+                Some(binding) => {
+                    self.commands.insert(*key, binding);
+                }
+                None => problems.push(format!("Unknown command \"{}\"", name)),
+            }
+        }
+
+        // A command whose keys all went to other commands is out of reach
+        for name in &self.names {
+            let had_keys = before.iter().filter(|(_, binding)| binding.name == *name);
+            let unbound = |key: &KeyEvent| {
+                bindings
+                    .iter()
+                    .any(|(bound, command)| bound == key && command == UNBOUND)
+            };
+            let has_keys = self.commands.values().any(|binding| binding.name == *name);
+            if !has_keys && !had_keys.clone().all(|(key, _)| unbound(key)) {
+                problems.push(format!("\"{}\" has no key left", name));
+            }
+        }
+        problems
+    }
+
+    /// Every command with the keys bound to it, generated from the registry so that help
+    /// screens always show the keys in effect.
+    pub fn bindings(&self) -> ContextBindings {
+        self.names
+            .iter()
+            .map(|name| {
+                let mut keys: Vec<(String, bool)> = self
+                    .commands
+                    .iter()
+                    .filter(|(_, binding)| binding.name == *name)
+                    .map(|(key, _)| {
+                        let is_named = !matches!(key.code, KeyCode::Char(_));
+                        (chord_name(key), is_named)
+                    })
+                    .collect();
+                // Named keys like arrows first, then letters
+                keys.sort_by(|(a, a_named), (b, b_named)| b_named.cmp(a_named).then(a.cmp(b)));
+                (*name, keys.into_iter().map(|(key, _)| key).collect())
+            })
+            .collect()
+    }
+
+    /// Keys for browsing the history of a game, shared by the game and game over screens.
+    fn register_replay(&mut self) {
+        self.register(
+            "first-move",
+            KeyEvent::from(KeyCode::Home),
+            ReplayCommand::new(ReplayStep::First),
+        );
+        self.register(
+            "previous-move",
+            KeyEvent::from(KeyCode::Char('[')),
+            ReplayCommand::new(ReplayStep::Previous),
+        );
+        self.register(
+            "next-move",
+            KeyEvent::from(KeyCode::Char(']')),
+            ReplayCommand::new(ReplayStep::Next),
+        );
+        self.register(
+            "last-move",
+            KeyEvent::from(KeyCode::End),
+            ReplayCommand::new(ReplayStep::Last),
+        );
+    }
+
+    fn register_help(&mut self) {
+        self.register("help", KeyEvent::from(KeyCode::Char('?')), KeyHelpCommand);
+    }
+
+    fn register<T: Command + Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        key_event: KeyEvent,
+        command: T,
+    ) {
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        }
+        let command = CommandBox::new(command);
+        self.commands.insert(key_event, Binding { name, command });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyModifiers;
+
+    use super::*;
+    use crate::application::App;
+
+    fn command_at(registry: &CommandRegistry, key: KeyCode) -> Option<&'static str> {
+        registry
+            .commands
+            .get(&KeyEvent::from(key))
+            .map(|binding| binding.name)
+    }
+
+    #[test]
+    fn test_main_menu_down_selects_the_next_entry() {
+        let registry = CommandRegistry::init_main_menu_registry();
+        let mut app = App::default();
+
+        for key in [KeyCode::Down, KeyCode::Char('j')] {
+            assert_eq!(command_at(&registry, key), Some("down"));
+            app.menu.selected = 0;
+            let command = registry.get(&KeyEvent::from(key)).unwrap();
+            command.0.execute(&mut app).unwrap();
+            assert_eq!(app.menu.selected, 1);
+        }
+
+        for key in [KeyCode::Up, KeyCode::Char('k')] {
+            assert_eq!(command_at(&registry, key), Some("up"));
+            app.menu.selected = 1;
+            let command = registry.get(&KeyEvent::from(key)).unwrap();
+            command.0.execute(&mut app).unwrap();
+            assert_eq!(app.menu.selected, 0);
+        }
+    }
+
+    #[test]
+    fn test_rebind_adds_a_key() {
+        let mut registry = CommandRegistry::init_main_menu_registry();
+        let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+
+        let problems = registry.rebind(&[(ctrl_q, "quit".to_string())]);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(registry.commands.get(&ctrl_q).map(|b| b.name), Some("quit"));
+        assert_eq!(command_at(&registry, KeyCode::Char('q')), Some("quit"));
     }
 
-    fn register<T: Command + Send + Sync + 'static>(&mut self, key_event: KeyEvent, command: T) {
-        self.commands.insert(key_event, CommandBox::new(command));
+    #[test]
+    fn test_rebind_takes_a_default_key_from_another_command() {
+        let mut registry = CommandRegistry::init_main_menu_registry();
+
+        // "down" keeps its arrow key
+        let problems = registry.rebind(&[(KeyEvent::from(KeyCode::Char('j')), "up".to_string())]);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(command_at(&registry, KeyCode::Char('j')), Some("up"));
+        assert_eq!(command_at(&registry, KeyCode::Down), Some("down"));
+        let down = registry
+            .bindings()
+            .into_iter()
+            .find(|(name, _)| *name == "down");
+        assert_eq!(down, Some(("down", vec!["down".to_string()])));
+    }
+
+    #[test]
+    fn test_rebind_reports_a_command_left_without_keys() {
+        let mut registry = CommandRegistry::init_main_menu_registry();
+
+        let problems = registry.rebind(&[(KeyEvent::from(KeyCode::Enter), "quit".to_string())]);
+
+        assert_eq!(problems, ["\"select\" has no key left"]);
+        assert_eq!(command_at(&registry, KeyCode::Enter), Some("quit"));
+    }
+
+    #[test]
+    fn test_rebind_swapping_keys_leaves_no_command_behind() {
+        let mut registry = CommandRegistry::init_main_menu_registry();
+
+        // The commands are looked up as they were before the file applied
+        let problems = registry.rebind(&[
+            (KeyEvent::from(KeyCode::Enter), "quit".to_string()),
+            (KeyEvent::from(KeyCode::Char('q')), "select".to_string()),
+        ]);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(command_at(&registry, KeyCode::Enter), Some("quit"));
+        assert_eq!(command_at(&registry, KeyCode::Char('q')), Some("select"));
+    }
+
+    #[test]
+    fn test_rebind_unbinds_keys() {
+        let mut registry = CommandRegistry::init_main_menu_registry();
+
+        // Taking away every key on purpose is not a problem
+        let problems = registry.rebind(&[
+            (KeyEvent::from(KeyCode::Char('q')), UNBOUND.to_string()),
+            (KeyEvent::from(KeyCode::Char('k')), UNBOUND.to_string()),
+        ]);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(command_at(&registry, KeyCode::Char('q')), None);
+        assert_eq!(command_at(&registry, KeyCode::Char('k')), None);
+        assert_eq!(command_at(&registry, KeyCode::Up), Some("up"));
+    }
+
+    #[test]
+    fn test_rebind_reports_unknown_commands() {
+        let mut registry = CommandRegistry::init_main_menu_registry();
+
+        let problems =
+            registry.rebind(&[(KeyEvent::from(KeyCode::Char('x')), "teleport".to_string())]);
+
+        assert_eq!(problems, ["Unknown command \"teleport\""]);
+        assert_eq!(command_at(&registry, KeyCode::Char('x')), None);
     }
 }
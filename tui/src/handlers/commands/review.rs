@@ -0,0 +1,46 @@
+use derive_new::new;
+use domain::board::Direction;
+
+use crate::application::{App, AppResult};
+
+use super::Command;
+
+#[derive(Debug, Clone)]
+pub(super) struct StartReviewCommand;
+
+impl Command for StartReviewCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        app.start_review();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, new)]
+pub(super) struct ReviewNavigationCommand {
+    direction: Direction,
+}
+
+impl Command for ReviewNavigationCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        if let Some(review) = app.review.as_mut() {
+            match self.direction {
+                Direction::North => review.previous(),
+                Direction::South => review.next(),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ReviewSaveCommand;
+
+impl Command for ReviewSaveCommand {
+    fn execute(&self, app: &mut App) -> AppResult<()> {
+        if let Some(review) = app.review.as_mut() {
+            review.save_pgn();
+        }
+        Ok(())
+    }
+}
@@ -12,14 +12,16 @@ pub struct MainMenuHandler {
 }
 
 impl MainMenuHandler {
-    pub fn new() -> Self {
-        Self {
-            registry: CommandRegistry::init_main_menu_registry(),
-        }
+    pub fn new(registry: CommandRegistry) -> Self {
+        Self { registry }
     }
 }
 
 impl KeyEventHandler for MainMenuHandler {
+    fn registry(&self) -> &CommandRegistry {
+        &self.registry
+    }
+
     fn handle_key_event(&self, key_event: KeyEvent, state: &mut App) -> AppResult<()> {
         if let Some(command) = self.registry.get(&key_event).cloned() {
             command.0.execute(state)
@@ -1,17 +1,28 @@
+pub mod engine_setup;
 pub mod game;
 pub mod game_over;
+pub mod help;
 pub mod main_menu;
 pub mod promotion_menu;
+pub mod review;
 
+pub use engine_setup::*;
 pub use game::*;
 pub use game_over::*;
+pub use help::*;
 pub use main_menu::*;
 pub use promotion_menu::*;
+pub use review::*;
 
 use ratatui::crossterm::event::KeyEvent;
 
-use crate::application::{App, AppResult};
+use crate::{
+    application::{App, AppResult},
+    handlers::commands::CommandRegistry,
+};
 
 pub trait KeyEventHandler {
+    fn registry(&self) -> &CommandRegistry;
+
     fn handle_key_event(&self, key_event: KeyEvent, state: &mut App) -> AppResult<()>;
 }
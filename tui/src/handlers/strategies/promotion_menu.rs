@@ -12,14 +12,16 @@ pub struct PromotionMenuHandler {
 }
 
 impl PromotionMenuHandler {
-    pub fn new() -> Self {
-        Self {
-            registry: CommandRegistry::init_promotion_menu_registry(),
-        }
+    pub fn new(registry: CommandRegistry) -> Self {
+        Self { registry }
     }
 }
 
 impl KeyEventHandler for PromotionMenuHandler {
+    fn registry(&self) -> &CommandRegistry {
+        &self.registry
+    }
+
     fn handle_key_event(&self, key_event: KeyEvent, state: &mut App) -> AppResult<()> {
         if let Some(command) = self.registry.get(&key_event).cloned() {
             command.0.execute(state)
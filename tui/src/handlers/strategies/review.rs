@@ -0,0 +1,32 @@
+use ratatui::crossterm::event::KeyEvent;
+
+use crate::{
+    application::{App, AppResult},
+    handlers::commands::CommandRegistry,
+};
+
+use super::KeyEventHandler;
+
+pub struct ReviewHandler {
+    registry: CommandRegistry,
+}
+
+impl ReviewHandler {
+    pub fn new(registry: CommandRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+impl KeyEventHandler for ReviewHandler {
+    fn registry(&self) -> &CommandRegistry {
+        &self.registry
+    }
+
+    fn handle_key_event(&self, key_event: KeyEvent, state: &mut App) -> AppResult<()> {
+        if let Some(command) = self.registry.get(&key_event).cloned() {
+            command.0.execute(state)
+        } else {
+            Ok(())
+        }
+    }
+}
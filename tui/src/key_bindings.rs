@@ -0,0 +1,382 @@
+use std::{collections::HashMap, fs, io::ErrorKind, path::PathBuf};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    application::{AppResult, EventContext},
+    constants::{CONFIG_DIR, KEYS_FILE},
+};
+
+/// Bound to a key in the config file, removes the key's default binding.
+pub const UNBOUND: &str = "none";
+
+/// The commands of one context in the order they are listed, each with its keys.
+pub type ContextBindings = Vec<(&'static str, Vec<String>)>;
+
+/// Key bindings from `keys.toml` in the user's config directory. They are applied on top
+/// of the default bindings of each context.
+#[derive(Debug, Clone, Default)]
+pub struct KeyBindings {
+    contexts: HashMap<EventContext, Vec<(KeyEvent, String)>>,
+}
+
+impl KeyBindings {
+    /// The bindings from the config file, with the problems found in it. A missing file
+    /// leaves the defaults alone.
+    pub fn load() -> (Self, Vec<String>) {
+        let Some(path) = Self::path() else {
+            return (Self::default(), vec![]);
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).unwrap_or_else(|e| {
+                let problem = format!("{}: {}", path.display(), e);
+                (Self::default(), vec![problem])
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => (Self::default(), vec![]),
+            Err(e) => (Self::default(), vec![format!("{}: {}", path.display(), e)]),
+        }
+    }
+
+    /// Reads a key bindings file: a table per context mapping key chords to command names.
+    /// Chords and commands that are not understood are skipped and reported.
+    ///
+    /// ```toml
+    /// [game]
+    /// "ctrl-f" = "flip"
+    /// "f" = "none"
+    /// "shift-up" = "first-move"
+    /// ```
+    pub fn parse(text: &str) -> AppResult<(Self, Vec<String>)> {
+        let file: HashMap<String, toml::Table> = toml::from_str(text)?;
+        let mut bindings = Self::default();
+        let mut problems = vec![];
+
+        let mut tables: Vec<_> = file.into_iter().collect();
+        tables.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (name, table) in tables {
+            let Some(context) = EventContext::ALL
+                .into_iter()
+                .find(|context| context.name() == name)
+            else {
+                problems.push(format!("Unknown context [{}]", name));
+                continue;
+            };
+
+            let keys = bindings.contexts.entry(context).or_default();
+            for (chord, command) in table {
+                let (Some(key), Some(command)) = (parse_chord(&chord), command.as_str()) else {
+                    problems.push(format!("Invalid binding \"{}\" in [{}]", chord, name));
+                    continue;
+                };
+                if keys.iter().any(|(bound, _)| *bound == key) {
+                    problems.push(format!("\"{}\" is bound twice in [{}]", chord, name));
+                    continue;
+                }
+                keys.push((key, command.to_string()));
+            }
+        }
+
+        Ok((bindings, problems))
+    }
+
+    /// The keys the file binds in `context` with their command names.
+    pub fn context(&self, context: EventContext) -> &[(KeyEvent, String)] {
+        self.contexts.get(&context).map_or(&[], Vec::as_slice)
+    }
+
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(CONFIG_DIR).join(KEYS_FILE))
+    }
+}
+
+/// Reads a chord like `ctrl-shift-x`, `alt-enter`, `K` or `f5`.
+pub fn parse_chord(text: &str) -> Option<KeyEvent> {
+    let (modifiers, key) = match text.strip_suffix("--") {
+        Some(modifiers) => (modifiers, "-"),
+        None if text == "-" => ("", "-"),
+        None => text.rsplit_once('-').unwrap_or(("", text)),
+    };
+
+    let mut parsed = KeyModifiers::NONE;
+    for modifier in modifiers.split('-').filter(|modifier| !modifier.is_empty()) {
+        parsed |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match key.to_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "insert" => KeyCode::Insert,
+            "delete" => KeyCode::Delete,
+            name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+        },
+    };
+
+    Some(KeyEvent::new(code, parsed))
+}
+
+/// The chord of `key` as `parse_chord` reads it.
+pub fn chord_name(key: &KeyEvent) -> String {
+    let code = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyCode::Char(c.to_ascii_uppercase())
+        }
+        code => code,
+    };
+    // Upper case letters already say shift
+    let is_letter = matches!(code, KeyCode::Char(c) if c.is_ascii_uppercase());
+
+    let mut name = String::new();
+    let modifiers = [
+        (KeyModifiers::CONTROL, "ctrl-"),
+        (KeyModifiers::ALT, "alt-"),
+        (KeyModifiers::SHIFT, "shift-"),
+    ];
+    for (modifier, prefix) in modifiers {
+        if key.modifiers.contains(modifier) && !(modifier == KeyModifiers::SHIFT && is_letter) {
+            name.push_str(prefix);
+        }
+    }
+
+    match code {
+        KeyCode::Char(' ') => name.push_str("space"),
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::F(n) => name.push_str(&format!("f{}", n)),
+        KeyCode::Esc => name.push_str("esc"),
+        KeyCode::PageUp => name.push_str("pageup"),
+        KeyCode::PageDown => name.push_str("pagedown"),
+        KeyCode::BackTab => name.push_str("backtab"),
+        code => name.push_str(&format!("{:?}", code).to_lowercase()),
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_chord_with_modifiers() {
+        assert_eq!(
+            parse_chord("ctrl-shift-x"),
+            Some(key(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ))
+        );
+        assert_eq!(
+            parse_chord("Control-Alt-f"),
+            Some(key(
+                KeyCode::Char('f'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ))
+        );
+        assert_eq!(
+            parse_chord("K"),
+            Some(key(KeyCode::Char('K'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_chord("-"),
+            Some(key(KeyCode::Char('-'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_chord("ctrl--"),
+            Some(key(KeyCode::Char('-'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn test_parse_chord_with_named_keys() {
+        assert_eq!(
+            parse_chord("alt-enter"),
+            Some(key(KeyCode::Enter, KeyModifiers::ALT))
+        );
+        assert_eq!(
+            parse_chord("Escape"),
+            Some(key(KeyCode::Esc, KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_chord("space"),
+            Some(key(KeyCode::Char(' '), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_chord("shift-pageup"),
+            Some(key(KeyCode::PageUp, KeyModifiers::SHIFT))
+        );
+        assert_eq!(
+            parse_chord("f12"),
+            Some(key(KeyCode::F(12), KeyModifiers::NONE))
+        );
+    }
+
+    #[test]
+    fn test_parse_chord_rejects_unknown_keys_and_modifiers() {
+        for chord in ["", "hyper-x", "ctrl-", "enterr", "fx", "f0x", "ctrl-shift"] {
+            assert_eq!(parse_chord(chord), None, "{}", chord);
+        }
+    }
+
+    #[test]
+    fn test_chord_name() {
+        assert_eq!(
+            chord_name(&key(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )),
+            "ctrl-alt-x"
+        );
+        // Shift is written as an upper case letter
+        assert_eq!(
+            chord_name(&key(KeyCode::Char('k'), KeyModifiers::SHIFT)),
+            "K"
+        );
+        assert_eq!(
+            chord_name(&key(KeyCode::Up, KeyModifiers::SHIFT)),
+            "shift-up"
+        );
+        assert_eq!(
+            chord_name(&key(KeyCode::Char(' '), KeyModifiers::CONTROL)),
+            "ctrl-space"
+        );
+        assert_eq!(chord_name(&key(KeyCode::F(5), KeyModifiers::NONE)), "f5");
+        assert_eq!(chord_name(&key(KeyCode::Esc, KeyModifiers::NONE)), "esc");
+        assert_eq!(
+            chord_name(&key(KeyCode::Enter, KeyModifiers::NONE)),
+            "enter"
+        );
+    }
+
+    #[test]
+    fn test_chord_name_reads_back() {
+        for chord in [
+            "ctrl-alt-x",
+            "K",
+            "shift-up",
+            "ctrl-space",
+            "f5",
+            "esc",
+            "enter",
+            "backtab",
+            "pagedown",
+            "-",
+        ] {
+            assert_eq!(chord_name(&parse_chord(chord).unwrap()), chord);
+        }
+    }
+
+    #[test]
+    fn test_parse_bindings_per_context() {
+        let (bindings, problems) = KeyBindings::parse(
+            r#"
+[game]
+"ctrl-f" = "flip"
+"f" = "none"
+
+[main_menu]
+"enter" = "select"
+"#,
+        )
+        .unwrap();
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        let mut game = bindings.context(EventContext::Game).to_vec();
+        game.sort_by_key(|(_, command)| command.clone());
+        assert_eq!(
+            game,
+            [
+                (
+                    key(KeyCode::Char('f'), KeyModifiers::CONTROL),
+                    "flip".to_string()
+                ),
+                (
+                    key(KeyCode::Char('f'), KeyModifiers::NONE),
+                    UNBOUND.to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            bindings.context(EventContext::MainMenu),
+            [(
+                key(KeyCode::Enter, KeyModifiers::NONE),
+                "select".to_string()
+            )]
+        );
+        assert!(bindings.context(EventContext::Review).is_empty());
+    }
+
+    #[test]
+    fn test_parse_reports_unknown_contexts_and_keys() {
+        let (bindings, problems) = KeyBindings::parse(
+            r#"
+[game]
+"hyper-x" = "flip"
+"x" = 3
+"ctrl-f" = "flip"
+
+[board]
+"f" = "flip"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            problems,
+            [
+                "Unknown context [board]",
+                "Invalid binding \"hyper-x\" in [game]",
+                "Invalid binding \"x\" in [game]",
+            ]
+        );
+        assert_eq!(bindings.context(EventContext::Game).len(), 1);
+    }
+
+    #[test]
+    fn test_parse_reports_a_key_bound_twice() {
+        let (bindings, problems) = KeyBindings::parse(
+            r#"
+[game]
+"control-f" = "flip"
+"ctrl-f" = "hint"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(problems, ["\"ctrl-f\" is bound twice in [game]"]);
+        assert_eq!(
+            bindings.context(EventContext::Game),
+            [(
+                key(KeyCode::Char('f'), KeyModifiers::CONTROL),
+                "flip".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_a_broken_file() {
+        assert!(KeyBindings::parse("[game").is_err());
+        assert!(KeyBindings::parse("game = 1").is_err());
+    }
+}
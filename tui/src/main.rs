@@ -1,12 +1,17 @@
 use application::{App, AppResult};
+use key_bindings::KeyBindings;
 use message_handler::{Message, MessageHandler};
 use ratatui::{backend::CrosstermBackend, Terminal};
+use theme::Theme;
 use tui::Tui;
 
 mod application;
+mod clipboard;
 mod constants;
 mod handlers;
+mod key_bindings;
 mod message_handler;
+mod theme;
 mod tui;
 mod widgets;
 
@@ -14,9 +19,20 @@ mod widgets;
 async fn main() -> AppResult<()> {
     let backend = CrosstermBackend::new(std::io::stderr());
     let terminal = Terminal::new(backend)?;
-    let message_handler = MessageHandler::new(250);
+    let (key_bindings, mut config_problems) = KeyBindings::load();
+    let message_handler = MessageHandler::new(250, &key_bindings);
+    config_problems.extend(message_handler.binding_problems.iter().cloned());
+    let (theme, theme_problems) = Theme::load();
+    config_problems.extend(theme_problems);
+    // Problems with the config files are shown right away
+    let mut app = App {
+        theme,
+        key_help: message_handler.key_help(),
+        show_key_help: !config_problems.is_empty(),
+        config_problems,
+        ..App::default()
+    };
     let mut tui = Tui::new(terminal, message_handler);
-    let mut app = App::default();
 
     tui.init()?;
 
@@ -28,7 +44,14 @@ async fn main() -> AppResult<()> {
             Message::KeyPress(key_event) => {
                 tui.message_handler.handle_key_events(key_event, &mut app)?
             }
-            _ => {}
+            Message::MousePress(mouse_event) => tui
+                .message_handler
+                .handle_mouse_event(mouse_event, &mut app)?,
+            // The next draw picks up the new size and repaints everything
+            Message::Resize(_, _) => tui.clear()?,
+            Message::Focus(focused) => app.set_focused(focused),
+            Message::Paste(text) => tui.message_handler.handle_paste(&text, &mut app),
+            Message::Quit => app.quit(),
         }
     }
 
@@ -5,32 +5,44 @@ use std::{
     time::{Duration, Instant},
 };
 
-use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind, MouseEvent};
+use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
 
 use crate::{
     application::{App, AppResult, EventContext},
-    handlers::strategies::{
-        GameHandler, GameOverHandler, KeyEventHandler, MainMenuHandler, PromotionMenuHandler,
+    handlers::{
+        commands::{Command, CommandRegistry, MouseCommand},
+        strategies::{
+            EngineSetupHandler, GameHandler, GameOverHandler, HelpHandler, KeyEventHandler,
+            MainMenuHandler, PromotionMenuHandler, ReviewHandler,
+        },
     },
+    key_bindings::{ContextBindings, KeyBindings},
 };
 
-#[derive(Debug, Clone, Copy)]
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
 pub enum Message {
     Tick,
     Quit,
     KeyPress(KeyEvent),
     MousePress(MouseEvent),
+    Resize(u16, u16),
+    Focus(bool),
+    Paste(String),
 }
 
+#[allow(dead_code)]
 pub struct MessageHandler {
     sender: mpsc::Sender<Message>,
     receiver: mpsc::Receiver<Message>,
     handler: thread::JoinHandle<()>,
     key_event_handlers: HashMap<EventContext, Box<dyn KeyEventHandler>>,
+    /// Key bindings from the config file that could not be applied.
+    pub binding_problems: Vec<String>,
 }
 
 impl MessageHandler {
-    pub fn new(tick_rate: u64) -> Self {
+    pub fn new(tick_rate: u64, key_bindings: &KeyBindings) -> Self {
         let tick_rate = Duration::from_millis(tick_rate);
         let (sender, receiver) = mpsc::channel();
         let handler = {
@@ -46,7 +58,12 @@ impl MessageHandler {
                         match event::read().expect("Unable to read event") {
                             Event::Key(e) => sender.send(Message::KeyPress(e)),
                             Event::Mouse(e) => sender.send(Message::MousePress(e)),
-                            _ => unimplemented!(),
+                            Event::Resize(width, height) => {
+                                sender.send(Message::Resize(width, height))
+                            }
+                            Event::FocusGained => sender.send(Message::Focus(true)),
+                            Event::FocusLost => sender.send(Message::Focus(false)),
+                            Event::Paste(text) => sender.send(Message::Paste(text)),
                         }
                         .expect("Failed to send terminal event")
                     }
@@ -61,28 +78,96 @@ impl MessageHandler {
             })
         };
 
+        // Default bindings with the config file's on top; what cannot be applied is
+        // reported at startup
+        let mut binding_problems = vec![];
+        let mut registry = |context: EventContext, init: fn() -> CommandRegistry| {
+            let mut registry = init();
+            binding_problems.extend(
+                registry
+                    .rebind(key_bindings.context(context))
+                    .into_iter()
+                    .map(|problem| format!("[{}] {}", context.name(), problem)),
+            );
+            registry
+        };
+
         let mut key_event_handlers: HashMap<EventContext, Box<dyn KeyEventHandler>> =
             HashMap::new();
 
         key_event_handlers.insert(
             EventContext::PromotionMenu,
-            Box::new(PromotionMenuHandler::new()),
+            Box::new(PromotionMenuHandler::new(registry(
+                EventContext::PromotionMenu,
+                CommandRegistry::init_promotion_menu_registry,
+            ))),
+        );
+
+        key_event_handlers.insert(
+            EventContext::Game,
+            Box::new(GameHandler::new(registry(
+                EventContext::Game,
+                CommandRegistry::init_game_registry,
+            ))),
+        );
+
+        key_event_handlers.insert(
+            EventContext::MainMenu,
+            Box::new(MainMenuHandler::new(registry(
+                EventContext::MainMenu,
+                CommandRegistry::init_main_menu_registry,
+            ))),
         );
 
-        key_event_handlers.insert(EventContext::Game, Box::new(GameHandler::new()));
+        key_event_handlers.insert(
+            EventContext::EngineSetup,
+            Box::new(EngineSetupHandler::new(registry(
+                EventContext::EngineSetup,
+                CommandRegistry::init_engine_setup_registry,
+            ))),
+        );
 
-        key_event_handlers.insert(EventContext::MainMenu, Box::new(MainMenuHandler::new()));
+        key_event_handlers.insert(
+            EventContext::GameOver,
+            Box::new(GameOverHandler::new(registry(
+                EventContext::GameOver,
+                CommandRegistry::init_game_over_registry,
+            ))),
+        );
 
-        key_event_handlers.insert(EventContext::GameOver, Box::new(GameOverHandler::new()));
+        key_event_handlers.insert(
+            EventContext::Review,
+            Box::new(ReviewHandler::new(registry(
+                EventContext::Review,
+                CommandRegistry::init_review_registry,
+            ))),
+        );
+
+        key_event_handlers.insert(
+            EventContext::Help,
+            Box::new(HelpHandler::new(registry(
+                EventContext::Help,
+                CommandRegistry::init_help_registry,
+            ))),
+        );
 
         Self {
             sender,
             receiver,
             handler,
             key_event_handlers,
+            binding_problems,
         }
     }
 
+    /// The keys of every context as the registries have them.
+    pub fn key_help(&self) -> HashMap<EventContext, ContextBindings> {
+        self.key_event_handlers
+            .iter()
+            .map(|(context, handler)| (*context, handler.registry().bindings()))
+            .collect()
+    }
+
     pub fn next(&self) -> AppResult<Message> {
         Ok(self.receiver.recv()?)
     }
@@ -91,6 +176,11 @@ impl MessageHandler {
         if key_event.kind != KeyEventKind::Press {
             return Ok(());
         }
+        // Any key closes the help overlay
+        if app.show_key_help {
+            app.show_key_help = false;
+            return Ok(());
+        }
 
         if let Some(handler) = self.key_event_handlers.get(&app.event_context) {
             handler.handle_key_event(key_event, app)?;
@@ -98,4 +188,20 @@ impl MessageHandler {
 
         Ok(())
     }
+
+    /// A pasted FEN or PGN is loaded as a game. Pasted text never reaches the key
+    /// bindings, so it cannot run commands.
+    pub fn handle_paste(&self, text: &str, app: &mut App) {
+        app.load_pasted(text);
+    }
+
+    pub fn handle_mouse_event(&self, mouse_event: MouseEvent, app: &mut App) -> AppResult<()> {
+        if app.show_key_help {
+            if let MouseEventKind::Down(_) = mouse_event.kind {
+                app.show_key_help = false;
+            }
+            return Ok(());
+        }
+        MouseCommand::new(mouse_event).execute(app)
+    }
 }
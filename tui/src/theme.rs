@@ -0,0 +1,493 @@
+use std::{fs, io::ErrorKind, path::PathBuf, str::FromStr};
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::{
+    application::AppResult,
+    constants::{BLACK, CONFIG_DIR, THEME_FILE, WHITE},
+};
+
+/// The colours the interface is drawn in: the board's squares, coordinates, pieces and
+/// highlights, the menus and dialogs, the move list and the review, plus the piece set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub light_square: Color,
+    pub dark_square: Color,
+    pub coordinates: Color,
+    pub white_piece: Color,
+    pub black_piece: Color,
+    pub cursor: Color,
+    pub selected: Color,
+    pub legal_move: Color,
+    pub legal_capture: Color,
+    pub last_move: Color,
+    pub check: Color,
+    pub hint: Color,
+    pub threat: Color,
+    /// Text of the menus.
+    pub text: Color,
+    /// The selected menu entry and headings.
+    pub accent: Color,
+    /// Background of the selected entry of a dialog.
+    pub selection: Color,
+    pub selection_text: Color,
+    pub dialog_background: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// Checks and captures in the move list.
+    pub check_move: Color,
+    pub capture_move: Color,
+    /// The classes of moves in a review.
+    pub best_move: Color,
+    pub good_move: Color,
+    pub inaccuracy: Color,
+    pub mistake: Color,
+    pub blunder: Color,
+    /// Moves of a review where the game turned.
+    pub critical_move: Color,
+    pub piece_set: PieceSet,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            light_square: WHITE,
+            dark_square: BLACK,
+            coordinates: Color::Gray,
+            white_piece: Color::White,
+            black_piece: Color::Black,
+            cursor: Color::Blue,
+            selected: Color::Cyan,
+            legal_move: Color::Magenta,
+            legal_capture: Color::LightMagenta,
+            last_move: Color::Rgb(170, 162, 58),
+            check: Color::Red,
+            hint: Color::Green,
+            threat: Color::LightRed,
+            text: Color::White,
+            accent: Color::Yellow,
+            selection: Color::Yellow,
+            selection_text: Color::Black,
+            dialog_background: Color::Black,
+            success: Color::LightGreen,
+            warning: Color::LightRed,
+            error: Color::Red,
+            check_move: Color::LightRed,
+            capture_move: Color::Yellow,
+            best_move: Color::Green,
+            good_move: Color::White,
+            inaccuracy: Color::Yellow,
+            mistake: Color::LightRed,
+            blunder: Color::Red,
+            critical_move: Color::Red,
+            piece_set: PieceSet::default(),
+        }
+    }
+}
+
+impl Theme {
+    /// The theme from `theme.toml` in the user's config directory, with the problem found
+    /// in it if it cannot be read. The default theme stands in for a missing or broken
+    /// file. Colours are reduced to what the terminal shows.
+    pub fn load() -> (Self, Vec<String>) {
+        let (theme, problems) = match Self::path() {
+            None => (Self::default(), vec![]),
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(text) => match Self::parse(&text) {
+                    Ok(theme) => (theme, vec![]),
+                    Err(e) => (Self::default(), vec![format!("{}: {}", path.display(), e)]),
+                },
+                Err(e) if e.kind() == ErrorKind::NotFound => (Self::default(), vec![]),
+                Err(e) => (Self::default(), vec![format!("{}: {}", path.display(), e)]),
+            },
+        };
+        (theme.adapt(ColorDepth::detect()), problems)
+    }
+
+    /// Reads a theme file. Every entry is optional and falls back to the default theme.
+    /// Colours are names like `light-red`, `#rrggbb` or a palette index.
+    ///
+    /// ```toml
+    /// piece_set = "figurines"
+    ///
+    /// [squares]
+    /// light = "#eeeed2"
+    /// dark = "#769656"
+    ///
+    /// [highlights]
+    /// last_move = "#baca44"
+    ///
+    /// [text]
+    /// coordinates = "dark-gray"
+    ///
+    /// [interface]
+    /// accent = "light-cyan"
+    ///
+    /// [review]
+    /// blunder = "magenta"
+    /// ```
+    pub fn parse(text: &str) -> AppResult<Self> {
+        let file: ThemeFile = toml::from_str(text)?;
+        let mut theme = Self::default();
+
+        let colors = [
+            (&mut theme.light_square, file.squares.light),
+            (&mut theme.dark_square, file.squares.dark),
+            (&mut theme.cursor, file.highlights.cursor),
+            (&mut theme.selected, file.highlights.selected),
+            (&mut theme.legal_move, file.highlights.legal_move),
+            (&mut theme.legal_capture, file.highlights.legal_capture),
+            (&mut theme.last_move, file.highlights.last_move),
+            (&mut theme.check, file.highlights.check),
+            (&mut theme.hint, file.highlights.hint),
+            (&mut theme.threat, file.highlights.threat),
+            (&mut theme.coordinates, file.text.coordinates),
+            (&mut theme.white_piece, file.text.white_piece),
+            (&mut theme.black_piece, file.text.black_piece),
+            (&mut theme.text, file.interface.text),
+            (&mut theme.accent, file.interface.accent),
+            (&mut theme.selection, file.interface.selection),
+            (&mut theme.selection_text, file.interface.selection_text),
+            (
+                &mut theme.dialog_background,
+                file.interface.dialog_background,
+            ),
+            (&mut theme.success, file.interface.success),
+            (&mut theme.warning, file.interface.warning),
+            (&mut theme.error, file.interface.error),
+            (&mut theme.check_move, file.moves.check),
+            (&mut theme.capture_move, file.moves.capture),
+            (&mut theme.best_move, file.review.best),
+            (&mut theme.good_move, file.review.good),
+            (&mut theme.inaccuracy, file.review.inaccuracy),
+            (&mut theme.mistake, file.review.mistake),
+            (&mut theme.blunder, file.review.blunder),
+            (&mut theme.critical_move, file.review.critical),
+        ];
+        for (slot, value) in colors {
+            if let Some(value) = value {
+                *slot =
+                    Color::from_str(&value).map_err(|_| format!("Invalid colour: {}", value))?;
+            }
+        }
+
+        if let Some(name) = file.piece_set {
+            theme.piece_set =
+                PieceSet::from_name(&name).ok_or(format!("Unknown piece set: {}", name))?;
+        }
+        Ok(theme)
+    }
+
+    /// The theme with every colour replaced by the closest one `depth` can show.
+    pub fn adapt(mut self, depth: ColorDepth) -> Self {
+        for color in [
+            &mut self.light_square,
+            &mut self.dark_square,
+            &mut self.coordinates,
+            &mut self.white_piece,
+            &mut self.black_piece,
+            &mut self.cursor,
+            &mut self.selected,
+            &mut self.legal_move,
+            &mut self.legal_capture,
+            &mut self.last_move,
+            &mut self.check,
+            &mut self.hint,
+            &mut self.threat,
+            &mut self.text,
+            &mut self.accent,
+            &mut self.selection,
+            &mut self.selection_text,
+            &mut self.dialog_background,
+            &mut self.success,
+            &mut self.warning,
+            &mut self.error,
+            &mut self.check_move,
+            &mut self.capture_move,
+            &mut self.best_move,
+            &mut self.good_move,
+            &mut self.inaccuracy,
+            &mut self.mistake,
+            &mut self.blunder,
+            &mut self.critical_move,
+        ] {
+            *color = depth.reduce(*color);
+        }
+        self
+    }
+
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(CONFIG_DIR).join(THEME_FILE))
+    }
+}
+
+/// How pieces are drawn on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PieceSet {
+    /// Block art spanning several lines, for large boards.
+    #[default]
+    Blocks,
+    /// Unicode chess figurines, e.g. ♔ and ♚.
+    Figurines,
+    /// FEN letters, upper case for white.
+    Letters,
+}
+
+impl PieceSet {
+    pub fn next(&self) -> Self {
+        match self {
+            PieceSet::Blocks => PieceSet::Figurines,
+            PieceSet::Figurines => PieceSet::Letters,
+            PieceSet::Letters => PieceSet::Blocks,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PieceSet::Blocks => "blocks",
+            PieceSet::Figurines => "figurines",
+            PieceSet::Letters => "letters",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [PieceSet::Blocks, PieceSet::Figurines, PieceSet::Letters]
+            .into_iter()
+            .find(|set| set.name() == name)
+    }
+}
+
+/// How many colours the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+/// The 16 ANSI colours with the RGB values xterm gives them.
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+impl ColorDepth {
+    /// Guesses the colour support from `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    pub fn reduce(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => {
+                let level = |value: u8| (u16::from(value) * 5 + 127) / 255;
+                Color::Indexed((16 + 36 * level(r) + 6 * level(g) + level(b)) as u8)
+            }
+            (ColorDepth::Ansi256, _) => color,
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi((r, g, b)),
+            (ColorDepth::Ansi16, Color::Indexed(index)) if index >= 16 => {
+                nearest_ansi(indexed_rgb(index))
+            }
+            (ColorDepth::Ansi16, _) => color,
+        }
+    }
+}
+
+fn nearest_ansi((r, g, b): (u8, u8, u8)) -> Color {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .into_iter()
+            .map(|(a, b)| (i32::from(a) - i32::from(b)).pow(2))
+            .sum::<i32>()
+    };
+    ANSI_16
+        .into_iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map_or(Color::Reset, |(color, _)| color)
+}
+
+/// The RGB value of a colour of the 256-colour palette beyond the first 16.
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    if index >= 232 {
+        let gray = 8 + (index - 232) * 10;
+        return (gray, gray, gray);
+    }
+
+    let cube = index - 16;
+    let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+    (level(cube / 36), level(cube / 6 % 6), level(cube % 6))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    piece_set: Option<String>,
+    squares: SquareColors,
+    highlights: HighlightColors,
+    text: TextColors,
+    interface: InterfaceColors,
+    moves: MoveColors,
+    review: ReviewColors,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SquareColors {
+    light: Option<String>,
+    dark: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct HighlightColors {
+    cursor: Option<String>,
+    selected: Option<String>,
+    legal_move: Option<String>,
+    legal_capture: Option<String>,
+    last_move: Option<String>,
+    check: Option<String>,
+    hint: Option<String>,
+    threat: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TextColors {
+    coordinates: Option<String>,
+    white_piece: Option<String>,
+    black_piece: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct InterfaceColors {
+    text: Option<String>,
+    accent: Option<String>,
+    selection: Option<String>,
+    selection_text: Option<String>,
+    dialog_background: Option<String>,
+    success: Option<String>,
+    warning: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct MoveColors {
+    check: Option<String>,
+    capture: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ReviewColors {
+    best: Option<String>,
+    good: Option<String>,
+    inaccuracy: Option<String>,
+    mistake: Option<String>,
+    blunder: Option<String>,
+    critical: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_overrides_only_the_given_entries() {
+        let theme = Theme::parse(
+            r##"
+piece_set = "letters"
+
+[squares]
+light = "#eeeed2"
+
+[interface]
+accent = "light-cyan"
+
+[review]
+blunder = "13"
+"##,
+        )
+        .unwrap();
+
+        assert_eq!(theme.light_square, Color::Rgb(0xee, 0xee, 0xd2));
+        assert_eq!(theme.accent, Color::LightCyan);
+        assert_eq!(theme.blunder, Color::Indexed(13));
+        assert_eq!(theme.piece_set, PieceSet::Letters);
+
+        let default = Theme::default();
+        assert_eq!(theme.dark_square, default.dark_square);
+        assert_eq!(theme.mistake, default.mistake);
+        assert_eq!(Theme::parse("").unwrap(), default);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_entries() {
+        assert!(Theme::parse("[squares]\nlight = \"not-a-colour\"").is_err());
+        assert!(Theme::parse("piece_set = \"runes\"").is_err());
+        assert!(Theme::parse("[squares]\nmiddle = \"red\"").is_err());
+        assert!(Theme::parse("[sounds]").is_err());
+        assert!(Theme::parse("[squares").is_err());
+    }
+
+    #[test]
+    fn test_reduce_to_256_colours() {
+        let depth = ColorDepth::Ansi256;
+        assert_eq!(depth.reduce(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(depth.reduce(Color::Rgb(0, 0, 0)), Color::Indexed(16));
+        assert_eq!(depth.reduce(Color::Rgb(255, 255, 255)), Color::Indexed(231));
+        assert_eq!(depth.reduce(Color::Yellow), Color::Yellow);
+        assert_eq!(depth.reduce(Color::Indexed(100)), Color::Indexed(100));
+    }
+
+    #[test]
+    fn test_reduce_to_16_colours() {
+        let depth = ColorDepth::Ansi16;
+        assert_eq!(depth.reduce(Color::Rgb(250, 5, 5)), Color::LightRed);
+        // 196 is pure red in the colour cube, 244 a middle gray
+        assert_eq!(depth.reduce(Color::Indexed(196)), Color::LightRed);
+        assert_eq!(depth.reduce(Color::Indexed(244)), Color::DarkGray);
+        assert_eq!(depth.reduce(Color::Indexed(5)), Color::Indexed(5));
+        assert_eq!(depth.reduce(Color::Blue), Color::Blue);
+
+        let rgb = Color::Rgb(1, 2, 3);
+        assert_eq!(ColorDepth::TrueColor.reduce(rgb), rgb);
+    }
+
+    #[test]
+    fn test_nearest_ansi() {
+        assert_eq!(nearest_ansi((0, 0, 0)), Color::Black);
+        assert_eq!(nearest_ansi((255, 255, 255)), Color::White);
+        assert_eq!(nearest_ansi((200, 200, 200)), Color::Gray);
+        assert_eq!(nearest_ansi((120, 130, 125)), Color::DarkGray);
+        assert_eq!(nearest_ansi((0, 0, 200)), Color::Blue);
+        assert_eq!(nearest_ansi((194, 178, 128)), Color::DarkGray);
+    }
+}
@@ -2,7 +2,10 @@ use ratatui::{
     backend::Backend,
     crossterm::{
         self,
-        event::{DisableMouseCapture, EnableMouseCapture},
+        event::{
+            DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+            EnableFocusChange, EnableMouseCapture,
+        },
         terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
     },
     Terminal,
@@ -32,7 +35,13 @@ impl<B: Backend> Tui<B> {
 
     pub fn init(&mut self) -> AppResult<()> {
         terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        crossterm::execute!(
+            io::stderr(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableBracketedPaste,
+            EnableFocusChange
+        )?;
 
         let panic_hook = take_hook();
         set_hook(Box::new(move |panic| {
@@ -53,6 +62,12 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    /// Forgets what is on screen, so the next draw repaints all of it.
+    pub fn clear(&mut self) -> AppResult<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     pub fn exit(mut self) -> AppResult<()> {
         Self::reset()?;
         self.terminal.show_cursor()?;
@@ -61,7 +76,13 @@ impl<B: Backend> Tui<B> {
 
     fn reset() -> AppResult<()> {
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        crossterm::execute!(
+            io::stderr(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste,
+            DisableFocusChange
+        )?;
         Ok(())
     }
 }
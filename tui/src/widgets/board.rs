@@ -1,27 +1,124 @@
-use domain::board::{Board as DomainBoard, Position};
+use domain::{
+    board::{Board as DomainBoard, Position},
+    game::{Color as PieceColor, Player},
+    moves::{Move, MoveType},
+    pieces::PieceType,
+};
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Stylize},
+    layout::Rect,
+    style::{Color, Style, Stylize},
     widgets::{Block, Widget},
 };
 
-use crate::{
-    application::ViewState,
-    constants::{BLACK, WHITE},
-};
+use crate::{application::ViewState, theme::Theme};
 
-use super::piece::Piece;
+use super::{
+    board_layout::{BoardLayout, COORDINATE_WIDTH},
+    piece::Piece,
+    TooSmall,
+};
 
-pub struct Board<'a>(&'a DomainBoard, &'a ViewState);
+pub struct Board<'a> {
+    board: &'a DomainBoard,
+    view_state: &'a ViewState,
+    theme: &'a Theme,
+    last_move: Option<&'a Move>,
+}
 
 impl<'a> Board<'a> {
-    pub fn new(board: &'a DomainBoard, view_state: &'a ViewState) -> Self {
-        Self(board, view_state)
+    pub fn new(board: &'a DomainBoard, view_state: &'a ViewState, theme: &'a Theme) -> Self {
+        Self {
+            board,
+            view_state,
+            theme,
+            last_move: None,
+        }
+    }
+
+    /// Tints the squares the move that led to the position was played from and to.
+    pub fn last_move(mut self, last_move: Option<&'a Move>) -> Self {
+        self.last_move = last_move;
+        self
+    }
+}
+
+impl Board<'_> {
+    /// Rank numbers left of the squares and file letters below them, as seen from the
+    /// side at the bottom.
+    fn render_coordinates(&self, layout: &BoardLayout, buf: &mut Buffer) {
+        let view_state = self.view_state;
+        let style = Style::default().fg(self.theme.coordinates);
+
+        for i in 0..8 {
+            let square = layout.square(i, 0);
+            let rank = 8 - view_state.square_at(i, 0).row;
+            let x = square.x - COORDINATE_WIDTH;
+            buf.set_string(x, square.y + square.height / 2, rank.to_string(), style);
+        }
+        for j in 0..8 {
+            let square = layout.square(7, j);
+            let file = (b'a' + view_state.square_at(0, j).column as u8) as char;
+            let x = square.x + square.width / 2;
+            buf.set_string(x, square.bottom(), file.to_string(), style);
+        }
+    }
+}
+
+impl Board<'_> {
+    /// The colour of `square`. Later highlights win over earlier ones, the cursor over all.
+    fn square_color(&self, square: Position, light: bool, checked_king: Option<Position>) -> Color {
+        let (theme, view_state) = (self.theme, self.view_state);
+        let mut color = match light {
+            true => theme.light_square,
+            false => theme.dark_square,
+        };
+        let touches = |m: &Move| m.from == square || m.to == square;
+
+        if self.last_move.is_some_and(touches) {
+            color = theme.last_move;
+        }
+        if checked_king == Some(square) {
+            color = theme.check;
+        }
+        if view_state.selected_position == Some(square) {
+            color = theme.selected;
+        }
+        if let Some(m) = view_state
+            .currently_legal_moves
+            .iter()
+            .find(|m| m.to == square)
+        {
+            let is_capture = m.move_type == MoveType::EnPassant || self.board[&square].is_some();
+            color = match is_capture {
+                true => theme.legal_capture,
+                false => theme.legal_move,
+            };
+        }
+        if view_state.hint.as_ref().is_some_and(touches) {
+            color = theme.hint;
+        }
+        if view_state.threat.as_ref().is_some_and(touches) {
+            color = theme.threat;
+        }
+        if view_state.cursor_position == square {
+            color = theme.cursor;
+        }
+        color
     }
 
-    pub fn inner(&self) -> (&DomainBoard, &ViewState) {
-        (self.0, self.1)
+    /// The square of a king in check, if either is.
+    fn checked_king(&self) -> Option<Position> {
+        [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .find(|color| self.board.is_in_check(Player::new(*color)))
+            .and_then(|color| {
+                self.board.piece_positions().into_iter().find(|position| {
+                    self.board[position].is_some_and(|piece| {
+                        piece.piece_color == color && matches!(piece.piece_type, PieceType::King(_))
+                    })
+                })
+            })
     }
 }
 
@@ -30,76 +127,26 @@ impl<'a> Widget for Board<'a> {
     where
         Self: Sized,
     {
-        let (board, view_state) = self.inner();
-        let width = area.width / 8;
-        let height = area.height / 8;
-        let border_height = area.height / 2 - (4 * height);
-        let border_width = area.width / 2 - (4 * width);
-
-        let columns = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Length(border_height),
-                    Constraint::Length(height),
-                    Constraint::Length(height),
-                    Constraint::Length(height),
-                    Constraint::Length(height),
-                    Constraint::Length(height),
-                    Constraint::Length(height),
-                    Constraint::Length(height),
-                    Constraint::Length(height),
-                    Constraint::Length(border_height),
-                ]
-                .as_ref(),
-            )
-            .split(area);
+        let Some(layout) = BoardLayout::fit(area) else {
+            TooSmall::new(self.theme).render(area, buf);
+            return;
+        };
 
-        board.fields.iter().enumerate().for_each(|(i, row)| {
-            let lines = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(
-                    [
-                        Constraint::Length(border_width),
-                        Constraint::Length(width),
-                        Constraint::Length(width),
-                        Constraint::Length(width),
-                        Constraint::Length(width),
-                        Constraint::Length(width),
-                        Constraint::Length(width),
-                        Constraint::Length(width),
-                        Constraint::Length(width),
-                        Constraint::Length(border_width),
-                    ]
-                    .as_ref(),
-                )
-                .split(columns[i + 1]);
-            row.iter().enumerate().for_each(|(j, c)| {
-                let mut cell_color: Color = if (i + j) % 2 == 0 { WHITE } else { BLACK };
+        if layout.coordinates {
+            self.render_coordinates(&layout, buf);
+        }
+        let checked_king = self.checked_king();
 
-                if let Some(selected) = view_state.selected_position {
-                    if selected == Position::from((i, j)) {
-                        cell_color = Color::Cyan;
-                    }
+        for i in 0..8 {
+            for j in 0..8 {
+                let square = self.view_state.square_at(i, j);
+                let cell_color = self.square_color(square, (i + j) % 2 == 0, checked_king);
+                let cell_area = layout.square(i, j);
+                Widget::render(Block::default().bg(cell_color), cell_area, buf);
+                if let Some(piece) = &self.board[&square] {
+                    Piece::new(piece, self.theme).render(cell_area, buf);
                 }
-
-                view_state.currently_legal_moves.iter().for_each(|pos| {
-                    if pos.to == Position::from((i, j)) {
-                        cell_color = Color::Magenta;
-                    }
-                });
-
-                if view_state.cursor_position == Position::from((i, j)) {
-                    cell_color = Color::Blue;
-                }
-
-                let cell = Block::default().bg(cell_color);
-                let square = lines[j + 1];
-                Widget::render(cell, square, buf);
-                if let Some(piece) = c {
-                    Piece::from(piece).render(square, buf);
-                }
-            })
-        });
+            }
+        }
     }
 }
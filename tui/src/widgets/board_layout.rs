@@ -0,0 +1,101 @@
+use ratatui::layout::{Position, Rect};
+
+/// Columns left of the board taken by the rank numbers.
+pub const COORDINATE_WIDTH: u16 = 2;
+
+/// Where the squares of a board go in an area. The squares are as large as the area
+/// allows and centred in it, with the coordinates around them when there is room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardLayout {
+    /// Top left corner of the square drawn in the first row and column.
+    pub x: u16,
+    pub y: u16,
+    pub square_width: u16,
+    pub square_height: u16,
+    pub coordinates: bool,
+}
+
+impl BoardLayout {
+    /// The layout of a board filling `area`, `None` if not even one cell per square fits.
+    pub fn fit(area: Rect) -> Option<Self> {
+        let with_coordinates = (
+            area.width.saturating_sub(2 * COORDINATE_WIDTH) / 8,
+            area.height.saturating_sub(2) / 8,
+        );
+        let (square_width, square_height, coordinates) = match with_coordinates {
+            (width, height) if width > 0 && height > 0 => (width, height, true),
+            _ => (area.width / 8, area.height / 8, false),
+        };
+        if square_width == 0 || square_height == 0 {
+            return None;
+        }
+
+        Some(Self {
+            x: area.x + (area.width - 8 * square_width) / 2,
+            y: area.y + (area.height - 8 * square_height) / 2,
+            square_width,
+            square_height,
+            coordinates,
+        })
+    }
+
+    /// The cells of the square drawn in `row` and `column` from the top left.
+    pub fn square(&self, row: usize, column: usize) -> Rect {
+        Rect::new(
+            self.x + column as u16 * self.square_width,
+            self.y + row as u16 * self.square_height,
+            self.square_width,
+            self.square_height,
+        )
+    }
+
+    /// Row and column from the top left of the square drawn at the terminal cell
+    /// `position`, if it is on the board.
+    pub fn square_under(&self, position: Position) -> Option<(usize, usize)> {
+        let column = position.x.checked_sub(self.x)? / self.square_width;
+        let row = position.y.checked_sub(self.y)? / self.square_height;
+        (row < 8 && column < 8).then_some((usize::from(row), usize::from(column)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_centres_the_squares_inside_the_coordinates() {
+        let layout = BoardLayout::fit(Rect::new(0, 0, 44, 26)).unwrap();
+        assert_eq!(
+            layout,
+            BoardLayout {
+                x: 2,
+                y: 1,
+                square_width: 5,
+                square_height: 3,
+                coordinates: true,
+            }
+        );
+        assert_eq!(BoardLayout::fit(Rect::new(0, 0, 7, 30)), None);
+    }
+
+    #[test]
+    fn test_square_under_the_corners() {
+        let layout = BoardLayout::fit(Rect::new(0, 0, 44, 26)).unwrap();
+        assert_eq!(layout.square_under(Position::new(2, 1)), Some((0, 0)));
+        assert_eq!(layout.square_under(Position::new(6, 3)), Some((0, 0)));
+        assert_eq!(layout.square_under(Position::new(7, 4)), Some((1, 1)));
+        assert_eq!(layout.square_under(Position::new(41, 24)), Some((7, 7)));
+    }
+
+    #[test]
+    fn test_square_under_the_coordinates_or_outside_is_none() {
+        let layout = BoardLayout::fit(Rect::new(0, 0, 44, 26)).unwrap();
+        // The rank numbers on the left and the file letters below
+        assert_eq!(layout.square_under(Position::new(1, 10)), None);
+        assert_eq!(layout.square_under(Position::new(20, 25)), None);
+        // The gap above the board and past its right edge
+        assert_eq!(layout.square_under(Position::new(20, 0)), None);
+        assert_eq!(layout.square_under(Position::new(42, 10)), None);
+        assert_eq!(layout.square_under(Position::new(100, 100)), None);
+    }
+}
@@ -1,13 +1,216 @@
-use domain::{game::GameResult, game::Player};
+use domain::game::{Color as PieceColor, EndReason, GameResult};
+use ratatui::{
+    layout::{Alignment, Position, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
 
+use crate::theme::Theme;
+
+/// Size of the dialog, enough for the longest reason and action.
+const WIDTH: u16 = 36;
+const HEIGHT: u16 = 13;
+/// Lines above the actions: the result, the score and a gap.
+const ACTIONS_TOP: u16 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOverAction {
+    Rematch,
+    SwapColours,
+    Review,
+    ExportPgn,
+    CopyFen,
+    MainMenu,
+}
+
+impl GameOverAction {
+    pub const ALL: [GameOverAction; 6] = [
+        GameOverAction::Rematch,
+        GameOverAction::SwapColours,
+        GameOverAction::Review,
+        GameOverAction::ExportPgn,
+        GameOverAction::CopyFen,
+        GameOverAction::MainMenu,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            GameOverAction::Rematch => "Rematch",
+            GameOverAction::SwapColours => "Rematch with colours swapped",
+            GameOverAction::Review => "Review with engine",
+            GameOverAction::ExportPgn => "Export PGN",
+            GameOverAction::CopyFen => "Copy FEN",
+            GameOverAction::MainMenu => "Back to menu",
+        }
+    }
+}
+
+/// The result of a finished game in a dialog over it, with what to do next.
+#[derive(Debug, Clone)]
 pub struct GameOver {
-    pub winner: Option<Player>,
+    pub result: GameResult,
+    pub selected: usize,
+    /// Whether the dialog is put away to look at the final position.
+    pub hidden: bool,
+    /// What the last action did, shown under the actions.
+    pub status: Option<String>,
 }
 
 impl From<GameResult> for GameOver {
     fn from(value: GameResult) -> Self {
         Self {
-            winner: value.winner,
+            result: value,
+            selected: 0,
+            hidden: false,
+            status: None,
+        }
+    }
+}
+
+impl GameOver {
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % GameOverAction::ALL.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(GameOverAction::ALL.len() - 1);
+    }
+
+    pub fn selected_action(&self) -> GameOverAction {
+        GameOverAction::ALL[self.selected]
+    }
+
+    /// The action drawn at the terminal cell `position` when the dialog is drawn over
+    /// `area`.
+    pub fn action_at(&self, area: Rect, position: Position) -> Option<usize> {
+        let inner = Self::block().inner(Self::dialog_area(area));
+        if !inner.contains(position) {
+            return None;
+        }
+        let row = position.y.checked_sub(inner.y + ACTIONS_TOP)?;
+        (usize::from(row) < GameOverAction::ALL.len()).then_some(usize::from(row))
+    }
+
+    /// Who won and how, e.g. "White wins by checkmate".
+    fn headline(&self) -> String {
+        let winner = match self.result.winner.map(|winner| winner.color) {
+            Some(PieceColor::White) => "White wins",
+            Some(PieceColor::Black) => "Black wins",
+            None => "Draw",
+        };
+        let reason = match self.result.end_reason {
+            EndReason::Checkmate => "by checkmate",
+            EndReason::Stalemate => "by stalemate",
+            EndReason::FiftyMoveRule => "by the fifty-move rule",
+            EndReason::InsufficientMaterial => "by insufficient material",
+            EndReason::ThreefoldRepetition => "by threefold repetition",
+            EndReason::Timeout => "on time",
+            EndReason::Resignation => "by resignation",
+        };
+        format!("{} {}", winner, reason)
+    }
+
+    fn block() -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Game over")
+            .title_alignment(Alignment::Center)
+    }
+
+    /// The dialog is centred in `area` and shrinks with it.
+    fn dialog_area(area: Rect) -> Rect {
+        let width = WIDTH.min(area.width);
+        let height = HEIGHT.min(area.height);
+        Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+impl GameOver {
+    pub fn render_self(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let area = Self::dialog_area(area);
+
+        let mut lines = vec![
+            Line::styled(
+                self.headline(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Line::from(self.result.score()),
+            Line::from(""),
+        ];
+        for (i, action) in GameOverAction::ALL.iter().enumerate() {
+            let style = match i == self.selected {
+                true => Style::default()
+                    .fg(theme.selection_text)
+                    .bg(theme.selection),
+                false => Style::default(),
+            };
+            lines.push(Line::styled(action.label(), style));
+        }
+        if let Some(status) = &self.status {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(
+                status.as_str(),
+                Style::default().fg(theme.success),
+            ));
+        }
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .block(Self::block()),
+            area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::game::EndReason;
+
+    use super::*;
+
+    fn game_over() -> GameOver {
+        GameOver::from(GameResult::draw(EndReason::Stalemate))
+    }
+
+    #[test]
+    fn test_action_at_each_row() {
+        // The dialog sits at (2, 3), its actions start on row 7
+        let area = Rect::new(0, 0, 40, 20);
+        for (i, _) in GameOverAction::ALL.iter().enumerate() {
+            let position = Position::new(20, 7 + i as u16);
+            assert_eq!(game_over().action_at(area, position), Some(i));
         }
     }
+
+    #[test]
+    fn test_action_at_the_result_border_or_outside_is_none() {
+        let area = Rect::new(0, 0, 40, 20);
+        // The headline, the row after the last action and the borders
+        assert_eq!(game_over().action_at(area, Position::new(20, 4)), None);
+        assert_eq!(game_over().action_at(area, Position::new(20, 13)), None);
+        assert_eq!(game_over().action_at(area, Position::new(2, 8)), None);
+        assert_eq!(game_over().action_at(area, Position::new(37, 8)), None);
+        // Beside the dialog
+        assert_eq!(game_over().action_at(area, Position::new(0, 8)), None);
+        assert_eq!(game_over().action_at(area, Position::new(39, 8)), None);
+    }
+
+    #[test]
+    fn test_action_at_follows_an_offset_area() {
+        let area = Rect::new(10, 5, 40, 20);
+        assert_eq!(game_over().action_at(area, Position::new(30, 12)), Some(0));
+        assert_eq!(game_over().action_at(area, Position::new(20, 7)), None);
+    }
 }
@@ -0,0 +1,76 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::theme::Theme;
+
+use super::centered_rect;
+
+/// Width of the command name column.
+const NAME_WIDTH: usize = 15;
+
+/// The keys of the current screen over it, with the problems found in the key bindings
+/// and theme files at startup above them.
+pub struct KeyHelp<'a> {
+    bindings: &'a [(&'static str, Vec<String>)],
+    problems: &'a [String],
+    theme: &'a Theme,
+}
+
+impl<'a> KeyHelp<'a> {
+    pub fn new(
+        bindings: &'a [(&'static str, Vec<String>)],
+        problems: &'a [String],
+        theme: &'a Theme,
+    ) -> Self {
+        Self {
+            bindings,
+            problems,
+            theme,
+        }
+    }
+}
+
+impl Widget for KeyHelp<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = centered_rect(60, 70, area);
+        let mut lines: Vec<Line> = self
+            .problems
+            .iter()
+            .map(|problem| Line::styled(problem.as_str(), Style::default().fg(self.theme.warning)))
+            .collect();
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+
+        for (name, keys) in self.bindings {
+            let keys = match keys.is_empty() {
+                true => "-".to_string(),
+                false => keys.join(", "),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:<width$}", name, width = NAME_WIDTH),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(keys),
+            ]));
+        }
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Keys")
+                    .title_bottom("Any key: close")
+                    .title_alignment(Alignment::Center),
+            )
+            .render(area, buf);
+    }
+}
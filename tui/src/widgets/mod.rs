@@ -1,10 +1,19 @@
 pub mod app_title;
 pub mod board;
+pub mod board_layout;
 mod centered_rect;
 pub mod game_over;
+pub mod key_help;
+pub mod move_history;
 pub mod piece;
 pub mod promotion_menu;
+pub mod tablebase;
+pub mod too_small;
 
 pub use app_title::*;
 pub use board::*;
 use centered_rect::*;
+pub use key_help::*;
+pub use move_history::*;
+pub use tablebase::*;
+pub use too_small::*;
@@ -0,0 +1,225 @@
+use domain::game::{Color as PieceColor, EndReason, GameResult, GameState};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::theme::Theme;
+
+/// Width of the move number column, e.g. ` 12. `.
+const NUMBER_WIDTH: u16 = 5;
+/// Width of one move, padding included.
+const MOVE_WIDTH: u16 = 8;
+
+/// The moves of a game as numbered pairs in SAN, scrolled to keep the current move in
+/// view.
+pub struct MoveHistory<'a> {
+    game_state: &'a GameState,
+    sans: &'a [String],
+    /// Index of the highlighted move, usually the last one played.
+    current: Option<usize>,
+    start: GameState,
+    theme: Option<&'a Theme>,
+}
+
+impl<'a> MoveHistory<'a> {
+    pub fn new(game_state: &'a GameState, sans: &'a [String], current: Option<usize>) -> Self {
+        let start = match &game_state.setup {
+            Some(fen) => GameState::from_fen(fen).unwrap_or_default(),
+            None => GameState::new(),
+        };
+        Self {
+            game_state,
+            sans,
+            current,
+            start,
+            theme: None,
+        }
+    }
+
+    /// Colours checks and captures with the theme instead of the default one.
+    pub fn theme(mut self, theme: &'a Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// The move drawn at the terminal cell `position` when the widget fills `area`.
+    pub fn ply_at(&self, area: Rect, position: Position) -> Option<usize> {
+        let inner = Self::block().inner(area);
+        if !inner.contains(position) {
+            return None;
+        }
+
+        let row = self.scroll(inner.height) + usize::from(position.y - inner.y);
+        let column = position.x - inner.x;
+        if column < NUMBER_WIDTH {
+            return None;
+        }
+        let half = usize::from(((column - NUMBER_WIDTH) / MOVE_WIDTH).min(1));
+        (row * 2 + half)
+            .checked_sub(self.offset())
+            .filter(|ply| *ply < self.sans.len())
+    }
+}
+
+impl Widget for MoveHistory<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let default = Theme::default();
+        let theme = self.theme.unwrap_or(&default);
+        let offset = self.offset();
+        let mut lines: Vec<Line> = vec![];
+        for (ply, san) in self.sans.iter().enumerate() {
+            let slot = ply + offset;
+            if ply == 0 || slot.is_multiple_of(2) {
+                let number = self.start.fullmove_number() + slot / 2;
+                let mut line = Line::from(format!("{:>3}. ", number));
+                if slot % 2 == 1 {
+                    line.push_span(Span::raw(format!("{:<8}", "...")));
+                }
+                lines.push(line);
+            }
+
+            let mut style = move_style(san, theme);
+            if self.current == Some(ply) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            if let Some(line) = lines.last_mut() {
+                line.push_span(Span::styled(format!("{:<7}", san), style));
+                line.push_span(Span::raw(" "));
+            }
+        }
+        if let Some(result) = &self.game_state.result {
+            lines.push(Line::styled(
+                result_text(result),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        let scroll = self.scroll(Self::block().inner(area).height);
+        Paragraph::new(lines)
+            .block(Self::block())
+            .scroll((scroll as u16, 0))
+            .render(area, buf);
+    }
+}
+
+impl MoveHistory<'_> {
+    fn block() -> Block<'static> {
+        Block::default().borders(Borders::ALL).title("Moves")
+    }
+
+    /// A game set up with black to move starts its first row with black's move.
+    fn offset(&self) -> usize {
+        usize::from(self.start.current_player.color == PieceColor::Black)
+    }
+
+    /// The first row shown, so that the current move is on screen. At the end of a
+    /// finished game the result stays in view too.
+    fn scroll(&self, height: u16) -> usize {
+        let is_last = self.current.is_some() && self.current == self.sans.len().checked_sub(1);
+        let row = match (&self.game_state.result, self.current) {
+            (Some(_), _) if is_last => (self.sans.len() + self.offset()).div_ceil(2),
+            (_, Some(ply)) => (ply + self.offset()) / 2,
+            (_, None) => 0,
+        };
+        (row + 1).saturating_sub(usize::from(height.max(1)))
+    }
+}
+
+/// Checks and captures stand out in the theme's colours.
+fn move_style(san: &str, theme: &Theme) -> Style {
+    if san.ends_with('#') || san.ends_with('+') {
+        Style::default()
+            .fg(theme.check_move)
+            .add_modifier(Modifier::BOLD)
+    } else if san.contains('x') {
+        Style::default().fg(theme.capture_move)
+    } else {
+        Style::default()
+    }
+}
+
+fn result_text(result: &GameResult) -> String {
+    let reason = match result.end_reason {
+        EndReason::Checkmate => "checkmate",
+        EndReason::Stalemate => "stalemate",
+        EndReason::FiftyMoveRule => "fifty-move rule",
+        EndReason::InsufficientMaterial => "insufficient material",
+        EndReason::ThreefoldRepetition => "threefold repetition",
+        EndReason::Timeout => "time",
+        EndReason::Resignation => "resignation",
+    };
+    format!("     {} ({})", result.score(), reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sans(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|san| san.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ply_at_finds_both_moves_of_a_row() {
+        let game_state = GameState::new();
+        let sans = sans(&["e4", "e5", "Nf3"]);
+        let history = MoveHistory::new(&game_state, &sans, None);
+        let area = Rect::new(0, 0, 30, 10);
+
+        assert_eq!(history.ply_at(area, Position::new(6, 1)), Some(0));
+        assert_eq!(history.ply_at(area, Position::new(13, 1)), Some(0));
+        assert_eq!(history.ply_at(area, Position::new(14, 1)), Some(1));
+        assert_eq!(history.ply_at(area, Position::new(28, 1)), Some(1));
+        assert_eq!(history.ply_at(area, Position::new(6, 2)), Some(2));
+    }
+
+    #[test]
+    fn test_ply_at_misses_numbers_borders_and_empty_slots() {
+        let game_state = GameState::new();
+        let sans = sans(&["e4", "e5", "Nf3"]);
+        let history = MoveHistory::new(&game_state, &sans, None);
+        let area = Rect::new(0, 0, 30, 10);
+
+        // The move number, the border and the slot after the last move
+        assert_eq!(history.ply_at(area, Position::new(3, 1)), None);
+        assert_eq!(history.ply_at(area, Position::new(0, 1)), None);
+        assert_eq!(history.ply_at(area, Position::new(6, 0)), None);
+        assert_eq!(history.ply_at(area, Position::new(6, 9)), None);
+        assert_eq!(history.ply_at(area, Position::new(14, 2)), None);
+        assert_eq!(history.ply_at(area, Position::new(6, 3)), None);
+        assert_eq!(history.ply_at(area, Position::new(40, 1)), None);
+    }
+
+    #[test]
+    fn test_ply_at_with_black_to_move_first() {
+        let mut game_state = GameState::new();
+        game_state.setup = Some("4k3/8/8/8/8/8/8/4K3 b - - 0 1".to_string());
+        let sans = sans(&["Kd7", "Kd2"]);
+        let history = MoveHistory::new(&game_state, &sans, None);
+        let area = Rect::new(0, 0, 30, 10);
+
+        // The first row starts with black's move, white's slot is empty
+        assert_eq!(history.ply_at(area, Position::new(6, 1)), None);
+        assert_eq!(history.ply_at(area, Position::new(14, 1)), Some(0));
+        assert_eq!(history.ply_at(area, Position::new(6, 2)), Some(1));
+    }
+
+    #[test]
+    fn test_ply_at_follows_the_scroll() {
+        let game_state = GameState::new();
+        let sans = sans(&["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6"]);
+        let history = MoveHistory::new(&game_state, &sans, Some(7));
+        // Two rows fit, the current move is on the last one
+        let area = Rect::new(0, 0, 30, 4);
+
+        assert_eq!(history.ply_at(area, Position::new(6, 1)), Some(4));
+        assert_eq!(history.ply_at(area, Position::new(14, 2)), Some(7));
+    }
+}
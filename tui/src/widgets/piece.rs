@@ -1,7 +1,7 @@
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
-    style::{Color, Style, Stylize},
+    style::{Color, Modifier, Style, Stylize},
     widgets::{Paragraph, Widget},
 };
 
@@ -10,20 +10,121 @@ use domain::{
     pieces::{Piece as DomainPiece, PieceType},
 };
 
-use crate::constants::{BISHOP, KING, KNIGHT, PAWN, QUEEN, ROOK};
+use crate::{
+    constants::{
+        BISHOP, KING, KNIGHT, MEDIUM_BISHOP, MEDIUM_KING, MEDIUM_KNIGHT, MEDIUM_PAWN, MEDIUM_QUEEN,
+        MEDIUM_ROOK, PAWN, QUEEN, ROOK,
+    },
+    theme::{PieceSet, Theme},
+};
 
+/// A piece drawn in the piece set and colours of a theme, as large as its square allows.
 #[derive(Debug, Clone)]
-pub struct Piece(DomainPiece);
-
-impl From<&DomainPiece> for Piece {
-    fn from(value: &DomainPiece) -> Self {
-        Self(*value)
-    }
+pub struct Piece {
+    piece: DomainPiece,
+    set: PieceSet,
+    color: Color,
 }
 
 impl Piece {
+    pub fn new(piece: &DomainPiece, theme: &Theme) -> Self {
+        let color = match piece.piece_color {
+            DomainColor::White => theme.white_piece,
+            DomainColor::Black => theme.black_piece,
+        };
+        Self {
+            piece: *piece,
+            set: theme.piece_set,
+            color,
+        }
+    }
+
     pub fn inner(self) -> DomainPiece {
-        self.0
+        self.piece
+    }
+
+    fn symbol(&self, style: PieceStyle) -> String {
+        let white = self.piece.piece_color == DomainColor::White;
+        let symbol = match (style, &self.piece.piece_type) {
+            (PieceStyle::Large, PieceType::Bishop(_)) => BISHOP,
+            (PieceStyle::Large, PieceType::King(_)) => KING,
+            (PieceStyle::Large, PieceType::Knight(_)) => KNIGHT,
+            (PieceStyle::Large, PieceType::Pawn(_)) => PAWN,
+            (PieceStyle::Large, PieceType::Queen(_)) => QUEEN,
+            (PieceStyle::Large, PieceType::Rook(_)) => ROOK,
+            (PieceStyle::Medium, PieceType::Bishop(_)) => MEDIUM_BISHOP,
+            (PieceStyle::Medium, PieceType::King(_)) => MEDIUM_KING,
+            (PieceStyle::Medium, PieceType::Knight(_)) => MEDIUM_KNIGHT,
+            (PieceStyle::Medium, PieceType::Pawn(_)) => MEDIUM_PAWN,
+            (PieceStyle::Medium, PieceType::Queen(_)) => MEDIUM_QUEEN,
+            (PieceStyle::Medium, PieceType::Rook(_)) => MEDIUM_ROOK,
+            (PieceStyle::Figurine, PieceType::King(_)) if white => "♔",
+            (PieceStyle::Figurine, PieceType::Queen(_)) if white => "♕",
+            (PieceStyle::Figurine, PieceType::Rook(_)) if white => "♖",
+            (PieceStyle::Figurine, PieceType::Bishop(_)) if white => "♗",
+            (PieceStyle::Figurine, PieceType::Knight(_)) if white => "♘",
+            (PieceStyle::Figurine, PieceType::Pawn(_)) if white => "♙",
+            (PieceStyle::Figurine, PieceType::King(_)) => "♚",
+            (PieceStyle::Figurine, PieceType::Queen(_)) => "♛",
+            (PieceStyle::Figurine, PieceType::Rook(_)) => "♜",
+            (PieceStyle::Figurine, PieceType::Bishop(_)) => "♝",
+            (PieceStyle::Figurine, PieceType::Knight(_)) => "♞",
+            (PieceStyle::Figurine, PieceType::Pawn(_)) => "♟",
+            (PieceStyle::Letter, PieceType::King(_)) => "K",
+            (PieceStyle::Letter, PieceType::Queen(_)) => "Q",
+            (PieceStyle::Letter, PieceType::Rook(_)) => "R",
+            (PieceStyle::Letter, PieceType::Bishop(_)) => "B",
+            (PieceStyle::Letter, PieceType::Knight(_)) => "N",
+            (PieceStyle::Letter, PieceType::Pawn(_)) => "P",
+        };
+
+        match (style, white) {
+            (PieceStyle::Letter, false) => symbol.to_lowercase(),
+            _ => symbol.to_string(),
+        }
+    }
+}
+
+/// How large a piece is drawn, from block art down to a single letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceStyle {
+    Large,
+    Medium,
+    Figurine,
+    Letter,
+}
+
+impl PieceStyle {
+    /// The largest style of `set` that fits a square of `width` by `height` cells.
+    pub fn fitting(set: PieceSet, width: u16, height: u16) -> Self {
+        let styles: &[PieceStyle] = match set {
+            PieceSet::Blocks => &[
+                PieceStyle::Large,
+                PieceStyle::Medium,
+                PieceStyle::Figurine,
+                PieceStyle::Letter,
+            ],
+            PieceSet::Figurines => &[PieceStyle::Figurine, PieceStyle::Letter],
+            PieceSet::Letters => &[PieceStyle::Letter],
+        };
+        styles
+            .iter()
+            .copied()
+            .find(|style| {
+                let (min_width, min_height) = style.min_size();
+                width >= min_width && height >= min_height
+            })
+            .unwrap_or(PieceStyle::Letter)
+    }
+
+    /// Width and height in cells a square needs for the style.
+    fn min_size(&self) -> (u16, u16) {
+        match self {
+            PieceStyle::Large => (10, 5),
+            PieceStyle::Medium => (6, 3),
+            PieceStyle::Figurine => (2, 1),
+            PieceStyle::Letter => (1, 1),
+        }
     }
 }
 
@@ -32,24 +133,17 @@ impl Widget for Piece {
     where
         Self: Sized,
     {
-        let piece = self.inner();
-        let color: Color = match &piece.piece_color {
-            DomainColor::Black => Color::Black,
-            DomainColor::White => Color::White,
-        };
-
-        let piece_str = match &piece.piece_type {
-            PieceType::Bishop(_) => BISHOP,
-            PieceType::King(_) => KING,
-            PieceType::Knight(_) => KNIGHT,
-            PieceType::Pawn(_) => PAWN,
-            PieceType::Queen(_) => QUEEN,
-            PieceType::Rook(_) => ROOK,
-        };
+        let style = PieceStyle::fitting(self.set, area.width, area.height);
+        let mut text = self.symbol(style);
+        // The piece sits in the middle of the square
+        let lines = text.trim_end().lines().count() as u16;
+        let padding = area.height.saturating_sub(lines) / 2;
+        text.insert_str(0, &"\n".repeat(usize::from(padding)));
 
-        let piece = Paragraph::new(piece_str)
+        let piece = Paragraph::new(text)
             .style(Style::default())
-            .fg(color)
+            .fg(self.color)
+            .add_modifier(Modifier::BOLD)
             .alignment(Alignment::Center);
 
         Widget::render(piece, area, buf);
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use domain::{
     game::Color as DomainColor,
     moves::Move,
@@ -5,11 +7,13 @@ use domain::{
 };
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Style},
-    widgets::{Block, Borders, StatefulWidget, Widget},
+    widgets::{Block, Borders, Widget},
 };
 
+use crate::theme::Theme;
+
 use super::{centered_rect, piece::Piece};
 
 #[derive(Debug, Clone)]
@@ -17,19 +21,32 @@ pub struct PromotionMenu {
     pub pieces: [Piece; 4],
     pub selected: usize,
     pub m: Move,
+    background: Color,
+    selection: Color,
 }
 
 impl PromotionMenu {
-    pub fn new(piece_color: DomainColor, m: Move) -> Self {
+    pub fn new(piece_color: DomainColor, m: Move, theme: &Theme) -> Self {
         Self {
             pieces: [
-                Piece::from(&DomainPiece::new(PieceType::Queen(Queen), piece_color)),
-                Piece::from(&DomainPiece::new(PieceType::Rook(Rook), piece_color)),
-                Piece::from(&DomainPiece::new(PieceType::Bishop(Bishop), piece_color)),
-                Piece::from(&DomainPiece::new(PieceType::Knight(Knight), piece_color)),
+                Piece::new(
+                    &DomainPiece::new(PieceType::Queen(Queen), piece_color),
+                    theme,
+                ),
+                Piece::new(&DomainPiece::new(PieceType::Rook(Rook), piece_color), theme),
+                Piece::new(
+                    &DomainPiece::new(PieceType::Bishop(Bishop), piece_color),
+                    theme,
+                ),
+                Piece::new(
+                    &DomainPiece::new(PieceType::Knight(Knight), piece_color),
+                    theme,
+                ),
             ],
             selected: 0,
             m,
+            background: theme.dialog_background,
+            selection: theme.selection,
         }
     }
 
@@ -37,6 +54,14 @@ impl PromotionMenu {
         self.selected = (self.selected + 1) % self.pieces.len();
     }
 
+    /// The piece drawn at the terminal cell `position` when the menu is drawn over
+    /// `area`.
+    pub fn piece_at(&self, area: Rect, position: Position) -> Option<usize> {
+        Self::piece_areas(centered_rect(45, 40, area))
+            .iter()
+            .position(|piece_area| piece_area.contains(position))
+    }
+
     pub fn previous(&mut self) {
         if self.selected > 0 {
             self.selected -= 1;
@@ -49,7 +74,7 @@ impl PromotionMenu {
 impl Widget for PromotionMenu {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let block = Block::default()
-            .style(Style::default().bg(Color::Black))
+            .style(Style::default().bg(self.background))
             .borders(Borders::ALL)
             .title("Promote")
             .title_alignment(Alignment::Center);
@@ -58,6 +83,27 @@ impl Widget for PromotionMenu {
 
         Widget::render(block, area, buf);
 
+        // Render each piece, highlighting the selected one
+        let piece_areas = Self::piece_areas(area);
+        for (i, piece) in self.pieces.iter().enumerate() {
+            let area = piece_areas[i];
+
+            // Apply highlighting if the piece is selected
+            if i == self.selected {
+                let selected_style = Style::default().bg(self.selection);
+                let selected_block = Block::default().style(selected_style);
+
+                Widget::render(selected_block, area, buf);
+            }
+
+            piece.clone().render(area, buf);
+        }
+    }
+}
+
+impl PromotionMenu {
+    /// The pieces go side by side across the middle third of the menu.
+    fn piece_areas(area: Rect) -> Rc<[Rect]> {
         let inner_area_vertical = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
@@ -70,7 +116,7 @@ impl Widget for PromotionMenu {
             )
             .split(area);
 
-        let inner_area_horizontal = Layout::default()
+        Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
                 [
@@ -81,21 +127,6 @@ impl Widget for PromotionMenu {
                 ]
                 .as_ref(),
             )
-            .split(inner_area_vertical[1]);
-
-        // Render each piece, highlighting the selected one
-        for (i, piece) in self.pieces.iter().enumerate() {
-            let area = inner_area_horizontal[i];
-
-            // Apply highlighting if the piece is selected
-            if i == self.selected {
-                let selected_style = Style::default().bg(Color::Yellow);
-                let selected_block = Block::default().style(selected_style);
-
-                Widget::render(selected_block, area, buf);
-            }
-
-            piece.clone().render(area, buf);
-        }
+            .split(inner_area_vertical[1])
     }
 }
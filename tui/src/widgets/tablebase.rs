@@ -0,0 +1,83 @@
+use derive_new::new;
+use domain::game::Color;
+use engine::tablebase::Wdl;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    widgets::{Paragraph, Widget},
+};
+
+/// The tablebase outcome of the current position, as probed for the side to move.
+#[derive(Debug, Clone, Copy, PartialEq, new)]
+pub struct TablebaseVerdict {
+    pub side_to_move: Color,
+    pub wdl: Wdl,
+    pub dtz: i32,
+}
+
+impl TablebaseVerdict {
+    pub fn text(&self) -> String {
+        let (winner, wdl) = match self.wdl {
+            Wdl::Win | Wdl::CursedWin => (self.side_to_move, self.wdl),
+            Wdl::Loss | Wdl::BlessedLoss => (self.side_to_move.opponent(), -self.wdl),
+            Wdl::Draw => return "Tablebase says: draw".to_string(),
+        };
+        let winner = match winner {
+            Color::White => "White",
+            Color::Black => "Black",
+        };
+
+        // The winner's moves until the win is converted by mate, a capture or a pawn move
+        match wdl {
+            Wdl::Win => format!(
+                "Tablebase says: {} wins in {}",
+                winner,
+                (self.dtz.abs() + 1) / 2
+            ),
+            _ => format!(
+                "Tablebase says: draw by the fifty-move rule ({} cannot win in time)",
+                winner
+            ),
+        }
+    }
+}
+
+pub struct TablebaseIndicator(TablebaseVerdict);
+
+impl TablebaseIndicator {
+    pub fn new(verdict: TablebaseVerdict) -> Self {
+        Self(verdict)
+    }
+}
+
+impl Widget for TablebaseIndicator {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        Paragraph::new(self.0.text())
+            .alignment(Alignment::Center)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_win_is_counted_in_the_winners_moves() {
+        let to_move_wins = TablebaseVerdict::new(Color::White, Wdl::Win, 3);
+        assert_eq!(to_move_wins.text(), "Tablebase says: White wins in 2");
+
+        let to_move_loses = TablebaseVerdict::new(Color::White, Wdl::Loss, -4);
+        assert_eq!(to_move_loses.text(), "Tablebase says: Black wins in 2");
+
+        let cursed = TablebaseVerdict::new(Color::Black, Wdl::CursedWin, 101);
+        assert!(cursed.text().contains("fifty-move rule"));
+        assert_eq!(
+            TablebaseVerdict::new(Color::Black, Wdl::Draw, 0).text(),
+            "Tablebase says: draw"
+        );
+    }
+}
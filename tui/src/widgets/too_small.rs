@@ -0,0 +1,49 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    constants::{MIN_HEIGHT, MIN_WIDTH},
+    theme::Theme,
+};
+
+/// Shown instead of a screen when the terminal is too small to draw it.
+pub struct TooSmall<'a> {
+    theme: &'a Theme,
+}
+
+impl<'a> TooSmall<'a> {
+    pub fn new(theme: &'a Theme) -> Self {
+        Self { theme }
+    }
+}
+
+impl Widget for TooSmall<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines = vec![
+            Line::styled(
+                "Terminal too small",
+                Style::default()
+                    .fg(self.theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Line::from(format!("{}x{}", area.width, area.height)),
+            Line::from(format!("Needs {}x{}", MIN_WIDTH, MIN_HEIGHT)),
+        ];
+        let top = area.height.saturating_sub(lines.len() as u16) / 2;
+        let area = Rect {
+            y: area.y + top,
+            height: area.height - top,
+            ..area
+        };
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}